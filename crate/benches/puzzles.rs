@@ -1,19 +1,49 @@
+//! Benchmarks puzzle generation.  To see what the `simd` feature buys, compare
+//! `cargo bench --bench puzzles` with `cargo bench --bench puzzles --features
+//! simd`; criterion reports the change from the previous run.
+
 use chrono::NaiveDate;
-use criterion::{criterion_group, criterion_main, Criterion};
-use luke_doku::{date::LogicalDate, gen::*};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use luke_doku::{core::{Grid, Set}, date::LogicalDate, gen::*, solve::ledger::Ledger};
+use std::str::FromStr;
 
 fn criterion_benchmark(c: &mut Criterion) {
   let date = NaiveDate::from_ymd_opt(1961, 9, 20).unwrap();
   let ds = daily_solution(&LogicalDate::from(date));
   c.bench_function("puzzles 30", |b| b.iter(|| gen_puzzles(&ds, std::hint::black_box(30))));
+
+  let grids: Vec<Grid> = CLUES.iter().map(|s| Grid::from_str(s).unwrap()).collect();
+  let mut group = c.benchmark_group("ledger");
+  group.throughput(Throughput::Elements(grids.len() as u64));
+  group.bench_function("apply_implications", |b| {
+    b.iter(|| apply_implications(std::hint::black_box(&grids)))
+  });
+  group.finish();
 }
 
 criterion_group!(benches, criterion_benchmark);
 criterion_main!(benches);
 
+/// Puzzles whose ledgers do a lot of propagation before needing to pivot.
+const CLUES: &[&str] = &[
+  ".9..74....2....6.375...........9..545.3.4.......58.....45....8....1.2.3.......92.",
+  ".6.5.4.3.1...9...8.........9...5...6.4.6.2.7.7...4...5.........4...8...1.5.2.3.4.",
+  "..9..87....65..3...............3..69.........23..7...............8..36....41..2..",
+  ".3....91.8.6.....2...8.4...5.2..7..........7.9..4.65.....7.3...3.8.....1.97...8..",
+];
+
 /// Generates N puzzles for the given day.
 fn gen_puzzles(ds: &DailySolution, count: i32) -> () {
   for i in 1..=count {
     let _ = ds.generate(i);
   }
 }
+
+/// Runs `apply_implications` on a fresh ledger for each of the given grids.
+fn apply_implications(grids: &[Grid]) -> usize {
+  grids
+    .iter()
+    .filter_map(|g| Ledger::new(g).ok())
+    .map(|mut ledger| ledger.apply_implications().map_or(0, |d| d.len() as usize))
+    .sum()
+}
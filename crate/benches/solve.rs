@@ -0,0 +1,39 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use luke_doku::{core::Grid, solve::*};
+use std::str::FromStr;
+
+/// Hard puzzles, and ones with many solutions, that make the solver pivot a
+/// lot.
+const PUZZLES: &[&str] = &[
+  "..9..87....65..3...............3..69.........23..7...............8..36....41..2..",
+  ".3....91.8.6.....2...8.4...5.2..7..........7.9..4.65.....7.3...3.8.....1.97...8..",
+  "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..",
+  "..............3.85..1.2.......5.7.....4...1...9.......5......73..2.1........4...9",
+  ".....6....59.....82....8....45........3........6..3.54...325..6..................",
+];
+
+fn criterion_benchmark(c: &mut Criterion) {
+  let puzzles: Vec<Grid> = PUZZLES.iter().map(|s| Grid::from_str(s).unwrap()).collect();
+  let mut group = c.benchmark_group("solve");
+  group.bench_function("DefaultHelper", |b| {
+    b.iter(|| solve_all(&puzzles, &mut DefaultHelper()))
+  });
+  group.bench_function("JczHelper", |b| {
+    b.iter(|| solve_all(&puzzles, &mut JczHelper()))
+  });
+  group.bench_function("UnitPivotHelper", |b| {
+    b.iter(|| solve_all(&puzzles, &mut UnitPivotHelper()))
+  });
+  group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
+
+/// Solves each of the given puzzles, stopping after 100 solutions.
+fn solve_all(puzzles: &[Grid], helper: &mut dyn SearchHelper) -> usize {
+  puzzles
+    .iter()
+    .map(|p| solve(std::hint::black_box(p), 100, helper).solutions.len())
+    .sum()
+}
@@ -0,0 +1,178 @@
+//! The `batch` subcommand: solves and evaluates a file full of puzzles.
+
+use std::collections::BTreeMap;
+use std::iter;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Instant;
+
+use serde::Serialize;
+
+use luke_doku::core::Grid;
+use luke_doku::evaluate::{evaluate_with_observer, Rating};
+use luke_doku::gen::{Puzzle, MAX_SOLUTIONS};
+use luke_doku::solve::{solve, DefaultHelper};
+
+use crate::input;
+use crate::output::{self, Format};
+use crate::CliError;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+  /// The file to read puzzles from, one per line; reads standard input if
+  /// omitted or `-`.  Blank lines and lines starting with `#` are skipped.
+  input: Option<String>,
+
+  /// The number of worker threads; defaults to the number of available
+  /// processors.
+  #[arg(short, long)]
+  threads: Option<usize>,
+
+  /// Includes how long each puzzle took, which makes the output vary from
+  /// run to run.
+  #[arg(long)]
+  timing: bool,
+}
+
+/// The result of processing one puzzle, as written to the output.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Record {
+  /// The 1-based line number the puzzle came from.
+  line: usize,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  clues: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  error: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  solutions: Option<usize>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  too_many_solutions: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  rating: Option<Rating>,
+  /// How many times the evaluator applied each kind of fact.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  facts: Option<BTreeMap<&'static str, usize>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  time_ms: Option<f64>,
+}
+
+pub fn run(args: Args, format: Format) -> Result<(), CliError> {
+  let text = input::read_text(args.input.as_deref())?;
+  let lines: Vec<(usize, &str)> = text
+    .lines()
+    .enumerate()
+    .map(|(i, line)| (i + 1, line.trim()))
+    .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+    .collect();
+  let threads = args
+    .threads
+    .unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get()))
+    .max(1);
+
+  let next = AtomicUsize::new(0);
+  let (sender, receiver) = mpsc::channel::<(usize, Record)>();
+  thread::scope(|scope| {
+    for _ in 0..threads {
+      let sender = sender.clone();
+      let next = &next;
+      let lines = &lines;
+      scope.spawn(move || loop {
+        let i = next.fetch_add(1, Ordering::Relaxed);
+        let Some(&(line, text)) = lines.get(i) else {
+          break;
+        };
+        let record = process(line, text, args.timing);
+        // The receiver only goes away if nobody is reading the output.
+        if sender.send((i, record)).is_err() {
+          break;
+        }
+      });
+    }
+    drop(sender);
+
+    // Write the records in input order, holding on to any that arrive early.
+    let mut pending = BTreeMap::new();
+    let mut written = 0;
+    let mut receiver = receiver.into_iter();
+    let in_order = iter::from_fn(move || loop {
+      if let Some(record) = pending.remove(&written) {
+        written += 1;
+        return Some(record);
+      }
+      let (i, record) = receiver.next()?;
+      pending.insert(i, record);
+    });
+    output::emit_lines(format, in_order, record_text)
+  })
+}
+
+/// Summarizes a record on a single line.
+fn record_text(r: &Record) -> String {
+  let mut text = format!("Line {}: ", r.line);
+  if let Some(error) = &r.error {
+    text += error;
+    return text;
+  }
+  if let Some(clues) = &r.clues {
+    text += &format!("{}: ", clues);
+  }
+  if let Some(solutions) = r.solutions {
+    text += &output::plural(solutions, "solution", "solutions");
+    if r.too_many_solutions == Some(true) {
+      text += " or more";
+    }
+  }
+  if let Some(rating) = &r.rating {
+    text += &format!(
+      ", {:?} ({})",
+      rating.complexity,
+      output::plural(rating.complexity as usize, "star", "stars")
+    );
+  }
+  if let Some(time_ms) = r.time_ms {
+    text += &format!(", {:.1} ms", time_ms);
+  }
+  text
+}
+
+/// Solves and evaluates a single puzzle.
+fn process(line: usize, text: &str, timing: bool) -> Record {
+  let start = Instant::now();
+  let mut record = Record {
+    line,
+    clues: None,
+    error: None,
+    solutions: None,
+    too_many_solutions: None,
+    rating: None,
+    facts: None,
+    time_ms: None,
+  };
+  let clues = match Grid::from_str(text) {
+    Ok(clues) => clues,
+    Err(e) => {
+      record.error = Some(e);
+      return record;
+    }
+  };
+  record.clues = Some(clues.to_flat_string());
+  let summary = solve(&clues, MAX_SOLUTIONS, &mut DefaultHelper());
+  record.solutions = Some(summary.solutions.len());
+  record.too_many_solutions = Some(summary.too_many_solutions);
+  if !summary.too_many_solutions {
+    if let Some(puzzle) = Puzzle::new(&clues, Some(summary.solutions)) {
+      let mut facts = BTreeMap::new();
+      record.rating = Some(evaluate_with_observer(&puzzle, |fact| {
+        *facts.entry(fact.type_name()).or_insert(0) += 1;
+      }));
+      record.facts = Some(facts);
+    }
+  }
+  if timing {
+    record.time_ms = Some(start.elapsed().as_secs_f64() * 1000.0);
+  }
+  record
+}
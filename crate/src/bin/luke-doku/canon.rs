@@ -0,0 +1,94 @@
+//! The `canon` subcommand: finds the lexicographically smallest grid in a
+//! solved grid's orbit, which identifies the orbit.
+
+use serde::Serialize;
+
+use luke_doku::core::{Grid, Loc, LocSet, Num, Set, SolvedGrid};
+use luke_doku::permute::GridPermutation;
+use luke_doku::solve::{ledger, solve, DefaultHelper, SearchHelper, SearcherFactory};
+
+use crate::input::{self, PUZZLE_HELP};
+use crate::output::{self, Format};
+use crate::CliError;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+  #[arg(help = PUZZLE_HELP)]
+  puzzle: Option<String>,
+
+  /// Instead of reading a grid, finds the smallest of all solved grids.
+  #[arg(long, conflicts_with = "puzzle")]
+  smallest: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Report {
+  solution: String,
+  canonical: String,
+  /// The number of permutations that turn the solution into the canonical
+  /// grid, which is the size of the grid's automorphism group.
+  automorphisms: usize,
+}
+
+pub fn run(args: Args, format: Format) -> Result<(), CliError> {
+  let solution = if args.smallest {
+    smallest_solution()
+  } else {
+    let grid = input::read_grid(args.puzzle.as_deref())?;
+    match grid.solved_grid() {
+      Some(solution) => solution,
+      None => unique_solution(&grid)?,
+    }
+  };
+  let (_, canonical, automorphisms) = GridPermutation::minimizing(&solution);
+  let report = Report {
+    solution: solution.to_string(),
+    canonical: canonical.to_string(),
+    automorphisms,
+  };
+  output::emit(format, &report, |_| {
+    format!(
+      "Canonical grid ({}-way symmetry):\n{:?}",
+      automorphisms, canonical
+    )
+  })
+}
+
+fn unique_solution(clues: &Grid) -> Result<SolvedGrid, CliError> {
+  let summary = solve(clues, 1, &mut DefaultHelper());
+  match summary.solutions[..] {
+    [solution] => Ok(solution),
+    [] => Err(CliError::NoResult(
+      "the puzzle has no solutions".to_string(),
+    )),
+    _ => Err(CliError::NoResult(
+      "the puzzle has more than one solution".to_string(),
+    )),
+  }
+}
+
+/// Finds the lexicographically minimal solved Sudoku grid.
+fn smallest_solution() -> SolvedGrid {
+  let mut helper = MinimizingSearchHelper;
+  let factory = SearcherFactory::new(&Grid::new());
+  let mut searcher = factory.new_searcher(&mut helper);
+  searcher.run(None);
+  searcher.found.expect("the empty grid has solutions")
+}
+
+/// A SearchHelper that always picks the lexicographically first available
+/// location, and leaves the numerals to try in that location in numerical
+/// order.
+struct MinimizingSearchHelper;
+
+impl SearchHelper for MinimizingSearchHelper {
+  fn choose_pivot_loc(&mut self, ledger: &ledger::Ledger, _doubles: &LocSet) -> Loc {
+    // The solver never calls this with an empty `unset`.
+    ledger.unset().smallest_item().unwrap()
+  }
+
+  fn order_pivot_nums(&mut self, _nums: &mut [Option<Num>]) {
+    // Do nothing: they're already ordered from least to greatest.
+  }
+}
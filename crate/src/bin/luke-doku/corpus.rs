@@ -0,0 +1,36 @@
+//! The `corpus` subcommand: regenerates the golden corpus of generated
+//! puzzles that the library's tests compare against.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use luke_doku::gen::corpus::{corpus_ids, corpus_record};
+
+use crate::CliError;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+  /// The file to write the corpus to; writes standard output if omitted or
+  /// `-`.  The checked-in corpus is `testdata/puzzle-corpus.jsonl`, relative
+  /// to the crate.
+  #[arg(short, long)]
+  output: Option<String>,
+}
+
+pub fn run(args: Args) -> Result<(), CliError> {
+  let (name, out): (&str, Box<dyn Write>) = match args.output.as_deref() {
+    None | Some("-") => ("stdout", Box::new(io::stdout().lock())),
+    Some(path) => {
+      let file = File::create(path).map_err(|e| CliError::Io(format!("{}: {}", path, e)))?;
+      (path, Box::new(file))
+    }
+  };
+  let mut out = BufWriter::new(out);
+  for id in corpus_ids() {
+    let record = corpus_record(id).map_err(|e| CliError::NoResult(e.to_string()))?;
+    writeln!(out, "{}", record.to_line()).map_err(|e| CliError::Io(format!("{}: {}", name, e)))?;
+  }
+  out
+    .flush()
+    .map_err(|e| CliError::Io(format!("{}: {}", name, e)))
+}
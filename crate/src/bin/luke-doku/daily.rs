@@ -0,0 +1,56 @@
+//! The `daily` subcommand: shows the solutions that each day's puzzles are
+//! generated from.
+
+use chrono::{Days, NaiveDate};
+use serde::Serialize;
+
+use luke_doku::date::LogicalDate;
+use luke_doku::gen::daily_solution;
+
+use crate::input;
+use crate::output::{self, Format};
+use crate::CliError;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+  /// The first date, formatted as YYYY-MM-DD.
+  #[arg(value_parser = input::parse_date)]
+  date: LogicalDate,
+
+  /// The number of days to show.
+  #[arg(short, long, default_value_t = 1)]
+  days: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Day {
+  date: String,
+  generator_version: i32,
+  solution: String,
+}
+
+pub fn run(args: Args, format: Format) -> Result<(), CliError> {
+  let report: Vec<Day> = dates(args.date, args.days)
+    .map(|date| {
+      let ds = daily_solution(&date);
+      Day {
+        date: date.to_string(),
+        generator_version: ds.generator_version,
+        solution: ds.solution.to_string(),
+      }
+    })
+    .collect();
+  output::emit(format, &report, |days| {
+    days
+      .iter()
+      .map(|d| format!("{} {}\n", d.date, d.solution))
+      .collect()
+  })
+}
+
+/// Iterates through `count` dates starting with `start`.
+pub fn dates(start: LogicalDate, count: u32) -> impl Iterator<Item = LogicalDate> {
+  let start: NaiveDate = start.into();
+  (0..count as u64).map_while(move |i| start.checked_add_days(Days::new(i)).map(LogicalDate::from))
+}
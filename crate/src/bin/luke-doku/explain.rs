@@ -0,0 +1,50 @@
+//! The `explain` subcommand: lists the deductions that solve a puzzle.
+
+use serde::Serialize;
+
+use luke_doku::deduce::Fact;
+use luke_doku::evaluate::{evaluate_with_observer, Rating};
+
+use crate::input::{self, PUZZLE_HELP};
+use crate::output::{self, Format};
+use crate::rate::make_puzzle;
+use crate::CliError;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+  #[arg(help = PUZZLE_HELP)]
+  puzzle: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Report {
+  clues: String,
+  rating: Rating,
+  /// The facts the evaluator applied, in order.
+  steps: Vec<Fact>,
+}
+
+pub fn run(args: Args, format: Format) -> Result<(), CliError> {
+  let clues = input::read_grid(args.puzzle.as_deref())?;
+  let puzzle = make_puzzle(&clues)?;
+  let mut steps = Vec::new();
+  let rating = evaluate_with_observer(&puzzle, |fact| steps.push(fact.clone()));
+  let report = Report {
+    clues: clues.to_flat_string(),
+    rating,
+    steps,
+  };
+  output::emit(format, &report, |r| {
+    let mut text = String::new();
+    for (i, fact) in r.steps.iter().enumerate() {
+      text += &format!("{:4}. {:?}\n", i + 1, fact);
+    }
+    text += &format!(
+      "\nComplexity: {:?}, after {}",
+      r.rating.complexity,
+      output::plural(r.steps.len(), "step", "steps")
+    );
+    text
+  })
+}
@@ -0,0 +1,64 @@
+//! The `generate` subcommand: generates one of a day's puzzles.
+
+use serde::Serialize;
+
+use luke_doku::gen::{generate_puzzle, PuzzleId, GENERATOR_VERSION};
+
+use crate::input;
+use crate::output::{self, Format};
+use crate::CliError;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+  /// The puzzle's ID, formatted as DATE:COUNTER:VERSION; or just its date,
+  /// formatted as YYYY-MM-DD, followed by COUNTER.
+  id: String,
+
+  /// The puzzle's counter, which starts at 1 for each date.
+  counter: Option<i32>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Report {
+  id: String,
+  clues: String,
+  solutions: Vec<String>,
+  sym: String,
+  broken: bool,
+  improper: bool,
+}
+
+pub fn run(args: Args, format: Format) -> Result<(), CliError> {
+  let id = match args.counter {
+    Some(counter) => {
+      let date = input::parse_date(&args.id).map_err(CliError::Input)?;
+      PuzzleId::new(date, counter, GENERATOR_VERSION)
+    }
+    None => args
+      .id
+      .parse::<PuzzleId>()
+      .map_err(|e| CliError::Input(e.to_string()))?,
+  };
+  let puzzle = generate_puzzle(id).map_err(|e| CliError::Input(e.to_string()))?;
+  let opts = puzzle.gen_opts.expect("generated puzzles have options");
+  let report = Report {
+    id: opts.id().to_string(),
+    clues: puzzle.clues.to_flat_string(),
+    solutions: puzzle.solutions.iter().map(|s| s.to_string()).collect(),
+    sym: format!("{:?}", opts.sym),
+    broken: opts.broken,
+    improper: opts.improper,
+  };
+  output::emit(format, &report, |r| {
+    format!(
+      "Puzzle {}: {} clues, {}\nSymmetry: {:?}{}\n\n{:?}",
+      r.id,
+      puzzle.clues.len(),
+      output::plural(r.solutions.len(), "solution", "solutions"),
+      opts.sym,
+      if r.broken { " (broken)" } else { "" },
+      puzzle.clues
+    )
+  })
+}
@@ -0,0 +1,58 @@
+//! The `hint` subcommand: finds a deduction to make next.
+
+use serde::Serialize;
+
+use luke_doku::deduce::{Fact, FactFinder};
+
+use crate::input::{self, PUZZLE_HELP};
+use crate::output::{self, Format};
+use crate::CliError;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+  #[arg(help = PUZZLE_HELP)]
+  puzzle: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Report {
+  grid: String,
+  /// Whether the hint points out a mistake rather than a step forward.
+  error: bool,
+  fact: Fact,
+}
+
+pub fn run(args: Args, format: Format) -> Result<(), CliError> {
+  let grid = input::read_grid(args.puzzle.as_deref())?;
+  if grid.len() == 81 {
+    return Err(CliError::NoResult("the grid is already full".to_string()));
+  }
+  let facts = FactFinder::new(&grid).deduce_all();
+  // Errors come first, since nothing else matters until they're fixed.  After
+  // that, prefer direct assignments, then other assignments, then anything.
+  let fact = facts
+    .iter()
+    .find(|f| f.is_error())
+    .or_else(|| {
+      facts
+        .iter()
+        .find(|f| matches!(f, Fact::SingleLoc { .. } | Fact::SingleNum { .. }))
+    })
+    .or_else(|| facts.iter().find(|f| f.is_asgmt()))
+    .or_else(|| facts.first())
+    .ok_or_else(|| CliError::NoResult("no deductions found".to_string()))?;
+  let report = Report {
+    grid: grid.to_flat_string(),
+    error: fact.is_error(),
+    fact: fact.clone(),
+  };
+  output::emit(format, &report, |r| {
+    format!(
+      "{}{}: {:?}",
+      if r.error { "Error, " } else { "" },
+      r.fact.type_name(),
+      r.fact
+    )
+  })
+}
@@ -0,0 +1,67 @@
+//! Parsing shared by the subcommands.
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use chrono::NaiveDate;
+
+use luke_doku::core::Grid;
+use luke_doku::date::LogicalDate;
+use luke_doku::formats;
+
+use crate::CliError;
+
+/// The help text for the PUZZLE argument that most subcommands take.
+pub const PUZZLE_HELP: &str = "The puzzle: 81 characters with digits for clues and `.` or `0` \
+                               for blanks (other characters are ignored), or any format that \
+                               luke_doku::formats reads; or the name of a file containing one, \
+                               or `-` to read standard input, which is also the default";
+
+/// Reads the text named by an input argument: the contents of the named file,
+/// or standard input if the argument is `-` or missing.
+pub fn read_text(arg: Option<&str>) -> Result<String, CliError> {
+  match arg {
+    None | Some("-") => {
+      let mut text = String::new();
+      io::stdin()
+        .read_to_string(&mut text)
+        .map_err(|e| CliError::Io(format!("stdin: {}", e)))?;
+      Ok(text)
+    }
+    Some(path) => fs::read_to_string(path).map_err(|e| match e.kind() {
+      io::ErrorKind::NotFound => CliError::Input(format!("{}: {}", path, e)),
+      _ => CliError::Io(format!("{}: {}", path, e)),
+    }),
+  }
+}
+
+/// Reads a grid from a PUZZLE argument, which can be the grid itself, the name
+/// of a file containing it, or `-` (or nothing) for standard input.  Includes
+/// any numerals the solver has placed, for formats that record them.
+pub fn read_grid(arg: Option<&str>) -> Result<Grid, CliError> {
+  if let Some(text) = arg {
+    if text != "-" && !Path::new(text).exists() {
+      return parse_grid(text).map_err(|e| CliError::Input(format!("bad puzzle: {}", e)));
+    }
+  }
+  let text = read_text(arg)?;
+  parse_grid(&text).map_err(|e| {
+    CliError::Input(format!(
+      "bad puzzle in {}: {}",
+      arg.filter(|&a| a != "-").unwrap_or("stdin"),
+      e
+    ))
+  })
+}
+
+fn parse_grid(text: &str) -> Result<Grid, String> {
+  formats::parse(text).map(|imported| imported.current())
+}
+
+/// Parses a date formatted as YYYY-MM-DD, for use as a clap value parser.
+pub fn parse_date(s: &str) -> Result<LogicalDate, String> {
+  NaiveDate::parse_from_str(s, "%Y-%m-%d")
+    .map(LogicalDate::from)
+    .map_err(|e| format!("`{}` is not a date formatted as YYYY-MM-DD: {}", s, e))
+}
@@ -0,0 +1,119 @@
+//! The `luke-doku` command-line tool.
+
+use clap::{Parser, Subcommand};
+use std::fmt;
+use std::process::ExitCode;
+
+use output::Format;
+
+mod batch;
+mod canon;
+mod corpus;
+mod daily;
+mod explain;
+mod generate;
+mod hint;
+mod input;
+mod output;
+mod rate;
+mod solve;
+mod stats;
+
+#[derive(Parser, Debug)]
+#[command(
+  version,
+  about,
+  long_about = None,
+  after_help = "Exit status: 0 on success, 1 when there's no answer (for example, \
+                an unsolvable puzzle), 2 for invalid input, 3 for I/O errors."
+)]
+struct Cli {
+  /// How to write the results.
+  #[arg(short, long, value_enum, global = true, default_value_t = Format::Text)]
+  format: Format,
+
+  #[command(subcommand)]
+  command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+  /// Finds a puzzle's solutions.
+  Solve(solve::Args),
+  /// Rates a puzzle's complexity.
+  Rate(rate::Args),
+  /// Finds the next deduction to make in a partially solved puzzle.
+  Hint(hint::Args),
+  /// Lists the deductions the evaluator makes while solving a puzzle.
+  Explain(explain::Args),
+  /// Generates the puzzle with a given ID, or date and counter.
+  Generate(generate::Args),
+  /// Shows the daily solutions for a range of days.
+  Daily(daily::Args),
+  /// Finds the canonical form of a solved grid, or of a puzzle's solution.
+  Canon(canon::Args),
+  /// Gathers statistics about generated puzzles and daily solutions.
+  #[command(subcommand)]
+  Stats(stats::Command),
+  /// Solves and evaluates many puzzles in parallel, writing one line per
+  /// puzzle.
+  Batch(batch::Args),
+  /// Regenerates the golden corpus of generated puzzles, writing one JSON
+  /// object per puzzle.
+  Corpus(corpus::Args),
+}
+
+/// The ways a subcommand can fail.
+#[derive(Debug)]
+pub enum CliError {
+  /// The command ran correctly, but had no answer to give.
+  NoResult(String),
+  /// The command's input was invalid or unreadable.
+  Input(String),
+  /// Writing the command's output failed.
+  Io(String),
+}
+
+impl CliError {
+  fn exit_code(&self) -> ExitCode {
+    match self {
+      CliError::NoResult(_) => ExitCode::from(1),
+      CliError::Input(_) => ExitCode::from(2),
+      CliError::Io(_) => ExitCode::from(3),
+    }
+  }
+}
+
+impl fmt::Display for CliError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      CliError::NoResult(message) | CliError::Input(message) | CliError::Io(message) => {
+        f.write_str(message)
+      }
+    }
+  }
+}
+
+fn main() -> ExitCode {
+  let cli = Cli::parse();
+  let format = cli.format;
+  let result = match cli.command {
+    Command::Solve(args) => solve::run(args, format),
+    Command::Rate(args) => rate::run(args, format),
+    Command::Hint(args) => hint::run(args, format),
+    Command::Explain(args) => explain::run(args, format),
+    Command::Generate(args) => generate::run(args, format),
+    Command::Daily(args) => daily::run(args, format),
+    Command::Canon(args) => canon::run(args, format),
+    Command::Stats(command) => stats::run(command, format),
+    Command::Batch(args) => batch::run(args, format),
+    Command::Corpus(args) => corpus::run(args),
+  };
+  match result {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(e) => {
+      eprintln!("luke-doku: {}", e);
+      e.exit_code()
+    }
+  }
+}
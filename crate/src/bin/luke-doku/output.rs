@@ -0,0 +1,65 @@
+//! Writing results in the format the user asked for.
+
+use std::io::{self, BufWriter, Write};
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::CliError;
+
+/// The output formats every subcommand supports.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum Format {
+  /// Human-readable text.
+  Text,
+  /// A single JSON value.
+  Json,
+}
+
+/// Writes a subcommand's result to standard output, either as JSON or as the
+/// text produced by `text`.
+pub fn emit<T: Serialize>(
+  format: Format,
+  value: &T,
+  text: impl FnOnce(&T) -> String,
+) -> Result<(), CliError> {
+  let out = match format {
+    Format::Text => text(value),
+    Format::Json => serde_json::to_string_pretty(value).expect("results are serializable"),
+  };
+  let mut stdout = io::stdout().lock();
+  finish(writeln!(stdout, "{}", out.trim_end()).and_then(|_| stdout.flush()))
+}
+
+/// Writes a stream of results to standard output, one line per result, as
+/// they arrive: either as JSON objects or as the text produced by `text`.
+/// Stops pulling results once nobody is reading them.
+pub fn emit_lines<T: Serialize>(
+  format: Format,
+  values: impl IntoIterator<Item = T>,
+  text: impl Fn(&T) -> String,
+) -> Result<(), CliError> {
+  let mut stdout = BufWriter::new(io::stdout().lock());
+  let result = values.into_iter().try_for_each(|value| {
+    let line = match format {
+      Format::Text => text(&value),
+      Format::Json => serde_json::to_string(&value).expect("results are serializable"),
+    };
+    writeln!(stdout, "{}", line)
+  });
+  finish(result.and_then(|_| stdout.flush()))
+}
+
+/// Turns the outcome of writing to standard output into a subcommand's result.
+fn finish(result: io::Result<()>) -> Result<(), CliError> {
+  match result {
+    // Whoever stopped reading has all they wanted.
+    Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+    result => result.map_err(|e| CliError::Io(format!("stdout: {}", e))),
+  }
+}
+
+/// Formats a count with the right form of a noun.
+pub fn plural(count: usize, singular: &str, plural: &str) -> String {
+  format!("{} {}", count, if count == 1 { singular } else { plural })
+}
@@ -0,0 +1,53 @@
+//! The `rate` subcommand: evaluates a puzzle's complexity.
+
+use serde::Serialize;
+
+use luke_doku::core::Grid;
+use luke_doku::evaluate::{evaluate, Rating};
+use luke_doku::gen::{Puzzle, MAX_SOLUTIONS};
+
+use crate::input::{self, PUZZLE_HELP};
+use crate::output::{self, Format};
+use crate::CliError;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+  #[arg(help = PUZZLE_HELP)]
+  puzzle: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Report {
+  clues: String,
+  solutions: usize,
+  rating: Rating,
+}
+
+pub fn run(args: Args, format: Format) -> Result<(), CliError> {
+  let clues = input::read_grid(args.puzzle.as_deref())?;
+  let puzzle = make_puzzle(&clues)?;
+  let report = Report {
+    clues: clues.to_flat_string(),
+    solutions: puzzle.solutions.len(),
+    rating: evaluate(&puzzle),
+  };
+  output::emit(format, &report, |r| {
+    format!(
+      "Complexity: {:?} ({})\nSolutions: {}",
+      r.rating.complexity,
+      output::plural(r.rating.complexity as usize, "star", "stars"),
+      r.solutions
+    )
+  })
+}
+
+/// Turns clues into a puzzle, or explains why they don't make one.
+pub fn make_puzzle(clues: &Grid) -> Result<Puzzle, CliError> {
+  Puzzle::new(clues, None).ok_or_else(|| {
+    CliError::NoResult(format!(
+      "not a puzzle: it must have between 1 and {} solutions",
+      MAX_SOLUTIONS
+    ))
+  })
+}
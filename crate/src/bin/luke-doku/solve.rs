@@ -0,0 +1,60 @@
+//! The `solve` subcommand: finds a puzzle's solutions.
+
+use serde::Serialize;
+
+use luke_doku::solve::{solve, DefaultHelper};
+
+use crate::input::{self, PUZZLE_HELP};
+use crate::output::{self, Format};
+use crate::CliError;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+  #[arg(help = PUZZLE_HELP)]
+  puzzle: Option<String>,
+
+  /// The most solutions to report.
+  #[arg(short, long, default_value_t = 10)]
+  max: i32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Report {
+  clues: String,
+  solutions: Vec<String>,
+  /// Whether the puzzle has more than `max` solutions.
+  too_many_solutions: bool,
+}
+
+pub fn run(args: Args, format: Format) -> Result<(), CliError> {
+  let clues = input::read_grid(args.puzzle.as_deref())?;
+  let mut summary = solve(&clues, args.max, &mut DefaultHelper());
+  summary.solutions.truncate(args.max.max(0) as usize);
+  let report = Report {
+    clues: clues.to_flat_string(),
+    solutions: summary.solutions.iter().map(|s| s.to_string()).collect(),
+    too_many_solutions: summary.too_many_solutions,
+  };
+  output::emit(format, &report, |r| {
+    let mut text = format!(
+      "{}{}\n",
+      output::plural(r.solutions.len(), "solution", "solutions"),
+      if r.too_many_solutions {
+        " (and more)"
+      } else {
+        ""
+      }
+    );
+    for solution in &summary.solutions {
+      text += &format!("\n{:?}\n", solution);
+    }
+    text
+  })?;
+  if summary.solutions.is_empty() && !summary.too_many_solutions {
+    return Err(CliError::NoResult(
+      "the puzzle has no solutions".to_string(),
+    ));
+  }
+  Ok(())
+}
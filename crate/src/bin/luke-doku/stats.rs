@@ -0,0 +1,487 @@
+//! The `stats` subcommands: surveys of generated puzzles and daily solutions.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::num::NonZeroU32;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use luke_doku::core::SolvedGrid;
+use luke_doku::date::LogicalDate;
+use luke_doku::deduce::Fact;
+use luke_doku::evaluate::{evaluate_with_observer, Complexity};
+use luke_doku::gen::{daily_solution, Puzzle};
+use luke_doku::permute::GridPermutation;
+
+use crate::daily::dates;
+use crate::input;
+use crate::output::{self, Format};
+use crate::CliError;
+
+#[derive(clap::Subcommand, Debug)]
+pub enum Command {
+  /// Summarizes the solution counts, complexities, and deductions of many
+  /// days' puzzles.
+  Puzzles(PuzzlesArgs),
+  /// Finds the puzzles with the fewest and most clues among a day's first
+  /// puzzles.
+  PuzzleRange(PuzzleRangeArgs),
+  /// Finds the days whose daily solutions have the smallest and largest
+  /// canonical grids in a range of days.
+  OrbitRange(OrbitRangeArgs),
+  /// Finds the first pair of days, from a starting date, whose daily
+  /// solutions belong to the same orbit.
+  SharedOrbit(SharedOrbitArgs),
+}
+
+pub fn run(command: Command, format: Format) -> Result<(), CliError> {
+  match command {
+    Command::Puzzles(args) => puzzles(args, format),
+    Command::PuzzleRange(args) => puzzle_range(args, format),
+    Command::OrbitRange(args) => orbit_range(args, format),
+    Command::SharedOrbit(args) => shared_orbit(args, format),
+  }
+}
+
+#[derive(clap::Args, Debug)]
+pub struct PuzzlesArgs {
+  /// The starting date, e.g., 2024-01-01
+  #[arg(value_parser = input::parse_date)]
+  start_date: LogicalDate,
+
+  /// Number of puzzles to generate per day
+  #[arg(short, long, default_value_t = NonZeroU32::new(10).unwrap())]
+  puzzles_per_day: NonZeroU32,
+
+  /// Number of days to generate for
+  #[arg(short, long, default_value_t = NonZeroU32::new(100).unwrap())]
+  days: NonZeroU32,
+}
+
+/// For each kind of fact, how many puzzles needed it how many times.
+type FactHistograms = BTreeMap<&'static str, BTreeMap<usize, usize>>;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PuzzlesReport {
+  total_puzzles: usize,
+  solutions: BTreeMap<i32, usize>,
+  complexities: BTreeMap<Complexity, usize>,
+  moderate_fact_histograms: FactHistograms,
+  complex_fact_histograms: FactHistograms,
+  anova: Anova,
+}
+
+/// A 1-way analysis of variance of complexity, grouped by day.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Anova {
+  /// The total sum of squares.
+  sst: f64,
+  /// The sum of squares between days.
+  ssb: f64,
+  /// The sum of squares within days.
+  ssw: f64,
+  /// The fraction of the variance explained by the day.
+  r_squared: f64,
+}
+
+fn count_interesting_facts(fact: &Fact, counts: &mut HashMap<&'static str, usize>) {
+  match fact {
+    Fact::SingleLoc { .. } | Fact::SingleNum { .. } | Fact::SpeculativeAssignment { .. } => {}
+    Fact::Implication {
+      antecedents,
+      consequent,
+    } => {
+      for ant in antecedents {
+        count_interesting_facts(ant, counts);
+      }
+      count_interesting_facts(consequent, counts);
+    }
+    _ => {
+      *counts.entry(fact.type_name()).or_insert(0) += 1;
+    }
+  }
+}
+
+fn puzzles(args: PuzzlesArgs, format: Format) -> Result<(), CliError> {
+  let puzzles_per_day = args.puzzles_per_day.get() as i32;
+  let mut total_puzzles = 0;
+  let mut solutions_distribution: BTreeMap<i32, usize> = BTreeMap::new();
+  let mut complexity_distribution: BTreeMap<Complexity, usize> = BTreeMap::new();
+  let mut fact_histograms_moderate = FactHistograms::new();
+  let mut fact_histograms_complex = FactHistograms::new();
+  let mut all_complexities: Vec<f64> = Vec::new();
+  let mut day_means: Vec<f64> = Vec::new();
+
+  if format == Format::Text {
+    eprintln!(
+      "Generating stats for {} days, {} puzzles per day starting on {}...",
+      args.days, args.puzzles_per_day, args.start_date
+    );
+  }
+
+  for date in dates(args.start_date, args.days.get()) {
+    let ds = daily_solution(&date);
+    let mut day_complexities = Vec::new();
+
+    for i in 1..=puzzles_per_day {
+      let puzzle = ds.generate(i).map_err(|e| CliError::Input(e.to_string()))?;
+      total_puzzles += 1;
+      *solutions_distribution
+        .entry(puzzle.solutions_count())
+        .or_insert(0) += 1;
+
+      let mut applied_facts = Vec::new();
+      let rating = evaluate_with_observer(&puzzle, |fact| {
+        applied_facts.push(fact.clone());
+      });
+      *complexity_distribution
+        .entry(rating.complexity)
+        .or_insert(0) += 1;
+
+      let comp_score = rating.complexity as i32 as f64;
+      day_complexities.push(comp_score);
+      all_complexities.push(comp_score);
+
+      let histograms = match rating.complexity {
+        Complexity::Moderate => &mut fact_histograms_moderate,
+        Complexity::Complex => &mut fact_histograms_complex,
+        _ => continue,
+      };
+      let mut fact_counts = HashMap::new();
+      for fact in &applied_facts {
+        count_interesting_facts(fact, &mut fact_counts);
+      }
+      for (fact_type, count) in fact_counts {
+        *histograms
+          .entry(fact_type)
+          .or_default()
+          .entry(count)
+          .or_insert(0) += 1;
+      }
+    }
+
+    let day_mean = day_complexities.iter().sum::<f64>() / day_complexities.len() as f64;
+    day_means.push(day_mean);
+  }
+
+  let overall_mean = all_complexities.iter().sum::<f64>() / all_complexities.len() as f64;
+  let sst: f64 = all_complexities
+    .iter()
+    .map(|comp| (comp - overall_mean).powi(2))
+    .sum();
+  let ssb: f64 = day_means
+    .iter()
+    .map(|day_mean| puzzles_per_day as f64 * (day_mean - overall_mean).powi(2))
+    .sum();
+  let report = PuzzlesReport {
+    total_puzzles,
+    solutions: solutions_distribution,
+    complexities: complexity_distribution,
+    moderate_fact_histograms: fact_histograms_moderate,
+    complex_fact_histograms: fact_histograms_complex,
+    anova: Anova {
+      sst,
+      ssb,
+      ssw: sst - ssb,
+      r_squared: if sst > 0.0 { ssb / sst } else { 0.0 },
+    },
+  };
+  output::emit(format, &report, puzzles_text)
+}
+
+fn puzzles_text(r: &PuzzlesReport) -> String {
+  let count_of = |c| r.complexities.get(&c).copied().unwrap_or(0);
+  let mut t = String::new();
+  let percent = |count: usize| (count as f64 / r.total_puzzles as f64) * 100.0;
+  // Writing to a String can't fail.
+  writeln!(t, "Total Puzzles Evaluated: {}", r.total_puzzles).unwrap();
+
+  writeln!(t, "\nNumber of Solutions Distribution:").unwrap();
+  for (sols, &count) in &r.solutions {
+    writeln!(
+      t,
+      "  {} solutions: {} ({:.1}%)",
+      sols,
+      count,
+      percent(count)
+    )
+    .unwrap();
+  }
+
+  writeln!(t, "\nComplexity Distribution:").unwrap();
+  for (comp, &count) in &r.complexities {
+    writeln!(t, "  {:?}: {} ({:.1}%)", comp, count, percent(count)).unwrap();
+  }
+
+  write_fact_histograms(
+    &mut t,
+    "Fact Type Histograms (Moderate Puzzles):",
+    count_of(Complexity::Moderate),
+    &r.moderate_fact_histograms,
+  );
+  write_fact_histograms(
+    &mut t,
+    "Fact Type Histograms (Complex Puzzles):",
+    count_of(Complexity::Complex),
+    &r.complex_fact_histograms,
+  );
+
+  let a = &r.anova;
+  writeln!(
+    t,
+    "\nIntra-day Similarity Analysis (1-way ANOVA on Complexity):"
+  )
+  .unwrap();
+  writeln!(t, "  Total Variance (SST): {:.2}", a.sst).unwrap();
+  writeln!(t, "  Between-Day Variance (SSB): {:.2}", a.ssb).unwrap();
+  writeln!(t, "  Within-Day Variance (SSW): {:.2}", a.ssw).unwrap();
+  writeln!(
+    t,
+    "  Variance explained by Day (R^2): {:.2}%",
+    a.r_squared * 100.0
+  )
+  .unwrap();
+  if a.r_squared > 0.1 {
+    writeln!(t, "  (Conclusion: Puzzles generated on the same day are significantly more similar in complexity to each other than to puzzles from other days.)").unwrap();
+  } else {
+    writeln!(t, "  (Conclusion: The day a puzzle is generated does not have a large impact on its complexity.)").unwrap();
+  }
+  t
+}
+
+fn write_fact_histograms(
+  t: &mut String,
+  title: &str,
+  total_puzzles_in_bucket: usize,
+  histograms: &FactHistograms,
+) {
+  writeln!(t, "\n{}", title).unwrap();
+  if total_puzzles_in_bucket == 0 {
+    writeln!(t, "  (No puzzles in this category)").unwrap();
+    return;
+  }
+  for (fact, counts) in histograms {
+    writeln!(t, "  {}:", fact).unwrap();
+
+    let total_with_fact: usize = counts.values().sum();
+    let max_count = counts.keys().copied().max().unwrap_or(0);
+    let count_0 = total_puzzles_in_bucket - total_with_fact;
+    let bucket_size = if max_count <= 15 {
+      1
+    } else if max_count <= 50 {
+      5
+    } else if max_count <= 200 {
+      10
+    } else if max_count <= 1000 {
+      50
+    } else {
+      100
+    };
+
+    let mut bucketed = BTreeMap::new();
+    if count_0 > 0 {
+      bucketed.insert(0, count_0);
+    }
+    for (&appearances, &num_puzzles) in counts {
+      let bucket = (appearances / bucket_size) * bucket_size;
+      *bucketed.entry(bucket).or_insert(0) += num_puzzles;
+    }
+
+    let max_puzzles = bucketed.values().copied().max().unwrap_or(0);
+    let max_bar_width = 30;
+    for (bucket, num_puzzles) in bucketed {
+      let bar_len = if max_puzzles > 0 {
+        (num_puzzles as f64 / max_puzzles as f64 * max_bar_width as f64).round() as usize
+      } else {
+        0
+      };
+      let bar = "∎".repeat(bar_len);
+      if bucket_size == 1 {
+        writeln!(
+          t,
+          "    {:>3} appearances: {:<30} ({} puzzles)",
+          bucket, bar, num_puzzles
+        )
+        .unwrap();
+      } else {
+        writeln!(
+          t,
+          "    {:>3}-{:<3} appearances: {:<30} ({} puzzles)",
+          bucket,
+          bucket + bucket_size - 1,
+          bar,
+          num_puzzles
+        )
+        .unwrap();
+      }
+    }
+  }
+}
+
+#[derive(clap::Args, Debug)]
+pub struct PuzzleRangeArgs {
+  /// The date, formatted as YYYY-MM-DD.
+  #[arg(value_parser = input::parse_date)]
+  date: LogicalDate,
+
+  /// The number of puzzles to look at.
+  count: NonZeroU32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PuzzleRangeReport {
+  fewest: PuzzleSummary,
+  most: PuzzleSummary,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PuzzleSummary {
+  counter: i32,
+  num_clues: usize,
+  clues: String,
+}
+
+impl PuzzleSummary {
+  fn new(puzzle: &Puzzle) -> Self {
+    PuzzleSummary {
+      counter: puzzle.gen_opts.map_or(0, |o| o.counter),
+      num_clues: puzzle.clues.len(),
+      clues: puzzle.clues.to_flat_string(),
+    }
+  }
+}
+
+fn puzzle_range(args: PuzzleRangeArgs, format: Format) -> Result<(), CliError> {
+  let ds = daily_solution(&args.date);
+  let mut fewest: Option<Puzzle> = None;
+  let mut most: Option<Puzzle> = None;
+  for i in 1..=args.count.get() as i32 {
+    let puzzle = ds.generate(i).map_err(|e| CliError::Input(e.to_string()))?;
+    let len = puzzle.clues.len();
+    if fewest.as_ref().is_none_or(|p| len < p.clues.len()) {
+      fewest = Some(puzzle.clone());
+    }
+    if most.as_ref().is_none_or(|p| len > p.clues.len()) {
+      most = Some(puzzle);
+    }
+  }
+  // The count is nonzero, so both are set.
+  let (fewest, most) = (fewest.unwrap(), most.unwrap());
+  let report = PuzzleRangeReport {
+    fewest: PuzzleSummary::new(&fewest),
+    most: PuzzleSummary::new(&most),
+  };
+  output::emit(format, &report, |r| {
+    format!(
+      "Smallest number of clues ({}, puzzle {}):\n{:?}\n\nLargest number of clues ({}, puzzle {}):\n{:?}",
+      r.fewest.num_clues, r.fewest.counter, fewest.clues, r.most.num_clues, r.most.counter, most.clues
+    )
+  })
+}
+
+#[derive(clap::Args, Debug)]
+pub struct OrbitRangeArgs {
+  /// The starting date, formatted as YYYY-MM-DD.
+  #[arg(value_parser = input::parse_date)]
+  start_date: LogicalDate,
+
+  /// The number of days to look at.
+  days: NonZeroU32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OrbitRangeReport {
+  smallest: DatedGrid,
+  largest: DatedGrid,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DatedGrid {
+  date: String,
+  canonical: String,
+}
+
+fn orbit_range(args: OrbitRangeArgs, format: Format) -> Result<(), CliError> {
+  let mut smallest: Option<(SolvedGrid, LogicalDate)> = None;
+  let mut largest: Option<(SolvedGrid, LogicalDate)> = None;
+  for date in dates(args.start_date, args.days.get()) {
+    let ds = daily_solution(&date);
+    let (_, min, _) = GridPermutation::minimizing(&ds.solution);
+    if smallest.is_none_or(|(prev, _)| min < prev) {
+      smallest = Some((min, date));
+    }
+    if largest.is_none_or(|(prev, _)| min > prev) {
+      largest = Some((min, date));
+    }
+  }
+  // The number of days is nonzero, so both are set.
+  let (smallest, largest) = (smallest.unwrap(), largest.unwrap());
+  let dated = |(grid, date): (SolvedGrid, LogicalDate)| DatedGrid {
+    date: date.to_string(),
+    canonical: grid.to_string(),
+  };
+  let report = OrbitRangeReport {
+    smallest: dated(smallest),
+    largest: dated(largest),
+  };
+  output::emit(format, &report, |r| {
+    format!(
+      "Smallest minimum found on {}:\n{:?}\n\nLargest minimum found on {}:\n{:?}",
+      r.smallest.date, smallest.0, r.largest.date, largest.0
+    )
+  })
+}
+
+#[derive(clap::Args, Debug)]
+pub struct SharedOrbitArgs {
+  /// The starting date, formatted as YYYY-MM-DD.
+  #[arg(value_parser = input::parse_date)]
+  start_date: LogicalDate,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SharedOrbitReport {
+  first: String,
+  second: String,
+  canonical: String,
+}
+
+/// Finds a pair of days whose daily solutions belong to the same Sudoku orbit.
+/// Even though there are 5B+ distinct orbits, you reach a 50% likelihood of
+/// finding a match after around sqrt(5B), which is around 70K.  (This is the
+/// "birthday paradox.")
+fn shared_orbit(args: SharedOrbitArgs, format: Format) -> Result<(), CliError> {
+  let mut minima: HashMap<SolvedGrid, LogicalDate> = HashMap::new();
+  let (first, second, canonical) = dates(args.start_date, u32::MAX)
+    .find_map(|date| {
+      let ds = daily_solution(&date);
+      let (_, min, _) = GridPermutation::minimizing(&ds.solution);
+      minima.insert(min, date).map(|prev| (prev, date, min))
+    })
+    .ok_or_else(|| CliError::NoResult("ran out of dates".to_string()))?;
+  let report = SharedOrbitReport {
+    first: first.to_string(),
+    second: second.to_string(),
+    canonical: canonical.to_string(),
+  };
+  output::emit(format, &report, |r| {
+    let start: NaiveDate = args.start_date.into();
+    let (a, b): (NaiveDate, NaiveDate) = (first.into(), second.into());
+    format!(
+      "{}, {}: {} and {} days from start, {} days apart",
+      r.first,
+      r.second,
+      (a - start).num_days(),
+      (b - start).num_days(),
+      (b - a).num_days()
+    )
+  })
+}
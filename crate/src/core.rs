@@ -3,27 +3,72 @@
 //! Among these are:
 //!
 //! - Grid: the 9x9 Sudoku board
+//! - CandidateGrid: the board as the numerals still possible in each square
 //! - Num: the 9 numerals that go in the grid's squares
 //! - Loc: the 81 locations of the grid
 //! - various types identifying parts of the grid like Row, Col and Blk
+//! - Variant: the rules a puzzle follows beyond standard Sudoku's
+//! - PeerRelation: a rule by which an assignment restricts other locations
+//! - Regions: the layout of a Jigsaw Sudoku's irregular blocks
+//! - Cages: the summed groups of locations of a Killer Sudoku
+//! - SizedGrid: a board of another size, like 4x4 or 16x16
 
 mod asgmt;
 pub mod bits;
+mod cages;
+mod candidates;
 mod grid;
 mod id_types;
 mod loc;
 pub mod masks;
 mod num;
+mod peers;
+mod regions;
 pub mod set;
+pub mod simd;
+mod sized;
 mod units;
+mod variant;
 
 pub use asgmt::*;
+pub use cages::*;
+pub use candidates::*;
 pub use grid::*;
 pub use loc::*;
 pub use num::*;
+pub use peers::*;
+pub use regions::*;
 pub use set::Set;
+pub use sized::*;
 pub use units::*;
+pub use variant::*;
 
-/// Marker error for invalid Sudoku grids.
-#[derive(Debug)]
-pub struct Invalid;
+/// Error for invalid Sudoku grids, saying where the contradiction showed up.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Invalid {
+  /// The location has no possible numerals left, or more than one assigned.
+  Loc(Loc),
+  /// The numeral has no possible location left in the unit, or more than one
+  /// assigned.
+  Unit(Unit, Num),
+  /// The numeral's possible locations in the row band's three blocks can't
+  /// be spread across the band's three rows.
+  Band(Band, Num),
+}
+
+impl std::fmt::Display for Invalid {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Invalid::Loc(loc) => write!(f, "{} has no consistent numeral", loc),
+      Invalid::Unit(unit, num) => write!(f, "{} has no consistent location for {}", unit, num),
+      Invalid::Band(band, num) => {
+        write!(
+          f,
+          "Row band {} has no consistent locations for {}",
+          band.ordinal(),
+          num
+        )
+      }
+    }
+  }
+}
@@ -5,7 +5,7 @@ use super::bits::{Bits, Bits27, Bits3x27, Bits9x3x27, BitsArray};
 use super::grid::Grid;
 use super::masks::loc_to_zeroed_peers;
 use super::set::Set;
-use super::{loc::*, num::*, Invalid};
+use super::{loc::*, num::*, units::*, Invalid};
 use crate::define_set_operators;
 
 /// An assignment: combines a Sudoku location and a numeral to represent that
@@ -63,12 +63,29 @@ impl AsgmtSet {
   }
 
   /// Makes a new AsgmtSet containing all assignments that are consistent with
-  /// the given grid.  Returns `Err(Invalid)` if the grid is invalid.
+  /// the given grid.  Returns an `Invalid` error naming the conflicting unit if
+  /// the grid is invalid.
   pub fn valid_possibles_from_grid(grid: &Grid) -> Result<Self, Invalid> {
     let mut answer = Self::all();
     for asgmt in grid.iter() {
       if !answer.contains(asgmt) {
-        return Err(Invalid);
+        let loc = asgmt.loc;
+        let units = [
+          loc.row().to_unit(),
+          loc.col().to_unit(),
+          loc.blk().to_unit(),
+        ];
+        return Err(
+          units
+            .into_iter()
+            .find(|unit| {
+              unit
+                .locs()
+                .iter()
+                .any(|l| l != loc && grid[l] == Some(asgmt.num))
+            })
+            .map_or(Invalid::Loc(loc), |unit| Invalid::Unit(unit, asgmt.num)),
+        );
       }
       answer.apply(asgmt)
     }
@@ -130,7 +147,8 @@ impl AsgmtSet {
 
   /// Finds all locations in this set that have either one or two assignments,
   /// and returns those two location sets as a tuple.  Returns an Invalid
-  /// error if any locations have no possible assignments.
+  /// error naming the first location that has no possible assignments, if
+  /// any do.
   pub fn valid_singles_and_doubles(&self) -> Result<(LocSet, LocSet), Invalid> {
     // `minN` is locations with at least N assignments in this set.
     let mut min1 = Bits3x27::ZERO;
@@ -142,7 +160,8 @@ impl AsgmtSet {
       min1 |= bits;
     }
     if min1 != Bits3x27::ONES {
-      return Err(Invalid);
+      let empty = LocSet::all() - LocSet(min1);
+      return Err(Invalid::Loc(empty.smallest_item().unwrap()));
     }
     // Works like min1 & !min2, because min1 is a superset of min2.
     let exactly1 = LocSet(min1 ^ min2);
@@ -255,6 +274,30 @@ define_set_operators!(AsgmtSet);
 mod tests {
   use super::*;
 
+  #[test]
+  fn invalid_grids_name_the_conflict() {
+    let g = "1...1............................................................................"
+      .parse::<Grid>()
+      .unwrap();
+    assert_eq!(
+      Err(Invalid::Unit(Unit::Row(R1), N1)),
+      AsgmtSet::valid_possibles_from_grid(&g)
+    );
+    let g = "1.........1......................................................................"
+      .parse::<Grid>()
+      .unwrap();
+    assert_eq!(
+      Err(Invalid::Unit(Unit::Blk(B1), N1)),
+      AsgmtSet::valid_possibles_from_grid(&g)
+    );
+
+    let mut set = AsgmtSet::all();
+    for num in Num::all() {
+      set.remove(Asgmt::new(num, L35));
+    }
+    assert_eq!(Err(Invalid::Loc(L35)), set.valid_singles_and_doubles());
+  }
+
   #[test]
   fn to_grid() {
     let s = ".1..5..8.4.89.62.1..6...7....5.3.9.....8.7.....1.4.3....4...1..2.93.16.7.7..6..2.";
@@ -9,6 +9,8 @@ use std::{
 use wasm_bindgen::convert::{FromWasmAbi, IntoWasmAbi};
 use wasm_bindgen::describe::{inform, WasmDescribe, U16, U32, U8};
 
+use super::simd;
+
 /// Operations on a fixed-capacity collection of bits.
 ///
 /// You can see a `Bits` object as a set of bits, or as a set of values
@@ -279,6 +281,7 @@ macro_rules! define_wrapped_bits_types {
     ) => {
         $(
         #[derive(Clone, Copy, Default, Eq, Hash, PartialEq)]
+        #[repr(transparent)]
         $(#[$outer])*
         pub struct $type_name($int);
 
@@ -441,6 +444,10 @@ define_wrapped_bits_types! {
     /// An 18-bit set.  This is used for the 18 ways to form an overlap within a
     /// band.
     Bits18: u32[18], U32;
+
+    /// A 29-bit set, for the units: the 27 standard ones plus the 2 diagonals
+    /// that X-Sudoku adds.
+    Bits29: u32[29], U32;
 }
 
 impl Bits9 {
@@ -492,6 +499,120 @@ pub trait BitsArray<T: Bits, const N: usize> {
   fn mut_array(&mut self) -> &mut [T; N];
 }
 
+/// Implements the bitwise operators for an array of Bits, one element at a
+/// time.
+macro_rules! impl_bits_array_ops {
+    ($type_name:ident, $count:expr) => {
+        impl BitAnd for $type_name {
+            type Output = Self;
+            fn bitand(mut self, rhs: Self) -> Self {
+                self &= rhs;
+                self
+            }
+        }
+        impl BitAndAssign for $type_name {
+            fn bitand_assign(&mut self, rhs: Self) {
+                for i in 0..$count {
+                    unsafe {
+                        // Safe because $count is the size of the arrays.
+                        *self.0.get_unchecked_mut(i) &= *rhs.0.get_unchecked(i);
+                    }
+                }
+            }
+        }
+        impl BitOr for $type_name {
+            type Output = Self;
+            fn bitor(mut self, rhs: Self) -> Self {
+                self |= rhs;
+                self
+            }
+        }
+        impl BitOrAssign for $type_name {
+            fn bitor_assign(&mut self, rhs: Self) {
+                for i in 0..$count {
+                    unsafe {
+                        // Safe because $count is the size of the arrays.
+                        *self.0.get_unchecked_mut(i) |= *rhs.0.get_unchecked(i);
+                    }
+                }
+            }
+        }
+        impl BitXor for $type_name {
+            type Output = Self;
+            fn bitxor(mut self, rhs: Self) -> Self {
+                self ^= rhs;
+                self
+            }
+        }
+        impl BitXorAssign for $type_name {
+            fn bitxor_assign(&mut self, rhs: Self) {
+                for i in 0..$count {
+                    unsafe {
+                        // Safe because $count is the size of the arrays.
+                        *self.0.get_unchecked_mut(i) ^= *rhs.0.get_unchecked(i);
+                    }
+                }
+            }
+        }
+        impl Not for $type_name {
+            type Output = Self;
+            fn not(self) -> Self {
+                self ^ Self::ONES
+            }
+        }
+    };
+}
+
+/// Implements the bitwise operators for `Bits9x3x27` using the vectorized
+/// operations on its words.
+#[cfg(feature = "simd")]
+macro_rules! impl_simd_bits_array_ops {
+    ($type_name:ident) => {
+        impl BitAnd for $type_name {
+            type Output = Self;
+            fn bitand(mut self, rhs: Self) -> Self {
+                self &= rhs;
+                self
+            }
+        }
+        impl BitAndAssign for $type_name {
+            fn bitand_assign(&mut self, rhs: Self) {
+                simd::and_assign(self.words_mut(), rhs.words());
+            }
+        }
+        impl BitOr for $type_name {
+            type Output = Self;
+            fn bitor(mut self, rhs: Self) -> Self {
+                self |= rhs;
+                self
+            }
+        }
+        impl BitOrAssign for $type_name {
+            fn bitor_assign(&mut self, rhs: Self) {
+                simd::or_assign(self.words_mut(), rhs.words());
+            }
+        }
+        impl BitXor for $type_name {
+            type Output = Self;
+            fn bitxor(mut self, rhs: Self) -> Self {
+                self ^= rhs;
+                self
+            }
+        }
+        impl BitXorAssign for $type_name {
+            fn bitxor_assign(&mut self, rhs: Self) {
+                simd::xor_assign(self.words_mut(), rhs.words());
+            }
+        }
+        impl Not for $type_name {
+            type Output = Self;
+            fn not(self) -> Self {
+                self ^ Self::ONES
+            }
+        }
+    };
+}
+
 /// Defines Bits-implementing newtypes for fixed-size arrays of Bits types.
 macro_rules! define_bits_array_types {
     (
@@ -648,63 +769,6 @@ macro_rules! define_bits_array_types {
             }
         }
 
-        impl BitAnd for $type_name {
-            type Output = Self;
-            fn bitand(mut self, rhs: Self) -> Self {
-                self &= rhs;
-                self
-            }
-        }
-        impl BitAndAssign for $type_name {
-            fn bitand_assign(&mut self, rhs: Self) {
-                for i in 0..$count {
-                    unsafe {
-                        // Safe because $count is the size of the arrays.
-                        *self.0.get_unchecked_mut(i) &= *rhs.0.get_unchecked(i);
-                    }
-                }
-            }
-        }
-        impl BitOr for $type_name {
-            type Output = Self;
-            fn bitor(mut self, rhs: Self) -> Self {
-                self |= rhs;
-                self
-            }
-        }
-        impl BitOrAssign for $type_name {
-            fn bitor_assign(&mut self, rhs: Self) {
-                for i in 0..$count {
-                    unsafe {
-                        // Safe because $count is the size of the arrays.
-                        *self.0.get_unchecked_mut(i) |= *rhs.0.get_unchecked(i);
-                    }
-                }
-            }
-        }
-        impl BitXor for $type_name {
-            type Output = Self;
-            fn bitxor(mut self, rhs: Self) -> Self {
-                self ^= rhs;
-                self
-            }
-        }
-        impl BitXorAssign for $type_name {
-            fn bitxor_assign(&mut self, rhs: Self) {
-                for i in 0..$count {
-                    unsafe {
-                        // Safe because $count is the size of the arrays.
-                        *self.0.get_unchecked_mut(i) ^= *rhs.0.get_unchecked(i);
-                    }
-                }
-            }
-        }
-        impl Not for $type_name {
-            type Output = Self;
-            fn not(self) -> Self {
-                self ^ Self::ONES
-            }
-        }
     )*
     };
 }
@@ -717,6 +781,32 @@ define_bits_array_types! {
     Bits9x3x27: [Bits3x27; 9];
 }
 
+impl_bits_array_ops!(Bits3x27, 3);
+#[cfg(not(feature = "simd"))]
+impl_bits_array_ops!(Bits9x3x27, 9);
+#[cfg(feature = "simd")]
+impl_simd_bits_array_ops!(Bits9x3x27);
+
+// `Bits9x3x27` is laid out exactly like an array of 27 `u32`s.
+const_assert!(std::mem::size_of::<Bits9x3x27>() == std::mem::size_of::<simd::Words>());
+
+impl Bits9x3x27 {
+    /// Views the bits as 27 words, one per numeral and band.
+    pub fn words(&self) -> &simd::Words {
+        // Safe because `Bits9x3x27` and `Bits3x27` are `repr(C)` arrays, and
+        // `Bits27` is a `repr(transparent)` `u32`.
+        unsafe { &*(self as *const Self as *const simd::Words) }
+    }
+
+    /// Views the bits as 27 mutable words, one per numeral and band.  Callers
+    /// must not set any bits beyond each word's 27th.
+    pub fn words_mut(&mut self) -> &mut simd::Words {
+        // Safe because `Bits9x3x27` and `Bits3x27` are `repr(C)` arrays, and
+        // `Bits27` is a `repr(transparent)` `u32`.
+        unsafe { &mut *(self as *mut Self as *mut simd::Words) }
+    }
+}
+
 /// Iterates through the bits of a `Bits` instance, by returning a separate
 /// single-bit `Bits` instance for each `1` bit.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -935,6 +1025,7 @@ mod tests {
   simple_bits_test!(Bits3, 3);
   simple_bits_test!(Bits9, 9);
   simple_bits_test!(Bits27, 27);
+  simple_bits_test!(Bits29, 29);
   simple_bits_test!(Bits3x27, 81);
   simple_bits_test!(Bits9x3x27, 729);
 
@@ -0,0 +1,287 @@
+//! Defines the Cage type, for Killer Sudoku: a group of locations whose
+//! numerals must add up to a given sum.
+
+use serde::Serialize;
+use std::ops::Index;
+
+use super::*;
+
+/// A Killer Sudoku cage: the numerals in its locations add up to its sum.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize)]
+pub struct Cage {
+  pub locs: LocSet,
+  pub sum: i32,
+  /// Whether the cage's numerals must all be different, as they are in most
+  /// Killer Sudokus.
+  pub distinct: bool,
+}
+
+impl Cage {
+  /// Makes a cage with distinct numerals.
+  pub fn new(locs: LocSet, sum: i32) -> Self {
+    Self {
+      locs,
+      sum,
+      distinct: true,
+    }
+  }
+
+  /// Makes a cage over the given locations whose sum comes from the given
+  /// solution.
+  pub fn from_solution(locs: LocSet, solution: &SolvedGrid) -> Self {
+    let sum = locs.iter().map(|loc| solution[loc].get() as i32).sum();
+    Self::new(locs, sum)
+  }
+
+  /// The smallest and largest sums the cage's locations could add up to.
+  fn sum_range(&self) -> (i32, i32) {
+    let n = self.locs.len();
+    if self.distinct {
+      (n * (n + 1) / 2, n * (19 - n) / 2)
+    } else {
+      (n, 9 * n)
+    }
+  }
+}
+
+/// The cages of a Killer Sudoku.  They don't overlap, and usually (but not
+/// necessarily) cover the whole grid.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, Serialize)]
+pub struct Cages(Vec<Cage>);
+
+impl Cages {
+  /// Makes a set of cages, failing if any of them overlap, or have an
+  /// impossible sum.
+  pub fn new(cages: Vec<Cage>) -> Result<Self, String> {
+    let mut covered = LocSet::new();
+    for (i, cage) in cages.iter().enumerate() {
+      if cage.locs.is_empty() || (cage.distinct && cage.locs.len() > 9) {
+        return Err(format!("Cage {} has {} locations", i, cage.locs.len()));
+      }
+      let (min, max) = cage.sum_range();
+      if !(min..=max).contains(&cage.sum) {
+        return Err(format!(
+          "Cage {} can't add up to {}: its range is {} through {}",
+          i, cage.sum, min, max
+        ));
+      }
+      if !(covered & cage.locs).is_empty() {
+        return Err(format!("Cage {} overlaps another", i));
+      }
+      covered |= cage.locs;
+    }
+    Ok(Self(cages))
+  }
+
+  /// How many cages there are.
+  pub fn len(&self) -> usize {
+    self.0.len()
+  }
+
+  /// Tells whether there are no cages.
+  pub fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+
+  /// Iterates the cages.
+  pub fn iter(&self) -> impl Iterator<Item = &Cage> {
+    self.0.iter()
+  }
+
+  /// The locations that are in a cage.
+  pub fn covered(&self) -> LocSet {
+    self
+      .0
+      .iter()
+      .fold(LocSet::new(), |acc, cage| acc | cage.locs)
+  }
+
+  /// Tells whether the given solution's numerals satisfy all the cages.
+  pub fn are_satisfied_by(&self, solution: &SolvedGrid) -> bool {
+    self.0.iter().all(|cage| {
+      let nums: Vec<Num> = cage.locs.iter().map(|loc| solution[loc]).collect();
+      let distinct = nums.iter().copied().collect::<NumSet>().len() as usize == nums.len();
+      Cage::from_solution(cage.locs, solution).sum == cage.sum && (distinct || !cage.distinct)
+    })
+  }
+}
+
+impl Index<usize> for Cages {
+  type Output = Cage;
+
+  fn index(&self, index: usize) -> &Cage {
+    &self.0[index]
+  }
+}
+
+/// Iterates the sets of `size` distinct numerals that add up to `sum`.
+pub fn sum_combinations(size: i32, sum: i32) -> impl Iterator<Item = NumSet> {
+  (0..1 << 9).filter_map(move |bits: i32| {
+    if bits.count_ones() as i32 != size {
+      return None;
+    }
+    let nums: NumSet = Num::all()
+      .filter(|num| bits & (1 << num.index()) != 0)
+      .collect();
+    let total: i32 = nums.iter().map(|num| num.get() as i32).sum();
+    (total == sum).then_some(nums)
+  })
+}
+
+/// Narrows the candidates of a group of locations whose numerals add up to the
+/// given sum, and if `distinct` is true must all differ.  Returns each
+/// location's candidates that could be part of such a sum.
+///
+/// With `distinct`, this is exact: every numeral it keeps appears in some way
+/// of filling the locations.  Otherwise it only compares each location against
+/// the others' smallest and largest candidates.
+pub fn sum_candidates(candidates: &[NumSet], sum: i32, distinct: bool) -> Vec<NumSet> {
+  let value = |num: Num| num.get() as i32;
+  if distinct {
+    let mut search = DistinctSum {
+      candidates,
+      sum,
+      completes: vec![None; (candidates.len() + 1) << 9],
+      visited: vec![false; (candidates.len() + 1) << 9],
+      result: vec![NumSet::new(); candidates.len()],
+    };
+    if search.completes(0, 0) {
+      search.collect(0, 0);
+    }
+    search.result
+  } else {
+    let mins: Vec<i32> = candidates
+      .iter()
+      .map(|c| c.smallest_item().map_or(0, value))
+      .collect();
+    let maxes: Vec<i32> = candidates
+      .iter()
+      .map(|c| c.iter().last().map_or(0, value))
+      .collect();
+    let (min_total, max_total): (i32, i32) = (mins.iter().sum(), maxes.iter().sum());
+    candidates
+      .iter()
+      .enumerate()
+      .map(|(i, c)| {
+        // What the other locations leave for this one.
+        let low = sum - (max_total - maxes[i]);
+        let high = sum - (min_total - mins[i]);
+        c.iter()
+          .filter(|num| (low..=high).contains(&value(*num)))
+          .collect()
+      })
+      .collect()
+  }
+}
+
+/// Fills the locations of a distinct-numeral sum in order, remembering which
+/// partial fillings, identified by how many locations are filled and with
+/// which numerals, can be completed.
+struct DistinctSum<'a> {
+  candidates: &'a [NumSet],
+  sum: i32,
+  completes: Vec<Option<bool>>,
+  visited: Vec<bool>,
+  result: Vec<NumSet>,
+}
+
+impl DistinctSum<'_> {
+  /// Tells whether the first `i` locations, filled with the numerals in the
+  /// `used` bits, can be completed.
+  fn completes(&mut self, i: usize, used: usize) -> bool {
+    let key = (i << 9) | used;
+    if let Some(answer) = self.completes[key] {
+      return answer;
+    }
+    let answer = if i == self.candidates.len() {
+      Num::all()
+        .filter(|num| used & (1 << num.index()) != 0)
+        .map(|num| num.get() as i32)
+        .sum::<i32>()
+        == self.sum
+    } else {
+      self.candidates[i]
+        .iter()
+        .any(|num| used & (1 << num.index()) == 0 && self.completes(i + 1, used | 1 << num.index()))
+    };
+    self.completes[key] = Some(answer);
+    answer
+  }
+
+  /// Records the numerals that can go in the remaining locations, given a
+  /// partial filling that can be completed.
+  fn collect(&mut self, i: usize, used: usize) {
+    let key = (i << 9) | used;
+    if i == self.candidates.len() || self.visited[key] {
+      return;
+    }
+    self.visited[key] = true;
+    for num in self.candidates[i].iter() {
+      let next = used | 1 << num.index();
+      if next != used && self.completes(i + 1, next) {
+        self.result[i].insert(num);
+        self.collect(i + 1, next);
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn nums(ns: &[i8]) -> NumSet {
+    ns.iter().map(|n| Num::new(*n).unwrap()).collect()
+  }
+
+  #[test]
+  fn test_sum_combinations() {
+    assert_eq!(
+      vec![nums(&[1, 2])],
+      sum_combinations(2, 3).collect::<Vec<_>>()
+    );
+    assert_eq!(4, sum_combinations(2, 10).count());
+    assert_eq!(1, sum_combinations(9, 45).count());
+    assert_eq!(0, sum_combinations(3, 5).count());
+  }
+
+  #[test]
+  fn test_sum_candidates() {
+    let all = NumSet::all();
+    // A 2-location cage adding to 3 must be 1 and 2.
+    assert_eq!(
+      vec![nums(&[1, 2]), nums(&[1, 2])],
+      sum_candidates(&[all, all], 3, true)
+    );
+    // A 17 in 2 is 8 and 9; with one location already 9, the other is 8.
+    assert_eq!(
+      vec![nums(&[9]), nums(&[8])],
+      sum_candidates(&[nums(&[9]), all], 17, true)
+    );
+    // Without the distinct rule, 2 locations adding to 4 could be 2 and 2.
+    assert_eq!(
+      vec![nums(&[1, 2]), nums(&[2])],
+      sum_candidates(&[all, nums(&[2, 5])], 4, false)
+    );
+    // No combination fits, so nothing is possible.
+    assert_eq!(
+      vec![NumSet::new(), NumSet::new()],
+      sum_candidates(&[nums(&[1]), nums(&[1])], 3, true)
+    );
+  }
+
+  #[test]
+  fn test_validation() {
+    let cage = |locs: &[Loc], sum| Cage::new(locs.iter().copied().collect(), sum);
+    assert!(Cages::new(vec![cage(&[L11, L12], 3), cage(&[L13], 9)]).is_ok());
+    assert!(Cages::new(vec![cage(&[L11, L12], 2)]).is_err());
+    assert!(Cages::new(vec![cage(&[L11, L12], 18)]).is_err());
+    assert!(Cages::new(vec![cage(&[L11, L12], 3), cage(&[L12], 9)]).is_err());
+    assert!(Cages::new(vec![Cage {
+      locs: [L11, L12].into_iter().collect(),
+      sum: 18,
+      distinct: false,
+    }])
+    .is_ok());
+  }
+}
@@ -0,0 +1,215 @@
+//! Defines the CandidateGrid type, representing a Sudoku grid as the set of
+//! numerals still possible in each location.  This is the form Sukaku puzzles
+//! take, and the form a partial analysis of any puzzle takes.
+
+use std::fmt;
+use std::str::FromStr;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::*;
+
+/// A Sudoku grid given as candidates: for each location, the numerals that
+/// might still go there.  A location with a single candidate counts as
+/// assigned.  Unlike a `Grid`, this can represent a Sukaku puzzle, or a puzzle
+/// partway through a solver's eliminations.
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+#[wasm_bindgen]
+pub struct CandidateGrid(AsgmtSet);
+
+#[wasm_bindgen]
+impl CandidateGrid {
+  /// Makes a CandidateGrid with every numeral possible in every location.
+  pub fn new() -> CandidateGrid {
+    CandidateGrid(AsgmtSet::all())
+  }
+
+  /// Makes a CandidateGrid holding the candidates consistent with the given
+  /// grid's numerals.
+  #[wasm_bindgen(js_name = "fromGrid")]
+  pub fn from_grid(grid: &Grid) -> CandidateGrid {
+    CandidateGrid(AsgmtSet::possibles_from_grid(grid))
+  }
+
+  /// Constructs a CandidateGrid from a string of 729 pencil marks.
+  #[wasm_bindgen(js_name = "newFromString")]
+  pub fn new_from_string(s: &str) -> Option<CandidateGrid> {
+    CandidateGrid::from_str(s).ok()
+  }
+
+  /// Renders the candidates as 729 pencil marks.
+  #[wasm_bindgen(js_name = "toString")]
+  pub fn to_string_js(&self) -> String {
+    self.to_string()
+  }
+
+  /// Tells whether the given numeral is a candidate for the given location.
+  /// The arguments are the numeral, 1 through 9, and the location's index, 0
+  /// through 80.
+  #[wasm_bindgen(js_name = "hasCandidate")]
+  pub fn has_candidate(&self, num: i8, loc: u8) -> bool {
+    match (Num::new(num), Loc::from_index(loc as usize)) {
+      (Some(num), Some(loc)) => self.contains(Asgmt::new(num, loc)),
+      _ => false,
+    }
+  }
+
+  /// Converts this to a grid, filling in the locations that have a single
+  /// candidate.
+  #[wasm_bindgen(js_name = "toGrid")]
+  pub fn to_grid(&self) -> Grid {
+    self.0.to_grid()
+  }
+}
+
+impl CandidateGrid {
+  /// Makes a CandidateGrid holding the given assignments as candidates.
+  pub fn from_asgmts(asgmts: AsgmtSet) -> CandidateGrid {
+    CandidateGrid(asgmts)
+  }
+
+  /// The candidates, as a set of assignments.
+  pub fn asgmts(&self) -> AsgmtSet {
+    self.0
+  }
+
+  /// The candidates for the given location.
+  pub fn candidates(&self, loc: Loc) -> NumSet {
+    Num::all()
+      .filter(|&num| self.contains(Asgmt::new(num, loc)))
+      .collect()
+  }
+
+  /// Tells whether the given assignment is a candidate.
+  pub fn contains(&self, asgmt: Asgmt) -> bool {
+    self.0.contains(asgmt)
+  }
+
+  /// Removes the given assignment from the candidates.  Returns whether it was
+  /// a candidate.
+  pub fn remove(&mut self, asgmt: Asgmt) -> bool {
+    self.0.remove(asgmt)
+  }
+}
+
+impl Default for CandidateGrid {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl From<AsgmtSet> for CandidateGrid {
+  fn from(asgmts: AsgmtSet) -> Self {
+    Self::from_asgmts(asgmts)
+  }
+}
+
+impl fmt::Display for CandidateGrid {
+  /// Prints the candidates as 729 pencil marks: nine characters per location,
+  /// in row-major order, with the digit itself for a candidate and `.` for a
+  /// non-candidate.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for loc in Loc::all() {
+      for num in Num::all() {
+        if self.contains(Asgmt::new(num, loc)) {
+          num.get().fmt(f)?;
+        } else {
+          '.'.fmt(f)?;
+        }
+      }
+    }
+    Ok(())
+  }
+}
+
+impl fmt::Debug for CandidateGrid {
+  /// Prints the candidates as a pencil-mark grid.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let cell = |loc: Loc| {
+      let digits: String = self
+        .candidates(loc)
+        .iter()
+        .map(|n| n.get().to_string())
+        .collect();
+      format!("{:<9}", digits)
+    };
+    let row = |r: usize| {
+      let cells: Vec<_> = (0..9)
+        .map(|c| cell(Loc::from_index(r * 9 + c).unwrap()))
+        .collect();
+      [&cells[0..3], &cells[3..6], &cells[6..9]]
+        .map(|blk| blk.join(" "))
+        .join(" | ")
+    };
+    let band = |b: usize| [row(b * 3), row(b * 3 + 1), row(b * 3 + 2)].join("\n");
+    let rule = ["-".repeat(29), "-".repeat(29), "-".repeat(29)].join("-+-");
+    f.write_str(&[band(0), band(1), band(2)].join(&format!("\n{}\n", rule)))
+  }
+}
+
+impl FromStr for CandidateGrid {
+  type Err = String;
+
+  /// Constructs a CandidateGrid from a string, which must contain exactly 729
+  /// pencil-mark characters, plus any number of whitespace characters.
+  ///
+  /// The pencil marks come nine to a location, in row-major order.  Each is
+  /// either the digit for its position, signifying a candidate, or `0` or
+  /// `.`, signifying a non-candidate.  So this method correctly parses the
+  /// Display form back into the original candidates.
+  fn from_str(s: &str) -> Result<CandidateGrid, String> {
+    let mut asgmts = AsgmtSet::new();
+    let mut i = 0;
+    for c in s.chars().filter(|c| !c.is_whitespace()) {
+      if i >= 729 {
+        return Err(format!("More than 729 pencil marks in {}", s));
+      }
+      let num = Num::from_index(i % 9).unwrap();
+      match c {
+        '0' | '.' => {}
+        _ if c.to_digit(10) == Some(num.get() as u32) => {
+          asgmts.insert(Asgmt::new(num, Loc::from_index(i / 9).unwrap()));
+        }
+        _ => return Err(format!("Unexpected {:?} in the place of {}", c, num.get())),
+      }
+      i += 1;
+    }
+    if i == 729 {
+      Ok(CandidateGrid(asgmts))
+    } else {
+      Err(format!("Fewer than 729 pencil marks in {}", s))
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const CLUES: &str =
+    ".1..5..8.4.89.62.1..6...7....5.3.9.....8.7.....1.4.3....4...1..2.93.16.7.7..6..2.";
+
+  #[test]
+  fn round_trip() {
+    let grid: Grid = CLUES.parse().unwrap();
+    let mut candidates = CandidateGrid::from_grid(&grid);
+    candidates.remove(Asgmt::new(N3, L11));
+    candidates.remove(Asgmt::new(N7, L11));
+    let text = candidates.to_string();
+    assert_eq!(729, text.len());
+    assert_eq!(candidates, text.parse().unwrap());
+    assert_eq!(11, format!("{:?}", candidates).lines().count());
+    assert!(!candidates.has_candidate(3, 0));
+    assert!(candidates.has_candidate(9, 0));
+    // Removing 3 and 7 leaves 9 as the only candidate for L11.
+    assert_eq!(Some(N9), candidates.to_grid()[L11]);
+    assert_eq!(Some(N1), candidates.to_grid()[L12]);
+  }
+
+  #[test]
+  fn errors() {
+    assert!("1".repeat(729).parse::<CandidateGrid>().is_err());
+    assert!("123456789".repeat(80).parse::<CandidateGrid>().is_err());
+    assert!("123456789".repeat(82).parse::<CandidateGrid>().is_err());
+    assert!("123456789".repeat(81).parse::<CandidateGrid>().is_ok());
+  }
+}
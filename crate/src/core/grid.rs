@@ -135,9 +135,14 @@ impl Grid {
 
   /// This grid's state: solved, incomplete, or broken.
   pub fn state<'a>(&'a self) -> GridState<'a> {
+    self.variant_state(Variant::Standard)
+  }
+
+  /// This grid's state under the given variant's rules.
+  pub fn variant_state<'a>(&'a self, variant: Variant) -> GridState<'a> {
     let mut broken = LocSet::new();
     let asgmts = AsgmtSet::simple_from_grid(self);
-    for unit in Unit::all() {
+    for unit in variant.units() {
       let unit_locs = unit.locs();
       for num in Num::all() {
         let locs = asgmts.num_locs(num) & unit_locs;
@@ -147,6 +152,15 @@ impl Grid {
         }
       }
     }
+    for relation in variant.extra_relations() {
+      for asgmt in self.iter() {
+        let clashes = relation.exclusions(asgmt) & asgmts;
+        if !clashes.is_empty() {
+          // A related location holds a numeral this one excludes.
+          broken.insert(asgmt.loc);
+        }
+      }
+    }
     if broken.is_empty() {
       if self.len() == 81 {
         GridState::Solved(self)
@@ -472,6 +486,15 @@ mod tests {
     )
     .unwrap();
     assert_eq!(GridState::Incomplete, g.state());
+    assert_eq!(GridState::Incomplete, g.variant_state(Variant::AntiKing));
+    assert_eq!(
+      GridState::Broken(loc_set! {L36, L55, L67, L85, L86, L93}),
+      g.variant_state(Variant::AntiKnight)
+    );
+    assert_eq!(
+      GridState::Broken(loc_set! {L22, L23, L54, L55}),
+      g.variant_state(Variant::NonConsecutive)
+    );
     let g = Grid::from_str(
       "123456789456789123789123456234567891567891234891234567345678912678912345912345678",
     )
@@ -0,0 +1,168 @@
+//! Defines the PeerRelation type, which generalizes the standard notion of
+//! peers: a rule that relates each location to others, such that assigning a
+//! numeral to the location rules out some numerals at the related ones.
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use super::*;
+
+/// A way that an assignment rules out assignments at other locations.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, ts_rs::TS)]
+#[ts(export, export_to = "../../www/src/facts/")]
+pub enum PeerRelation {
+  /// Locations that share a row, column or block can't have the same
+  /// numeral: the standard rule.
+  Unit,
+  /// Locations a chess knight's move apart can't have the same numeral.
+  Knight,
+  /// Locations a chess king's move apart, including diagonally, can't have
+  /// the same numeral.
+  King,
+  /// Orthogonally adjacent locations can't have consecutive numerals.
+  NonConsecutive,
+}
+
+impl PeerRelation {
+  /// The locations related to the given one.  For `Unit`, these are its
+  /// standard peers.
+  pub fn locs(self, loc: Loc) -> LocSet {
+    match self {
+      PeerRelation::Unit => loc.peers(),
+      PeerRelation::Knight => KNIGHT_LOCS[loc.index()],
+      PeerRelation::King => KING_LOCS[loc.index()],
+      PeerRelation::NonConsecutive => ORTHOGONAL_LOCS[loc.index()],
+    }
+  }
+
+  /// The complement of the locations related to the given one, for removing
+  /// them with an `&` operation.  `masks::loc_to_zeroed_peers` is the faster
+  /// equivalent for `Unit`.
+  pub fn zeroed_locs(self, loc: Loc) -> LocSet {
+    !self.locs(loc)
+  }
+
+  /// Tells whether related locations can't share a numeral, as opposed to
+  /// ruling out other numerals.
+  pub fn forbids_sharing(self) -> bool {
+    self != PeerRelation::NonConsecutive
+  }
+
+  /// The numerals that assigning the given one rules out at related
+  /// locations.
+  pub fn excluded_nums(self, num: Num) -> NumSet {
+    match self {
+      PeerRelation::Unit | PeerRelation::Knight | PeerRelation::King => num.as_set(),
+      PeerRelation::NonConsecutive => [num.get() - 1, num.get() + 1]
+        .into_iter()
+        .filter_map(Num::new)
+        .collect(),
+    }
+  }
+
+  /// The assignments that the given one rules out under this relation.
+  pub fn exclusions(self, asgmt: Asgmt) -> AsgmtSet {
+    let mut answer = AsgmtSet::new();
+    let locs = self.locs(asgmt.loc);
+    for num in self.excluded_nums(asgmt.num).iter() {
+      answer.union_in_place(num, locs);
+    }
+    answer
+  }
+}
+
+/// Makes a table of each location's related locations, the ones at the given
+/// row and column offsets from it.
+fn offset_locs(offsets: &[(i8, i8)]) -> [LocSet; 81] {
+  let mut answer = [LocSet::new(); 81];
+  for loc in Loc::all() {
+    let (row, col) = (loc.row().get(), loc.col().get());
+    for &(dr, dc) in offsets {
+      if let (Some(row), Some(col)) = (Row::new(row + dr), Col::new(col + dc)) {
+        answer[loc.index()].insert(Loc::at(row, col));
+      }
+    }
+  }
+  answer
+}
+
+static KNIGHT_LOCS: Lazy<[LocSet; 81]> = Lazy::new(|| {
+  offset_locs(&[
+    (-2, -1),
+    (-2, 1),
+    (-1, -2),
+    (-1, 2),
+    (1, -2),
+    (1, 2),
+    (2, -1),
+    (2, 1),
+  ])
+});
+
+static KING_LOCS: Lazy<[LocSet; 81]> = Lazy::new(|| {
+  offset_locs(&[
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+  ])
+});
+
+static ORTHOGONAL_LOCS: Lazy<[LocSet; 81]> =
+  Lazy::new(|| offset_locs(&[(-1, 0), (0, -1), (0, 1), (1, 0)]));
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_locs() {
+    assert_eq!(2, PeerRelation::Knight.locs(L11).len());
+    assert_eq!(8, PeerRelation::Knight.locs(L55).len());
+    assert!(PeerRelation::Knight.locs(L55).contains(L74));
+    assert_eq!(3, PeerRelation::King.locs(L11).len());
+    assert_eq!(8, PeerRelation::King.locs(L55).len());
+    assert_eq!(4, PeerRelation::NonConsecutive.locs(L55).len());
+    for relation in [
+      PeerRelation::Unit,
+      PeerRelation::Knight,
+      PeerRelation::King,
+      PeerRelation::NonConsecutive,
+    ] {
+      for loc in Loc::all() {
+        assert!(!relation.locs(loc).contains(loc));
+        // The relations are symmetric.
+        for other in relation.locs(loc).iter() {
+          assert!(relation.locs(other).contains(loc));
+        }
+      }
+    }
+    for loc in Loc::all() {
+      assert_eq!(
+        masks::loc_to_zeroed_peers(loc),
+        PeerRelation::Unit.zeroed_locs(loc)
+      );
+    }
+  }
+
+  #[test]
+  fn test_excluded_nums() {
+    let n = |n| Num::new(n).unwrap();
+    assert_eq!(n(5).as_set(), PeerRelation::King.excluded_nums(n(5)));
+    assert_eq!(
+      [n(4), n(6)].into_iter().collect::<NumSet>(),
+      PeerRelation::NonConsecutive.excluded_nums(n(5))
+    );
+    assert_eq!(
+      n(2).as_set(),
+      PeerRelation::NonConsecutive.excluded_nums(n(1))
+    );
+    let exclusions = PeerRelation::NonConsecutive.exclusions(Asgmt::new(n(9), L55));
+    assert_eq!(4, exclusions.num_locs(n(8)).len());
+    assert!(exclusions.num_locs(n(9)).is_empty());
+  }
+}
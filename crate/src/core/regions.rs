@@ -0,0 +1,268 @@
+//! Defines the Regions type, which lays out the 9 regions of a Jigsaw Sudoku:
+//! the irregular units that take the place of the standard blocks.
+
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+use super::*;
+use crate::define_id_types;
+
+define_id_types! {
+    /// Identifies one of the 9 regions of a Jigsaw Sudoku layout.
+    ///
+    /// In the standard layout, the regions are the blocks, numbered the same
+    /// way.
+    #[derive(Debug)]
+    Region: i8[9];
+}
+
+impl fmt::Display for Region {
+  /// Prints this region as Jn, where n is the ordinal number of the region.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "J{}", self.ordinal())
+  }
+}
+
+/// A layout of the grid into 9 regions of 9 orthogonally connected locations
+/// each.  A Jigsaw Sudoku's solution has every numeral once in each row,
+/// column and region.
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub struct Regions {
+  /// The region each location belongs to.
+  regions: [Region; 81],
+  /// The locations in each region.
+  locs: [LocSet; 9],
+}
+
+impl Regions {
+  /// The standard layout, whose regions are the blocks.
+  pub fn standard() -> Self {
+    let mut regions = [Region(0); 81];
+    for loc in Loc::all() {
+      regions[loc.index()] = Region(loc.blk().get());
+    }
+    Self::new(regions).unwrap()
+  }
+
+  /// Makes a layout from the region of each location, failing unless every
+  /// region has 9 locations that are orthogonally connected.
+  pub fn new(regions: [Region; 81]) -> Result<Self, String> {
+    let mut locs = [LocSet::new(); 9];
+    for loc in Loc::all() {
+      locs[regions[loc.index()].index()].insert(loc);
+    }
+    for region in Region::all() {
+      let region_locs = locs[region.index()];
+      if region_locs.len() != 9 {
+        return Err(format!(
+          "Region {} has {} locations, not 9",
+          region,
+          region_locs.len()
+        ));
+      }
+      if !is_connected(region_locs) {
+        return Err(format!("Region {} is not connected", region));
+      }
+    }
+    Ok(Self { regions, locs })
+  }
+
+  /// The region the given location belongs to.
+  pub fn region(&self, loc: Loc) -> Region {
+    self.regions[loc.index()]
+  }
+
+  /// The locations of the given region.
+  pub fn locs(&self, region: Region) -> LocSet {
+    self.locs[region.index()]
+  }
+
+  /// The region of each location.
+  pub fn to_array(&self) -> [Region; 81] {
+    self.regions
+  }
+
+  /// Tells whether this is the standard layout, with the same regions as the
+  /// blocks.  (They may be numbered differently.)
+  pub fn is_standard(&self) -> bool {
+    self.locs.iter().all(|locs| {
+      let blk = locs.smallest_item().unwrap().blk();
+      *locs == blk.locs()
+    })
+  }
+
+  /// Iterates the units of this layout: the rows, the columns, and the regions.
+  pub fn units(&self) -> impl Iterator<Item = LocSet> + '_ {
+    Row::all()
+      .map(|row| row.locs())
+      .chain(Col::all().map(|col| col.locs()))
+      .chain(self.locs.iter().copied())
+  }
+
+  /// The given location's peers under this layout: the other locations in its
+  /// row, column and region.
+  pub fn peers(&self, loc: Loc) -> LocSet {
+    let mut peers = loc.row().locs() | loc.col().locs() | self.locs(self.region(loc));
+    peers.remove(loc);
+    peers
+  }
+}
+
+impl Default for Regions {
+  fn default() -> Self {
+    Self::standard()
+  }
+}
+
+/// Tells whether the given locations form a single, orthogonally connected
+/// shape.  The empty set doesn't count.
+pub fn is_connected(locs: LocSet) -> bool {
+  let Some(start) = locs.smallest_item() else {
+    return false;
+  };
+  let mut reached = start.as_set();
+  let mut frontier = vec![start];
+  while let Some(loc) = frontier.pop() {
+    for neighbor in orthogonal_neighbors(loc) {
+      if locs.contains(neighbor) && reached.insert(neighbor) {
+        frontier.push(neighbor);
+      }
+    }
+  }
+  reached == locs
+}
+
+/// Iterates the locations directly above, below, left and right of the given
+/// one.
+pub fn orthogonal_neighbors(loc: Loc) -> impl Iterator<Item = Loc> {
+  let (row, col) = (loc.row().get(), loc.col().get());
+  [(-1, 0), (1, 0), (0, -1), (0, 1)]
+    .into_iter()
+    .filter_map(move |(dr, dc)| {
+      let row = Row::new(row + dr)?;
+      let col = Col::new(col + dc)?;
+      Some(Loc::at(row, col))
+    })
+}
+
+impl fmt::Display for Regions {
+  /// Prints each location's region ordinal, in row-major order.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for region in self.regions {
+      region.ordinal().fmt(f)?;
+    }
+    Ok(())
+  }
+}
+
+impl fmt::Debug for Regions {
+  /// Prints the region ordinals as 9 lines of 9.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let flat = self.to_string();
+    let lines: Vec<&str> = (0..9).map(|i| &flat[i * 9..i * 9 + 9]).collect();
+    f.write_str(&lines.join("\n"))
+  }
+}
+
+impl FromStr for Regions {
+  type Err = String;
+
+  /// Parses a layout from a string with exactly 81 region ordinals, `1`
+  /// through `9`, ignoring all other characters.  This means that both the
+  /// Display and Debug forms parse back into the original layout.
+  fn from_str(s: &str) -> Result<Self, String> {
+    let mut regions = [Region(0); 81];
+    let mut i = 0;
+    for c in s.chars() {
+      if ('1'..='9').contains(&c) {
+        if i >= Loc::COUNT {
+          return Err(format!("More than 81 locations in {}", s));
+        }
+        regions[i] = Region(c.to_digit(10).unwrap() as i8 - 1);
+        i += 1;
+      }
+    }
+    if i < Loc::COUNT {
+      return Err(format!("Only {} locations in {}", i, s));
+    }
+    Self::new(regions)
+  }
+}
+
+impl Serialize for Regions {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&self.to_string())
+  }
+}
+
+/// The standard layout with two pairs of locations traded between regions,
+/// for tests that need a layout that isn't the standard one.
+#[cfg(test)]
+pub(crate) const TRADED_REGIONS: &str = "
+    111222333
+    111222333
+    114222333
+    144555666
+    444555666
+    444555669
+    777888699
+    777888999
+    777888999";
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_standard() {
+    let regions = Regions::standard();
+    assert!(regions.is_standard());
+    for loc in Loc::all() {
+      assert_eq!(loc.peers(), regions.peers(loc));
+      assert_eq!(loc.blk().get(), regions.region(loc).get());
+    }
+    assert_eq!(27, regions.units().count());
+  }
+
+  #[test]
+  fn test_parsing() {
+    let regions: Regions = TRADED_REGIONS.parse().unwrap();
+    assert!(!regions.is_standard());
+    assert_eq!(regions, regions.to_string().parse().unwrap());
+    assert_eq!(regions, format!("{:?}", regions).parse().unwrap());
+    assert_eq!(Region::new(0), Some(regions.region(L31)));
+    assert_eq!(Region::new(3), Some(regions.region(L33)));
+    assert!(regions.peers(L33).contains(L42));
+    assert!(!regions.peers(L33).contains(L22));
+  }
+
+  #[test]
+  fn test_validation() {
+    assert!("123".parse::<Regions>().is_err());
+    // Region 1 has 10 locations and region 2 has 8.
+    let too_big = TRADED_REGIONS.replacen('2', "1", 1);
+    assert!(too_big
+      .parse::<Regions>()
+      .unwrap_err()
+      .contains("10 locations"));
+    // Swapping the two corners leaves both regions disconnected.
+    let mut chars: Vec<char> = Regions::standard().to_string().chars().collect();
+    chars.swap(0, 80);
+    let swapped: String = chars.into_iter().collect();
+    assert!(swapped
+      .parse::<Regions>()
+      .unwrap_err()
+      .contains("not connected"));
+  }
+
+  #[test]
+  fn test_is_connected() {
+    assert!(is_connected(L11.as_set()));
+    assert!(is_connected([L11, L12, L22].into_iter().collect()));
+    assert!(!is_connected([L11, L22].into_iter().collect()));
+    assert!(!is_connected(LocSet::new()));
+    assert_eq!(2, orthogonal_neighbors(L11).count());
+    assert_eq!(4, orthogonal_neighbors(L55).count());
+  }
+}
@@ -0,0 +1,298 @@
+//! Vectorized operations on the 27 words that back a `Bits9x3x27`, which is
+//! the representation of an `AsgmtSet`.
+//!
+//! With the `simd` feature enabled, these use SSE2 (or AVX2, when the target
+//! supports it) on x86_64, and `simd128` on wasm32 when the target supports
+//! it.  Everywhere else, and without the feature, they fall back to plain
+//! loops.
+
+/// The words backing a `Bits9x3x27`: one per numeral and band.
+pub type Words = [u32; 27];
+
+/// Computes `a &= b`.
+#[inline]
+pub fn and_assign(a: &mut Words, b: &Words) {
+  imp::and_assign(a, b)
+}
+
+/// Computes `a |= b`.
+#[inline]
+pub fn or_assign(a: &mut Words, b: &Words) {
+  imp::or_assign(a, b)
+}
+
+/// Computes `a ^= b`.
+#[inline]
+pub fn xor_assign(a: &mut Words, b: &Words) {
+  imp::xor_assign(a, b)
+}
+
+/// Returns a mask with bit `i` set when `a[i] != b[i]`.
+#[inline]
+pub fn diff_mask(a: &Words, b: &Words) -> u32 {
+  imp::diff_mask(a, b)
+}
+
+/// The plain-loop versions, used directly when there's no vectorized
+/// version, and for the leftover words when there is.
+mod scalar {
+  use super::Words;
+
+  #[inline]
+  pub fn and_assign_from(start: usize, a: &mut Words, b: &Words) {
+    for i in start..27 {
+      a[i] &= b[i];
+    }
+  }
+
+  #[inline]
+  pub fn or_assign_from(start: usize, a: &mut Words, b: &Words) {
+    for i in start..27 {
+      a[i] |= b[i];
+    }
+  }
+
+  #[inline]
+  pub fn xor_assign_from(start: usize, a: &mut Words, b: &Words) {
+    for i in start..27 {
+      a[i] ^= b[i];
+    }
+  }
+
+  #[inline]
+  pub fn diff_mask_from(start: usize, a: &Words, b: &Words) -> u32 {
+    let mut mask = 0;
+    for i in start..27 {
+      mask |= ((a[i] != b[i]) as u32) << i;
+    }
+    mask
+  }
+}
+
+#[cfg(not(any(
+  all(feature = "simd", target_arch = "x86_64"),
+  all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"),
+)))]
+mod imp {
+  use super::scalar::*;
+  use super::Words;
+
+  #[inline]
+  pub fn and_assign(a: &mut Words, b: &Words) {
+    and_assign_from(0, a, b)
+  }
+
+  #[inline]
+  pub fn or_assign(a: &mut Words, b: &Words) {
+    or_assign_from(0, a, b)
+  }
+
+  #[inline]
+  pub fn xor_assign(a: &mut Words, b: &Words) {
+    xor_assign_from(0, a, b)
+  }
+
+  #[inline]
+  pub fn diff_mask(a: &Words, b: &Words) -> u32 {
+    diff_mask_from(0, a, b)
+  }
+}
+
+/// Processes the words 8 at a time, leaving 3 for the scalar code.
+#[cfg(all(feature = "simd", target_arch = "x86_64", target_feature = "avx2"))]
+mod imp {
+  use super::scalar::*;
+  use super::Words;
+  use std::arch::x86_64::*;
+
+  const LANES: usize = 8;
+  const VECTORS: usize = 27 / LANES;
+
+  macro_rules! binary_op {
+    ($name:ident, $intrinsic:ident, $tail:ident) => {
+      #[inline]
+      pub fn $name(a: &mut Words, b: &Words) {
+        // Safe because the loop stays within the arrays, the loads and
+        // stores are unaligned, and the target supports AVX2.
+        unsafe {
+          for i in 0..VECTORS {
+            let pa = a.as_mut_ptr().add(i * LANES) as *mut __m256i;
+            let pb = b.as_ptr().add(i * LANES) as *const __m256i;
+            let v = $intrinsic(_mm256_loadu_si256(pa), _mm256_loadu_si256(pb));
+            _mm256_storeu_si256(pa, v);
+          }
+        }
+        $tail(VECTORS * LANES, a, b)
+      }
+    };
+  }
+
+  binary_op!(and_assign, _mm256_and_si256, and_assign_from);
+  binary_op!(or_assign, _mm256_or_si256, or_assign_from);
+  binary_op!(xor_assign, _mm256_xor_si256, xor_assign_from);
+
+  #[inline]
+  pub fn diff_mask(a: &Words, b: &Words) -> u32 {
+    let mut equal = 0u32;
+    // Safe because the loop stays within the arrays, the loads are
+    // unaligned, and the target supports AVX2.
+    unsafe {
+      for i in 0..VECTORS {
+        let va = _mm256_loadu_si256(a.as_ptr().add(i * LANES) as *const __m256i);
+        let vb = _mm256_loadu_si256(b.as_ptr().add(i * LANES) as *const __m256i);
+        let eq = _mm256_movemask_ps(_mm256_castsi256_ps(_mm256_cmpeq_epi32(va, vb)));
+        equal |= (eq as u32) << (i * LANES);
+      }
+    }
+    (!equal & ((1 << (VECTORS * LANES)) - 1)) | diff_mask_from(VECTORS * LANES, a, b)
+  }
+}
+
+/// Processes the words 4 at a time, leaving 3 for the scalar code.  SSE2 is
+/// part of the x86_64 baseline, so this needs no runtime detection.
+#[cfg(all(feature = "simd", target_arch = "x86_64", not(target_feature = "avx2")))]
+mod imp {
+  use super::scalar::*;
+  use super::Words;
+  use std::arch::x86_64::*;
+
+  const LANES: usize = 4;
+  const VECTORS: usize = 27 / LANES;
+
+  macro_rules! binary_op {
+    ($name:ident, $intrinsic:ident, $tail:ident) => {
+      #[inline]
+      pub fn $name(a: &mut Words, b: &Words) {
+        // Safe because the loop stays within the arrays, the loads and
+        // stores are unaligned, and SSE2 is always available on x86_64.
+        unsafe {
+          for i in 0..VECTORS {
+            let pa = a.as_mut_ptr().add(i * LANES) as *mut __m128i;
+            let pb = b.as_ptr().add(i * LANES) as *const __m128i;
+            let v = $intrinsic(_mm_loadu_si128(pa), _mm_loadu_si128(pb));
+            _mm_storeu_si128(pa, v);
+          }
+        }
+        $tail(VECTORS * LANES, a, b)
+      }
+    };
+  }
+
+  binary_op!(and_assign, _mm_and_si128, and_assign_from);
+  binary_op!(or_assign, _mm_or_si128, or_assign_from);
+  binary_op!(xor_assign, _mm_xor_si128, xor_assign_from);
+
+  #[inline]
+  pub fn diff_mask(a: &Words, b: &Words) -> u32 {
+    let mut equal = 0u32;
+    // Safe because the loop stays within the arrays, the loads are
+    // unaligned, and SSE2 is always available on x86_64.
+    unsafe {
+      for i in 0..VECTORS {
+        let va = _mm_loadu_si128(a.as_ptr().add(i * LANES) as *const __m128i);
+        let vb = _mm_loadu_si128(b.as_ptr().add(i * LANES) as *const __m128i);
+        let eq = _mm_movemask_ps(_mm_castsi128_ps(_mm_cmpeq_epi32(va, vb)));
+        equal |= (eq as u32) << (i * LANES);
+      }
+    }
+    (!equal & ((1 << (VECTORS * LANES)) - 1)) | diff_mask_from(VECTORS * LANES, a, b)
+  }
+}
+
+/// Processes the words 4 at a time, leaving 3 for the scalar code.
+#[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+mod imp {
+  use super::scalar::*;
+  use super::Words;
+  use std::arch::wasm32::*;
+
+  const LANES: usize = 4;
+  const VECTORS: usize = 27 / LANES;
+
+  macro_rules! binary_op {
+    ($name:ident, $intrinsic:ident, $tail:ident) => {
+      #[inline]
+      pub fn $name(a: &mut Words, b: &Words) {
+        // Safe because the loop stays within the arrays, and wasm's loads and
+        // stores needn't be aligned.
+        unsafe {
+          for i in 0..VECTORS {
+            let pa = a.as_mut_ptr().add(i * LANES) as *mut v128;
+            let pb = b.as_ptr().add(i * LANES) as *const v128;
+            v128_store(pa, $intrinsic(v128_load(pa), v128_load(pb)));
+          }
+        }
+        $tail(VECTORS * LANES, a, b)
+      }
+    };
+  }
+
+  binary_op!(and_assign, v128_and, and_assign_from);
+  binary_op!(or_assign, v128_or, or_assign_from);
+  binary_op!(xor_assign, v128_xor, xor_assign_from);
+
+  #[inline]
+  pub fn diff_mask(a: &Words, b: &Words) -> u32 {
+    let mut equal = 0u32;
+    // Safe because the loop stays within the arrays, and wasm's loads needn't
+    // be aligned.
+    unsafe {
+      for i in 0..VECTORS {
+        let va = v128_load(a.as_ptr().add(i * LANES) as *const v128);
+        let vb = v128_load(b.as_ptr().add(i * LANES) as *const v128);
+        equal |= (i32x4_bitmask(i32x4_eq(va, vb)) as u32) << (i * LANES);
+      }
+    }
+    (!equal & ((1 << (VECTORS * LANES)) - 1)) | diff_mask_from(VECTORS * LANES, a, b)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::random::*;
+
+  fn random_words(random: &mut Random) -> Words {
+    let mut words = [0; 27];
+    for w in words.iter_mut() {
+      *w = random.next_u32() & ((1 << 27) - 1);
+    }
+    words
+  }
+
+  #[test]
+  fn test_ops_match_scalar() {
+    let mut random = new_random("simd");
+    for _ in 0..100 {
+      let a = random_words(&mut random);
+      let mut b = random_words(&mut random);
+      // Make some of the words match.
+      for i in 0..27 {
+        if random.random_bool(0.5) {
+          b[i] = a[i];
+        }
+      }
+
+      let mut expected = a;
+      scalar::and_assign_from(0, &mut expected, &b);
+      let mut actual = a;
+      and_assign(&mut actual, &b);
+      assert_eq!(expected, actual);
+
+      let mut expected = a;
+      scalar::or_assign_from(0, &mut expected, &b);
+      let mut actual = a;
+      or_assign(&mut actual, &b);
+      assert_eq!(expected, actual);
+
+      let mut expected = a;
+      scalar::xor_assign_from(0, &mut expected, &b);
+      let mut actual = a;
+      xor_assign(&mut actual, &b);
+      assert_eq!(expected, actual);
+
+      let expected = (0..27).fold(0, |m, i| m | (((a[i] != b[i]) as u32) << i));
+      assert_eq!(expected, diff_mask(&a, &b));
+    }
+  }
+}
@@ -0,0 +1,416 @@
+//! Defines SizedGrid, a Sudoku board whose size is set by its blocks'
+//! dimensions: 4x4 and 6x6 boards for kids, 12x12 and 16x16 ones for
+//! enthusiasts.  The 9x9 types elsewhere in `core` are tuned to their size,
+//! and remain the ones to use for standard Sudoku.
+
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+use super::*;
+
+/// The most locations a SizedGrid can have: those of a 16x16 board.
+pub const MAX_SIZED_CELLS: usize = 256;
+
+/// A Sudoku board whose blocks have `BR` rows and `BC` columns.  Its side, and
+/// its number of numerals, is `BR * BC`, which can be at most 16.  Locations
+/// are identified by their row-major indices, called cells to set them apart
+/// from the 9x9 `Loc`.
+#[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SizedGrid<const BR: usize, const BC: usize> {
+  /// Each cell's numeral, with 0 meaning unassigned.
+  cells: [u8; MAX_SIZED_CELLS],
+}
+
+/// A 4x4 board, with 2x2 blocks.
+pub type Grid4 = SizedGrid<2, 2>;
+/// A 6x6 board, with 2x3 blocks.
+pub type Grid6 = SizedGrid<2, 3>;
+/// A 12x12 board, with 3x4 blocks.
+pub type Grid12 = SizedGrid<3, 4>;
+/// A 16x16 board, with 4x4 blocks.
+pub type Grid16 = SizedGrid<4, 4>;
+
+/// One of a SizedGrid's units, identified by its 0-based index.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(tag = "type", content = "id")]
+pub enum SizedUnit {
+  Row(usize),
+  Col(usize),
+  Blk(usize),
+}
+
+impl<const BR: usize, const BC: usize> SizedGrid<BR, BC> {
+  /// The board's side, which is also its number of numerals.
+  pub const SIZE: usize = {
+    assert!(BR > 0 && BC > 0 && BR * BC <= 16);
+    BR * BC
+  };
+  /// The board's number of locations.
+  pub const CELLS: usize = Self::SIZE * Self::SIZE;
+
+  /// Makes an empty board.
+  pub fn new() -> Self {
+    Self {
+      cells: [0; MAX_SIZED_CELLS],
+    }
+  }
+
+  /// The numeral at the given cell, if any.
+  pub fn get(&self, cell: usize) -> Option<u8> {
+    debug_assert!(cell < Self::CELLS);
+    match self.cells[cell] {
+      0 => None,
+      num => Some(num),
+    }
+  }
+
+  /// Assigns or clears the given cell.
+  pub fn set(&mut self, cell: usize, num: Option<u8>) {
+    debug_assert!(cell < Self::CELLS);
+    debug_assert!(num.is_none_or(|num| (1..=Self::SIZE as u8).contains(&num)));
+    self.cells[cell] = num.unwrap_or(0);
+  }
+
+  /// The number of assigned cells.
+  pub fn len(&self) -> usize {
+    self.cells[..Self::CELLS]
+      .iter()
+      .filter(|n| **n != 0)
+      .count()
+  }
+
+  /// Tells whether no cell is assigned.
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// Iterates the assigned cells and their numerals.
+  pub fn iter(&self) -> impl Iterator<Item = (usize, u8)> + '_ {
+    (0..Self::CELLS).filter_map(|cell| self.get(cell).map(|num| (cell, num)))
+  }
+
+  /// Iterates all the units: rows, then columns, then blocks.
+  pub fn units() -> impl Iterator<Item = SizedUnit> {
+    (0..Self::SIZE)
+      .map(SizedUnit::Row)
+      .chain((0..Self::SIZE).map(SizedUnit::Col))
+      .chain((0..Self::SIZE).map(SizedUnit::Blk))
+  }
+
+  /// The cells of the given unit, in row-major order.
+  pub fn unit_cells(unit: SizedUnit) -> Vec<usize> {
+    let size = Self::SIZE;
+    match unit {
+      SizedUnit::Row(row) => (0..size).map(|col| row * size + col).collect(),
+      SizedUnit::Col(col) => (0..size).map(|row| row * size + col).collect(),
+      SizedUnit::Blk(blk) => {
+        // There are BR blocks across each band of BR rows, and BC down each
+        // stack of BC columns.
+        let (top, left) = (blk / BR * BR, blk % BR * BC);
+        (0..size)
+          .map(|i| (top + i / BC) * size + left + i % BC)
+          .collect()
+      }
+    }
+  }
+
+  /// The units the given cell belongs to: its row, column and block.
+  pub fn cell_units(cell: usize) -> [SizedUnit; 3] {
+    let (row, col) = (cell / Self::SIZE, cell % Self::SIZE);
+    [
+      SizedUnit::Row(row),
+      SizedUnit::Col(col),
+      SizedUnit::Blk(row / BR * BR + col / BC),
+    ]
+  }
+
+  /// The other cells that share a unit with the given one.
+  pub fn peers(cell: usize) -> Vec<usize> {
+    let mut answer: Vec<usize> = Self::cell_units(cell)
+      .into_iter()
+      .flat_map(Self::unit_cells)
+      .filter(|other| *other != cell)
+      .collect();
+    answer.sort();
+    answer.dedup();
+    answer
+  }
+
+  /// Tells whether every cell is assigned, and every unit has each numeral
+  /// once.
+  pub fn is_solved(&self) -> bool {
+    self.len() == Self::CELLS
+      && Self::units().all(|unit| {
+        let nums = Self::unit_cells(unit)
+          .into_iter()
+          .fold(SizedNumSet::new(), |acc, cell| {
+            acc | SizedNumSet::singleton(self.cells[cell])
+          });
+        nums.len() == Self::SIZE
+      })
+  }
+
+  /// Tells whether this board's assignments include all of the given one's.
+  pub fn extends(&self, other: &Self) -> bool {
+    other.iter().all(|(cell, num)| self.get(cell) == Some(num))
+  }
+}
+
+impl<const BR: usize, const BC: usize> Default for SizedGrid<BR, BC> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl From<&Grid> for SizedGrid<3, 3> {
+  /// Converts a standard grid, for comparing the two implementations.
+  fn from(grid: &Grid) -> Self {
+    let mut answer = Self::new();
+    for asgmt in grid.iter() {
+      answer.set(asgmt.loc.index(), Some(asgmt.num.get() as u8));
+    }
+    answer
+  }
+}
+
+/// The character for the given numeral: `1` through `9`, then `A` for 10
+/// through `G` for 16.
+fn num_char(num: u8) -> char {
+  char::from_digit(num as u32, 17)
+    .unwrap()
+    .to_ascii_uppercase()
+}
+
+impl<const BR: usize, const BC: usize> fmt::Display for SizedGrid<BR, BC> {
+  /// Prints this board in row-major order, with `.` for unassigned cells.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for cell in 0..Self::CELLS {
+      match self.get(cell) {
+        Some(num) => num_char(num).fmt(f)?,
+        None => '.'.fmt(f)?,
+      }
+    }
+    Ok(())
+  }
+}
+
+impl<const BR: usize, const BC: usize> fmt::Debug for SizedGrid<BR, BC> {
+  /// Prints this board as Ascii art, one row per line.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let flat: Vec<char> = self.to_string().chars().collect();
+    let rows: Vec<String> = flat
+      .chunks(Self::SIZE)
+      .map(|row| {
+        row
+          .chunks(BC)
+          .map(|part| {
+            part
+              .iter()
+              .map(char::to_string)
+              .collect::<Vec<_>>()
+              .join(" ")
+          })
+          .collect::<Vec<_>>()
+          .join(" | ")
+      })
+      .collect();
+    let divider = vec!["-".repeat(2 * BC - 1); BR].join("-+-");
+    let bands: Vec<String> = rows.chunks(BR).map(|band| band.join("\n")).collect();
+    f.write_str(&bands.join(&format!("\n{}\n", divider)))
+  }
+}
+
+impl<const BR: usize, const BC: usize> FromStr for SizedGrid<BR, BC> {
+  type Err = String;
+
+  /// Parses a board from a string with exactly one character per cell, plus
+  /// any number of other characters.  A cell character is a numeral's
+  /// character, `1` through `9` or `A` through `G` (in either case) up to the
+  /// board's size, or `0` or `.` for a blank cell; a numeral's character past
+  /// the board's size is an error.  So both the Display and Debug forms parse
+  /// back into the original board.
+  fn from_str(s: &str) -> Result<Self, String> {
+    let mut i = 0;
+    let mut grid = Self::new();
+    for c in s.chars() {
+      let num = match c {
+        '.' | '0' => 0,
+        _ => match c.to_digit(17) {
+          Some(num) if num as usize <= Self::SIZE => num as u8,
+          Some(_) => return Err(format!("Numeral {:?} is past {} in {}", c, Self::SIZE, s)),
+          None => continue,
+        },
+      };
+      if i >= Self::CELLS {
+        return Err(format!("More than {} cells in {}", Self::CELLS, s));
+      }
+      grid.cells[i] = num;
+      i += 1;
+    }
+    if i == Self::CELLS {
+      Ok(grid)
+    } else {
+      Err(format!("Fewer than {} cells in {}", Self::CELLS, s))
+    }
+  }
+}
+
+/// A set of the numerals of a SizedGrid, 1 through 16.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct SizedNumSet(u16);
+
+impl SizedNumSet {
+  /// Makes an empty set.
+  pub fn new() -> Self {
+    Self(0)
+  }
+
+  /// Makes the set of numerals 1 through `size`.
+  pub fn all(size: usize) -> Self {
+    Self(((1u32 << size) - 1) as u16)
+  }
+
+  /// Makes the set containing just the given numeral.
+  pub fn singleton(num: u8) -> Self {
+    Self(1 << (num - 1))
+  }
+
+  pub fn len(self) -> usize {
+    self.0.count_ones() as usize
+  }
+
+  pub fn is_empty(self) -> bool {
+    self.0 == 0
+  }
+
+  pub fn contains(self, num: u8) -> bool {
+    self.0 & (1 << (num - 1)) != 0
+  }
+
+  pub fn insert(&mut self, num: u8) {
+    self.0 |= 1 << (num - 1);
+  }
+
+  pub fn remove(&mut self, num: u8) {
+    self.0 &= !(1 << (num - 1));
+  }
+
+  /// The smallest numeral in the set, if any.
+  pub fn smallest(self) -> Option<u8> {
+    (!self.is_empty()).then(|| self.0.trailing_zeros() as u8 + 1)
+  }
+
+  /// Iterates the numerals in increasing order.
+  pub fn iter(self) -> impl Iterator<Item = u8> {
+    (1..=16).filter(move |num| self.contains(*num))
+  }
+}
+
+impl std::ops::BitOr for SizedNumSet {
+  type Output = Self;
+  fn bitor(self, rhs: Self) -> Self {
+    Self(self.0 | rhs.0)
+  }
+}
+
+impl std::ops::BitAnd for SizedNumSet {
+  type Output = Self;
+  fn bitand(self, rhs: Self) -> Self {
+    Self(self.0 & rhs.0)
+  }
+}
+
+impl std::ops::Sub for SizedNumSet {
+  type Output = Self;
+  fn sub(self, rhs: Self) -> Self {
+    Self(self.0 & !rhs.0)
+  }
+}
+
+impl FromIterator<u8> for SizedNumSet {
+  fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Self {
+    let mut answer = Self::new();
+    for num in iter {
+      answer.insert(num);
+    }
+    answer
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_units() {
+    assert_eq!(vec![0, 1, 4, 5], Grid4::unit_cells(SizedUnit::Blk(0)));
+    assert_eq!(vec![10, 11, 14, 15], Grid4::unit_cells(SizedUnit::Blk(3)));
+    // 6x6 blocks are 2 rows by 3 columns, 2 across and 3 down.
+    assert_eq!(
+      vec![15, 16, 17, 21, 22, 23],
+      Grid6::unit_cells(SizedUnit::Blk(3))
+    );
+    assert_eq!(
+      [SizedUnit::Row(3), SizedUnit::Col(5), SizedUnit::Blk(3)],
+      Grid6::cell_units(23)
+    );
+    for cell in 0..Grid12::CELLS {
+      for unit in Grid12::cell_units(cell) {
+        assert!(Grid12::unit_cells(unit).contains(&cell));
+      }
+    }
+    // Row and column peers, plus the rest of the block outside them.
+    assert_eq!(30 + 9, Grid16::peers(0).len());
+    for loc in Loc::all() {
+      let peers: Vec<usize> = loc.peers().iter().map(|peer| peer.index()).collect();
+      assert_eq!(peers, SizedGrid::<3, 3>::peers(loc.index()));
+    }
+  }
+
+  #[test]
+  fn test_strings() {
+    let s = "1234341221434321";
+    let grid: Grid4 = s.parse().unwrap();
+    assert!(grid.is_solved());
+    assert_eq!(s, grid.to_string());
+    assert_eq!(grid, format!("{:?}", grid).parse().unwrap());
+    assert_eq!(
+      "1 2 | 3 4\n3 4 | 1 2\n----+----\n2 1 | 4 3\n4 3 | 2 1",
+      format!("{:?}", grid)
+    );
+    let mut grid = Grid16::new();
+    grid.set(0, Some(16));
+    grid.set(255, Some(10));
+    assert_eq!(2, grid.len());
+    let s = grid.to_string();
+    assert!(s.starts_with("G.") && s.ends_with(".A"));
+    assert_eq!(grid, s.to_lowercase().parse().unwrap());
+    assert!("123".parse::<Grid4>().is_err());
+    assert!(Grid4::from_str(&"1".repeat(17)).is_err());
+    assert!(Grid4::from_str(&format!("5{}", ".".repeat(15))).is_err());
+    assert!(Grid4::from_str(&format!("{}a", ".".repeat(15))).is_err());
+    assert!(SizedGrid::<2, 3>::from_str(&format!("7{}", ".".repeat(35))).is_err());
+    assert_eq!(
+      Ok(SizedGrid::<2, 3>::new()),
+      format!("{}x", ".".repeat(36)).parse()
+    );
+    let standard = Grid::from_str(
+      "123456789456789123789123456234567891567891234891234567345678912678912345912345678",
+    )
+    .unwrap();
+    assert!(SizedGrid::<3, 3>::from(&standard).is_solved());
+  }
+
+  #[test]
+  fn test_num_set() {
+    let all = SizedNumSet::all(16);
+    assert_eq!(16, all.len());
+    assert_eq!(Some(1), all.smallest());
+    let set: SizedNumSet = [3, 16].into_iter().collect();
+    assert_eq!(vec![3, 16], set.iter().collect::<Vec<_>>());
+    assert_eq!(set, all & set);
+    assert_eq!(14, (all - set).len());
+    assert!(SizedNumSet::new().smallest().is_none());
+  }
+}
@@ -32,13 +32,22 @@ define_id_types! {
     /// Blocks are numbered in row-major order.
     Blk: i8[9];
 
-    /// Identifies one of the 27 "units" (row/col/block) of a Sudoku grid.
+    /// Identifies one of the 2 main diagonals of a Sudoku grid, which are
+    /// units only in variants like X-Sudoku.
+    ///
+    /// D1 runs from the top left to the bottom right; D2 from the top right to
+    /// the bottom left.
+    Diag: i8[2];
+
+    /// Identifies one of the "units" of a Sudoku grid: the 27 standard ones
+    /// (row/col/block), followed by the 2 diagonals.
     ///
     /// The units of a Sudoku (not to be confused with Rust's unit type) are
     /// all the subregions of the grid that must contain all 9 numerals in a
-    /// valid solution.
+    /// valid solution.  The diagonals are units only in variants that say so;
+    /// see `Variant`.
     #[derive(Debug)]
-    UnitId: i8[27];
+    UnitId: i8[29];
 
     /// Identifies one of the mini-rows or -columns within a block.
     ///
@@ -47,7 +56,7 @@ define_id_types! {
     BlkLine: i8[3];
 }
 
-/// One of a row, column, or block.
+/// One of a row, column, or block, or in some variants a diagonal.
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, ts_rs::TS)]
 #[ts(export, export_to = "../../www/src/facts/")]
 #[serde(tag = "type", content = "id")]
@@ -55,11 +64,18 @@ pub enum Unit {
   Blk(Blk),
   Row(Row),
   Col(Col),
+  Diag(Diag),
 }
 
+/// The number of standard units: rows, columns and blocks.
+pub const STANDARD_UNIT_COUNT: usize = 27;
+
 impl Unit {
+  /// Iterates the 27 standard units, the ones every Sudoku variant has.
   pub fn all() -> impl Iterator<Item = Self> {
-    UnitId::all().map(|id| id.to_unit())
+    UnitId::all()
+      .take(STANDARD_UNIT_COUNT)
+      .map(|id| id.to_unit())
   }
 }
 
@@ -180,6 +196,32 @@ impl std::fmt::Debug for Blk {
   }
 }
 
+impl Diag {
+  /// Tells whether this diagonal runs through the given location.
+  pub fn contains(self, loc: Loc) -> bool {
+    let (row, col) = (loc.row().get(), loc.col().get());
+    if self.0 == 0 {
+      row == col
+    } else {
+      row + col == 8
+    }
+  }
+
+  /// Returns the location on this diagonal in the given row.
+  pub const fn loc_in_row(self, row: Row) -> Loc {
+    if self.0 == 0 {
+      Loc::at(row, row.t())
+    } else {
+      Loc::at(row, row.t().opp())
+    }
+  }
+}
+impl std::fmt::Debug for Diag {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "D{}", self.ordinal())
+  }
+}
+
 // Constant unit values: R1 through R9 (rows, top to bottom); C1 through C9
 // (columns, left to right); and B1 through B9 (blocks, top left going in
 // row-major order).
@@ -194,6 +236,11 @@ seq!(N in 1..=9 {
     }
 });
 
+/// The main diagonal, from the top left to the bottom right.
+pub const D1: Diag = Diag(0);
+/// The anti-diagonal, from the top right to the bottom left.
+pub const D2: Diag = Diag(1);
+
 pub const BL1: BlkLine = BlkLine(0);
 pub const BL2: BlkLine = BlkLine(1);
 pub const BL3: BlkLine = BlkLine(2);
@@ -211,6 +258,10 @@ impl UnitId {
     Self(18 + col.get())
   }
 
+  pub const fn from_diag(diag: Diag) -> Self {
+    Self(27 + diag.get())
+  }
+
   pub const fn to_unit(self) -> Unit {
     match self.get() {
       0..=8 => unsafe {
@@ -221,10 +272,14 @@ impl UnitId {
         // Safe because the result is in 0..9.
         Unit::Row(Row::new_unchecked(self.get() - 9))
       },
-      _ => unsafe {
-        // Safe because self.get() is in 0..27.
+      18..=26 => unsafe {
+        // Safe because the result is in 0..9.
         Unit::Col(Col::new_unchecked(self.get() - 18))
       },
+      _ => unsafe {
+        // Safe because self.get() is in 0..29.
+        Unit::Diag(Diag::new_unchecked(self.get() - 27))
+      },
     }
   }
 }
@@ -245,6 +300,7 @@ impl UnitTrait for Unit {
       Self::Blk(blk) => UnitId::from_blk(blk),
       Self::Row(row) => UnitId::from_row(row),
       Self::Col(col) => UnitId::from_col(col),
+      Self::Diag(diag) => UnitId::from_diag(diag),
     }
   }
 
@@ -257,6 +313,7 @@ impl UnitTrait for Unit {
       Self::Blk(blk) => blk.locs(),
       Self::Row(row) => row.locs(),
       Self::Col(col) => col.locs(),
+      Self::Diag(diag) => diag.locs(),
     }
   }
 }
@@ -310,6 +367,25 @@ impl UnitTrait for Blk {
   }
 }
 
+impl UnitTrait for Diag {
+  fn unit_id(self) -> UnitId {
+    UnitId::from_diag(self)
+  }
+
+  fn to_unit(self) -> Unit {
+    Unit::Diag(self)
+  }
+
+  fn locs(self) -> LocSet {
+    let band_bits = if self.0 == 0 {
+      [0o_004_002_001, 0o_004_002_001 << 3, 0o_004_002_001 << 6]
+    } else {
+      [0o_100_200_400, 0o_100_200_400 >> 3, 0o_100_200_400 >> 6]
+    };
+    LocSet(Bits3x27::new(band_bits.map(Bits27::from_backing_int)))
+  }
+}
+
 impl UnitTrait for UnitId {
   fn unit_id(self) -> UnitId {
     self
@@ -325,10 +401,14 @@ impl UnitTrait for UnitId {
         // Safe because the result is in 0..9.
         Unit::Row(Row::new_unchecked(self.get() - 9))
       },
-      _ => unsafe {
-        // Safe because self.get() is in 0..27.
+      18..=26 => unsafe {
+        // Safe because the result is in 0..9.
         Unit::Col(Col::new_unchecked(self.get() - 18))
       },
+      _ => unsafe {
+        // Safe because self.get() is in 0..29.
+        Unit::Diag(Diag::new_unchecked(self.get() - 27))
+      },
     }
   }
 
@@ -358,6 +438,25 @@ impl fmt::Display for Blk {
   }
 }
 
+impl fmt::Display for Diag {
+  /// Prints this diagonal as D1 (the main diagonal) or D2 (the anti-diagonal).
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "D{}", self.ordinal())
+  }
+}
+
+impl fmt::Display for Unit {
+  /// Prints this unit the way its own type prints it.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Blk(blk) => blk.fmt(f),
+      Self::Row(row) => row.fmt(f),
+      Self::Col(col) => col.fmt(f),
+      Self::Diag(diag) => diag.fmt(f),
+    }
+  }
+}
+
 impl WasmDescribe for BlkLine {
   fn describe() {
     inform(I8)
@@ -383,7 +482,7 @@ impl IntoWasmAbi for BlkLine {
 /// A set of units.
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, ts_rs::TS)]
 #[ts(export, export_to = "../../www/src/facts/")]
-pub struct UnitSet(#[ts(as = "Vec<Unit>")] pub Bits27);
+pub struct UnitSet(#[ts(as = "Vec<Unit>")] pub Bits29);
 
 impl serde::Serialize for UnitSet {
   fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -401,7 +500,7 @@ impl serde::Serialize for UnitSet {
 
 impl<'a> Set<'a> for UnitSet {
   type Item = Unit;
-  type Bits = Bits27;
+  type Bits = Bits29;
 
   fn bits(&self) -> &Self::Bits {
     &self.0
@@ -443,9 +542,26 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_diag_locs() {
+    for diag in Diag::all() {
+      let locs = diag.locs();
+      assert_eq!(9, locs.len());
+      for loc in Loc::all() {
+        assert_eq!(diag.contains(loc), locs.contains(loc), "{diag} {loc}");
+      }
+      for row in Row::all() {
+        assert_eq!(locs & row.locs(), diag.loc_in_row(row).as_set());
+      }
+    }
+    assert_eq!(L11.as_set(), (D1.locs() - D2.locs()) & R1.locs());
+    assert_eq!(L55.as_set(), D1.locs() & D2.locs());
+    assert_eq!(L91.as_set(), D2.locs() & R9.locs());
+  }
+
   #[test]
   fn test_unit_id() {
-    for i in 0..27 {
+    for i in 0..29 {
       let unit_id = UnitId::new(i).unwrap();
       assert_eq!(unit_id.get(), i);
       assert_eq!(unit_id.to_unit().unit_id(), unit_id);
@@ -0,0 +1,213 @@
+//! Defines the Variant type, which says what rules a puzzle follows beyond
+//! those of standard Sudoku.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::*;
+
+/// The Sudoku variants the engine knows how to play.  Each one keeps all the
+/// standard rules and adds constraints of its own: either by making further
+/// regions of the grid into units, or by relating locations to others whose
+/// numerals they restrict.
+#[derive(
+  Clone, Copy, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+)]
+#[wasm_bindgen]
+pub enum Variant {
+  /// Plain Sudoku, with just the 27 standard units.
+  #[default]
+  Standard,
+  /// X-Sudoku, in which the 2 main diagonals are units too.
+  X,
+  /// Anti-knight Sudoku: locations a knight's move apart can't share a
+  /// numeral.
+  AntiKnight,
+  /// Anti-king Sudoku: locations a king's move apart can't share a numeral.
+  AntiKing,
+  /// Non-consecutive Sudoku: orthogonally adjacent locations can't have
+  /// consecutive numerals.
+  NonConsecutive,
+}
+
+static X_UNITS: [Unit; 2] = [Unit::Diag(D1), Unit::Diag(D2)];
+
+impl Variant {
+  /// All the variants, in the order of their discriminants.
+  pub const ALL: [Variant; 5] = [
+    Variant::Standard,
+    Variant::X,
+    Variant::AntiKnight,
+    Variant::AntiKing,
+    Variant::NonConsecutive,
+  ];
+
+  /// The units this variant adds to the standard ones.
+  pub fn extra_units(self) -> &'static [Unit] {
+    match self {
+      Variant::X => &X_UNITS,
+      _ => &[],
+    }
+  }
+
+  /// The peer relations this variant adds to the standard one.
+  pub fn extra_relations(self) -> &'static [PeerRelation] {
+    match self {
+      Variant::AntiKnight => &[PeerRelation::Knight],
+      Variant::AntiKing => &[PeerRelation::King],
+      Variant::NonConsecutive => &[PeerRelation::NonConsecutive],
+      _ => &[],
+    }
+  }
+
+  /// The fewest clues the generator leaves in this variant's puzzles.
+  /// Non-Consecutive puzzles can get by with very few, but solving those
+  /// takes long enough to make generating them impractical.
+  pub fn min_clues(self) -> usize {
+    match self {
+      Variant::NonConsecutive => 12,
+      _ => 0,
+    }
+  }
+
+  /// Iterates all of this variant's units: the standard ones, followed by the
+  /// extra ones.
+  pub fn units(self) -> impl Iterator<Item = Unit> {
+    Unit::all().chain(self.extra_units().iter().copied())
+  }
+
+  /// Tells whether this variant is standard Sudoku.
+  pub fn is_standard(self) -> bool {
+    self == Variant::Standard
+  }
+
+  /// The given location's peers under this variant's rules: the locations
+  /// that can't have the same numeral as it.
+  pub fn peers(self, loc: Loc) -> LocSet {
+    let mut peers = loc.peers() | self.extra_peers(loc);
+    for relation in self.extra_relations() {
+      if relation.forbids_sharing() {
+        peers |= relation.locs(loc);
+      }
+    }
+    peers
+  }
+
+  /// The given location's peers within this variant's extra units.  Some of
+  /// them may be standard peers as well.
+  pub fn extra_peers(self, loc: Loc) -> LocSet {
+    let mut peers = LocSet::new();
+    for unit in self.extra_units() {
+      let locs = unit.locs();
+      if locs.contains(loc) {
+        peers |= locs;
+      }
+    }
+    peers.remove(loc);
+    peers
+  }
+
+  /// The assignments the given one rules out under this variant's rules,
+  /// beyond those the standard units do: its numeral in the rest of its extra
+  /// units, and whatever its extra relations exclude.
+  pub fn extra_exclusions(self, asgmt: Asgmt) -> AsgmtSet {
+    let mut answer = AsgmtSet::new();
+    answer.union_in_place(asgmt.num, self.extra_peers(asgmt.loc));
+    for relation in self.extra_relations() {
+      answer |= relation.exclusions(asgmt);
+    }
+    answer
+  }
+}
+
+impl fmt::Display for Variant {
+  /// Writes the variant's name in lower case, the form `FromStr` parses.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      Variant::Standard => "standard",
+      Variant::X => "x",
+      Variant::AntiKnight => "anti-knight",
+      Variant::AntiKing => "anti-king",
+      Variant::NonConsecutive => "non-consecutive",
+    })
+  }
+}
+
+impl FromStr for Variant {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.to_ascii_lowercase().as_str() {
+      "standard" => Ok(Variant::Standard),
+      "x" => Ok(Variant::X),
+      "anti-knight" => Ok(Variant::AntiKnight),
+      "anti-king" => Ok(Variant::AntiKing),
+      "non-consecutive" => Ok(Variant::NonConsecutive),
+      _ => Err(format!("Unknown Sudoku variant {:?}", s)),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_peers() {
+    for loc in Loc::all() {
+      assert_eq!(loc.peers(), Variant::Standard.peers(loc));
+    }
+    assert_eq!(20, Variant::X.peers(L12).len());
+    assert_eq!(26, Variant::X.peers(L11).len());
+    // L55 is on both diagonals: 20 standard peers, plus the 6 on each
+    // diagonal that aren't in its block.
+    assert_eq!(32, Variant::X.peers(L55).len());
+    assert!(Variant::X.peers(L19).contains(L91));
+    assert!(!Variant::X.peers(L19).contains(L19));
+    // None of L55's knight moves stay in its row, column or block.
+    assert_eq!(28, Variant::AntiKnight.peers(L55).len());
+    // A king's move only leaves the block from its edge.
+    assert_eq!(20, Variant::AntiKing.peers(L55).len());
+    assert_eq!(21, Variant::AntiKing.peers(L13).len());
+    // Non-consecutive neighbors may share a numeral, but they're standard
+    // peers anyway.
+    assert_eq!(20, Variant::NonConsecutive.peers(L55).len());
+  }
+
+  #[test]
+  fn test_extra_exclusions() {
+    let asgmt = Asgmt::new(N5, L55);
+    assert!(Variant::Standard.extra_exclusions(asgmt).is_empty());
+    assert_eq!(
+      Variant::X.extra_peers(L55),
+      Variant::X.extra_exclusions(asgmt).num_locs(N5)
+    );
+    assert_eq!(
+      PeerRelation::Knight.locs(L55),
+      Variant::AntiKnight.extra_exclusions(asgmt).num_locs(N5)
+    );
+    let exclusions = Variant::NonConsecutive.extra_exclusions(asgmt);
+    assert!(exclusions.num_locs(N5).is_empty());
+    assert_eq!(
+      [L45, L54, L56, L65].into_iter().collect::<LocSet>(),
+      exclusions.num_locs(N6)
+    );
+  }
+
+  #[test]
+  fn test_units() {
+    assert_eq!(27, Variant::Standard.units().count());
+    assert_eq!(29, Variant::X.units().count());
+  }
+
+  #[test]
+  fn test_parsing() {
+    for variant in Variant::ALL {
+      assert_eq!(Ok(variant), variant.to_string().parse());
+    }
+    assert_eq!(Ok(Variant::X), "X".parse());
+    assert!("jigsaw".parse::<Variant>().is_err());
+  }
+}
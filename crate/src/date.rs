@@ -1,4 +1,7 @@
-use std::fmt::{Debug, Display};
+use std::{
+  fmt::{Debug, Display},
+  str::FromStr,
+};
 
 use chrono::{Datelike, NaiveDate};
 use js_sys::Date;
@@ -19,8 +22,8 @@ impl LogicalDate {
   }
 
   #[wasm_bindgen(js_name = "fromString")]
-  pub fn new_from_string(s: &str) -> Self {
-    Self::from(NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap())
+  pub fn new_from_string(s: &str) -> Result<LogicalDate, JsError> {
+    Ok(Self::from_str(s)?)
   }
 
   #[wasm_bindgen(js_name = "fromDate")]
@@ -92,6 +95,15 @@ impl Display for LogicalDate {
   }
 }
 
+impl FromStr for LogicalDate {
+  type Err = chrono::ParseError;
+
+  /// Parses an ISO date like 2024-06-15.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Ok(Self::from(NaiveDate::parse_from_str(s, "%Y-%m-%d")?))
+  }
+}
+
 impl From<NaiveDate> for LogicalDate {
   fn from(date: NaiveDate) -> Self {
     let year = date.year();
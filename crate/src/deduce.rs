@@ -1,15 +1,21 @@
 //! Code that emulates human Sudoku deduction patterns.
 
 use crate::core::*;
+use crate::error::Error;
+use crate::sym::{DigitSym, Sym};
 use crate::time;
 
 pub mod advanced;
 mod internals;
+pub mod jigsaw;
+pub mod killer;
+pub mod sized;
 
 use crate::solve::ledger::Ledger;
+use crate::solve::{solve_variant, DefaultHelper};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
-use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::prelude::{wasm_bindgen, JsError, JsValue};
 
 /// A fact that can be deduced from a Sudoku grid.
 #[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Serialize, TS)]
@@ -36,8 +42,9 @@ pub enum Fact {
   /// Error: the given location has multiple numerals assigned to it.
   ConflictLoc { loc: Loc, nums: NumSet },
   /// Elimination: the given numeral can only be within the intersection of the
-  /// two given units, one of which must be a block and the other a line, so all
-  /// other locations in the `unit` can be eliminated.
+  /// two given units, one of which must be a block and the other a line (or,
+  /// in X-Sudoku, a diagonal), so all other locations in the `cross_unit` can
+  /// be eliminated.
   Overlap {
     num: Num,
     unit: Unit,
@@ -91,9 +98,65 @@ pub enum Fact {
     string_ends: LocSet,
     elimination_locs: LocSet,
   },
+  /// Elimination: wherever the given numeral goes within the given unit, it
+  /// is related to all the given locations, so the numerals its assignment
+  /// excludes under the relation can be eliminated from them.  Only found in
+  /// variants with extra relations, like Anti-Knight.
+  RelationClaim {
+    relation: PeerRelation,
+    num: Num,
+    unit: Unit,
+    locs: LocSet,
+  },
+  /// Elimination: each of the numerals remaining at the given location
+  /// excludes the given numeral from its related locations, so it can be
+  /// eliminated from the given ones.  Only found in variants whose relations
+  /// rule out different numerals, like Non-Consecutive.
+  RelationCover {
+    relation: PeerRelation,
+    loc: Loc,
+    nums: NumSet,
+    num: Num,
+    locs: LocSet,
+  },
+  /// Elimination: the clues are symmetric under the given symmetry once
+  /// their numerals are mapped too, each numeral `n` to `mapping[n - 1]`.
+  /// By Gurth's theorem the unique solution is as well, so the given numeral
+  /// can be eliminated from the given locations: at each of them, one of the
+  /// symmetric images of that placement is impossible.
+  SymmetricPlacement {
+    sym: Sym,
+    mapping: Vec<Num>,
+    num: Num,
+    locs: LocSet,
+  },
 }
 
 impl Fact {
+  /// Returns the name of this fact's variant, which is also its serialized
+  /// `type` tag.
+  pub fn type_name(&self) -> &'static str {
+    match self {
+      Fact::SingleLoc { .. } => "SingleLoc",
+      Fact::SingleNum { .. } => "SingleNum",
+      Fact::SpeculativeAssignment { .. } => "SpeculativeAssignment",
+      Fact::NoLoc { .. } => "NoLoc",
+      Fact::NoNum { .. } => "NoNum",
+      Fact::Conflict { .. } => "Conflict",
+      Fact::ConflictLoc { .. } => "ConflictLoc",
+      Fact::Overlap { .. } => "Overlap",
+      Fact::Subset { .. } => "Subset",
+      Fact::Implication { .. } => "Implication",
+      Fact::Fish { .. } => "Fish",
+      Fact::EmptyRectangle { .. } => "EmptyRectangle",
+      Fact::Skyscraper { .. } => "Skyscraper",
+      Fact::TwoStringKite { .. } => "TwoStringKite",
+      Fact::RelationClaim { .. } => "RelationClaim",
+      Fact::RelationCover { .. } => "RelationCover",
+      Fact::SymmetricPlacement { .. } => "SymmetricPlacement",
+    }
+  }
+
   /// Returns the assignment associated with this fact, if any.
   pub fn as_asgmt(&self) -> Option<Asgmt> {
     match self {
@@ -197,6 +260,23 @@ impl Fact {
         answer.union_in_place(*num, *elimination_locs);
         answer
       }
+      Fact::RelationClaim {
+        relation,
+        num,
+        locs,
+        ..
+      } => {
+        let mut answer = AsgmtSet::new();
+        for excluded in relation.excluded_nums(*num).iter() {
+          answer.union_in_place(excluded, *locs);
+        }
+        answer
+      }
+      Fact::RelationCover { num, locs, .. } | Fact::SymmetricPlacement { num, locs, .. } => {
+        let mut answer = AsgmtSet::new();
+        answer.union_in_place(*num, *locs);
+        answer
+      }
       _ => AsgmtSet::new(),
     }
   }
@@ -245,21 +325,97 @@ pub struct FactFinder {
   /// An alternate view of `remaining_asgmts` that is more efficient for some
   /// operations.  Also tracks errors.
   sukaku_map: internals::SukakuMap,
+
+  /// The rules the grid follows.
+  variant: Variant,
+
+  /// The symmetry of the starting grid that maps numerals too, if any.
+  digit_sym: Option<DigitSym>,
 }
 
 impl FactFinder {
   /// Creates a new `FactFinder` with the given grid.
   pub fn new(grid: &Grid) -> Self {
+    Self::new_for_variant(grid, Variant::Standard)
+  }
+
+  /// Creates a new `FactFinder` with the given grid, which follows the given
+  /// variant's rules.
+  pub fn new_for_variant(grid: &Grid, variant: Variant) -> Self {
     let possible_asgmts = AsgmtSet::possibles_from_grid(grid);
     let simple_asgmts = AsgmtSet::simple_from_grid(grid);
-    let sukaku_map = internals::SukakuMap::from_grid(grid);
+    let mut remaining_asgmts = possible_asgmts - simple_asgmts;
+    let mut sukaku_map = internals::SukakuMap::from_grid(grid);
+    for asgmt in grid.iter() {
+      internals::apply_variant_peers(variant, asgmt, &mut remaining_asgmts, &mut sukaku_map);
+    }
     Self {
-      remaining_asgmts: possible_asgmts - simple_asgmts,
+      remaining_asgmts,
       actual_asgmts: simple_asgmts,
       sukaku_map,
+      variant,
+      digit_sym: None,
+    }
+  }
+
+  /// Creates a new `FactFinder` with the given grid, which follows the given
+  /// variant's rules and is known to have exactly one solution.  Only then
+  /// does Gurth's theorem hold, so only this finder looks for a digit-mapped
+  /// symmetry of the grid, and deduces symmetric placements from it.
+  pub fn new_with_unique_solution(grid: &Grid, variant: Variant) -> Self {
+    let mut finder = Self::new_for_variant(grid, variant);
+    // The theorem also only covers the standard rules: the other variants'
+    // extra units and relations needn't survive the symmetry.
+    if variant == Variant::Standard {
+      finder.digit_sym = DigitSym::find(grid);
+    }
+    finder
+  }
+
+  /// Creates a new `FactFinder` with the given grid, which follows the given
+  /// variant's rules, solving the grid first to see whether it may assume a
+  /// digit-mapped symmetry.
+  fn new_counting_solutions(grid: &Grid, variant: Variant) -> Self {
+    let summary = solve_variant(grid, variant, 1, &mut DefaultHelper());
+    if summary.solutions.len() == 1 && !summary.too_many_solutions {
+      Self::new_with_unique_solution(grid, variant)
+    } else {
+      Self::new_for_variant(grid, variant)
     }
   }
 
+  /// Creates a new `FactFinder` with the given candidates, none of them yet
+  /// assigned.  Locations with a single candidate show up as singles.
+  pub fn from_candidates(candidates: &CandidateGrid) -> Self {
+    let asgmts = candidates.asgmts();
+    Self {
+      remaining_asgmts: asgmts,
+      actual_asgmts: AsgmtSet::new(),
+      sukaku_map: internals::SukakuMap::from_asgmts(&asgmts),
+      variant: Variant::Standard,
+      digit_sym: None,
+    }
+  }
+
+  /// The variant whose rules this finder follows.
+  pub fn variant(&self) -> Variant {
+    self.variant
+  }
+
+  /// The symmetry of the starting grid that maps numerals too, if any.  The
+  /// finder's deductions assume that the solution shares it.
+  pub fn digit_sym(&self) -> Option<DigitSym> {
+    self.digit_sym
+  }
+
+  fn collector(&self) -> internals::Collector {
+    let mut collector =
+      internals::Collector::new(self.remaining_asgmts, self.actual_asgmts, self.sukaku_map);
+    collector.variant = self.variant;
+    collector.digit_sym = self.digit_sym;
+    collector
+  }
+
   /// Returns the current state of the grid.
   pub fn to_grid(&self) -> Grid {
     self.actual_asgmts.to_grid()
@@ -279,8 +435,7 @@ impl FactFinder {
   /// Returns the facts deducible from the current state of the grid, including
   /// any errors, and stopping early if the max time is reached.
   pub fn deduce_all_with_timeout(&self, max_time_ms: Option<f64>) -> (Vec<Fact>, bool) {
-    let mut collector =
-      internals::Collector::new(self.remaining_asgmts, self.actual_asgmts, self.sukaku_map);
+    let mut collector = self.collector();
     collector.max_time_ms = max_time_ms;
     collector.collect(internals::ErrorMode::Collect).unwrap();
     (collector.facts, collector.timed_out)
@@ -289,19 +444,13 @@ impl FactFinder {
   /// Returns the facts deducible from the current state of the grid, ignoring
   /// any errors: use this when the current state is known to be valid.
   pub fn deduce_valid(&self) -> Vec<Fact> {
-    let mut collector =
-      internals::Collector::new(self.remaining_asgmts, self.actual_asgmts, self.sukaku_map);
+    let mut collector = self.collector();
     collector.collect(internals::ErrorMode::Ignore).unwrap();
     collector.facts
   }
 
-  pub fn deduce_quick_fact(
-    &self,
-    target: Asgmt,
-    max_time_ms: Option<f64>,
-  ) -> (Vec<Fact>, bool) {
-    let mut collector =
-      internals::Collector::new(self.remaining_asgmts, self.actual_asgmts, self.sukaku_map);
+  pub fn deduce_quick_fact(&self, target: Asgmt, max_time_ms: Option<f64>) -> (Vec<Fact>, bool) {
+    let mut collector = self.collector();
     collector.max_time_ms = max_time_ms.or(Some(200.0));
     if let Some(fact) = collector.collect_targeted(target) {
       (vec![fact], false)
@@ -313,8 +462,7 @@ impl FactFinder {
   /// Returns only the direct assignments that can be deduced from the
   /// current state of the grid.
   pub fn deduce_singles(&self) -> Vec<Fact> {
-    let mut collector =
-      internals::Collector::new(self.remaining_asgmts, self.actual_asgmts, self.sukaku_map);
+    let mut collector = self.collector();
     collector.collect_singles();
     collector.facts
   }
@@ -323,8 +471,7 @@ impl FactFinder {
   /// the search if an error is found.  This is useful for finding errors in the
   /// current state of the grid, such as when the grid is known to be invalid.
   pub fn deduce_invalid(&self) -> Result<Vec<Fact>, Invalid> {
-    let mut collector =
-      internals::Collector::new(self.remaining_asgmts, self.actual_asgmts, self.sukaku_map);
+    let mut collector = self.collector();
     collector.collect(internals::ErrorMode::ShortCircuit)?;
     Ok(collector.facts)
   }
@@ -352,9 +499,27 @@ impl FactFinder {
     self.remaining_asgmts.remove(asgmt);
     self.actual_asgmts.insert(asgmt);
     self.sukaku_map.apply(asgmt);
+    internals::apply_variant_peers(
+      self.variant,
+      asgmt,
+      &mut self.remaining_asgmts,
+      &mut self.sukaku_map,
+    );
   }
 }
 
+// The TypeScript types that the entry points below take and return, as
+// exported by ts-rs.
+#[wasm_bindgen(typescript_custom_section)]
+const TS_TYPES: &str = r#"
+import type {DeduceResult} from '../../www/src/facts/DeduceResult';
+import type {EliminationConstraint} from '../../www/src/facts/EliminationConstraint';
+import type {ErroneousAssignmentProductivity} from '../../www/src/facts/ErroneousAssignmentProductivity';
+import type {Fact} from '../../www/src/facts/Fact';
+import type {IndexedAsgmt} from '../../www/src/facts/IndexedAsgmt';
+"#;
+
+/// What `deduceFacts` and `deduceQuickFact` return.
 #[derive(Serialize, TS)]
 #[ts(export, export_to = "../../www/src/facts/")]
 #[serde(rename_all = "camelCase")]
@@ -363,22 +528,69 @@ pub struct DeduceResult {
   pub timed_out: bool,
 }
 
-#[derive(Deserialize)]
-pub struct WasmAsgmt {
+/// An assignment as the TypeScript side passes it in: a location index, 0
+/// through 80, and a numeral, 1 through 9.  Neither is checked until it's
+/// converted to an `Asgmt`.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, TS)]
+#[ts(export, export_to = "../../www/src/facts/")]
+pub struct IndexedAsgmt {
   pub loc: i8,
   pub num: i8,
 }
 
-pub fn apply_constraints(finder: &mut FactFinder, constraints: &[Vec<WasmAsgmt>]) {
+/// A set of assignments that have been disproved together: they can't all
+/// hold, so once all but one of them do, the last one is eliminated.  A
+/// single assignment is simply eliminated.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, TS)]
+#[ts(export, export_to = "../../www/src/facts/")]
+pub struct EliminationConstraint(pub Vec<IndexedAsgmt>);
+
+impl From<Asgmt> for IndexedAsgmt {
+  fn from(asgmt: Asgmt) -> Self {
+    Self {
+      loc: asgmt.loc.get(),
+      num: asgmt.num.get(),
+    }
+  }
+}
+
+impl IndexedAsgmt {
+  /// Converts this to an assignment, failing if its location or numeral is out
+  /// of range.
+  pub fn to_asgmt(&self) -> Result<Asgmt, Error> {
+    let num = Num::new(self.num).ok_or(Error::InvalidNum(self.num.into()))?;
+    let loc = Loc::new(self.loc).ok_or(Error::InvalidLoc(self.loc.into()))?;
+    Ok(Asgmt::new(num, loc))
+  }
+}
+
+/// Reads the elimination constraints passed to a wasm entry point, which may
+/// be missing.
+fn read_constraints(eliminations: JsValue) -> Result<Option<Vec<EliminationConstraint>>, Error> {
+  if eliminations.is_undefined() || eliminations.is_null() {
+    return Ok(None);
+  }
+  serde_wasm_bindgen::from_value(eliminations)
+    .map(Some)
+    .map_err(|e| Error::MalformedConstraints(e.to_string()))
+}
+
+pub fn apply_constraints(
+  finder: &mut FactFinder,
+  constraints: &[EliminationConstraint],
+) -> Result<(), Error> {
+  if constraints.iter().any(|constraint| constraint.0.is_empty()) {
+    return Err(Error::MalformedConstraints("empty constraint".to_string()));
+  }
   let mut changed = true;
   while changed {
     changed = false;
-    for constraint in constraints {
+    for EliminationConstraint(constraint) in constraints {
       let mut active_asgmts = Vec::new();
       let mut inactive_asgmts = Vec::new();
 
       for wa in constraint {
-        let asgmt = Asgmt::new(Num::new(wa.num).unwrap(), Loc::new(wa.loc).unwrap());
+        let asgmt = wa.to_asgmt()?;
         if finder.actual_asgmts.contains(asgmt) {
           active_asgmts.push(asgmt);
         } else if finder.remaining_asgmts.contains(asgmt) {
@@ -407,74 +619,58 @@ pub fn apply_constraints(finder: &mut FactFinder, constraints: &[Vec<WasmAsgmt>]
       }
     }
   }
+  Ok(())
 }
 
-#[wasm_bindgen(js_name = "deduceQuickFact")]
+#[wasm_bindgen(js_name = "deduceQuickFact", unchecked_return_type = "DeduceResult")]
 pub fn deduce_quick_fact(
   grid: &Grid,
   target_loc: i8,
   target_num: i8,
-  eliminations: wasm_bindgen::JsValue,
+  #[wasm_bindgen(unchecked_param_type = "readonly EliminationConstraint[] | undefined")]
+  eliminations: JsValue,
   max_time_ms: Option<f64>,
-) -> wasm_bindgen::JsValue {
-  let constraints: Option<Vec<Vec<WasmAsgmt>>> =
-    if eliminations.is_undefined() || eliminations.is_null() {
-      None
-    } else {
-      Some(serde_wasm_bindgen::from_value(eliminations).unwrap())
-    };
-
-  let loc = match Loc::new(target_loc) {
-    Some(l) => l,
-    None => {
-      return serde_wasm_bindgen::to_value(&DeduceResult {
-        facts: vec![],
-        timed_out: false,
-      })
-      .unwrap()
-    }
-  };
-  let num = match Num::new(target_num) {
-    Some(n) => n,
-    None => {
-      return serde_wasm_bindgen::to_value(&DeduceResult {
-        facts: vec![],
-        timed_out: false,
-      })
-      .unwrap()
-    }
-  };
-  let target = Asgmt::new(num, loc);
+  variant: Option<Variant>,
+) -> Result<JsValue, JsError> {
+  let constraints = read_constraints(eliminations)?;
+  let target = IndexedAsgmt {
+    loc: target_loc,
+    num: target_num,
+  }
+  .to_asgmt()?;
 
-  let mut finder = FactFinder::new(grid);
+  let mut finder = FactFinder::new_counting_solutions(grid, variant.unwrap_or_default());
   if let Some(ref c) = constraints {
-    apply_constraints(&mut finder, c);
+    apply_constraints(&mut finder, c)?;
   }
 
   let (facts, timed_out) = finder.deduce_quick_fact(target, max_time_ms);
-  serde_wasm_bindgen::to_value(&DeduceResult { facts, timed_out }).unwrap()
+  Ok(serde_wasm_bindgen::to_value(&DeduceResult {
+    facts,
+    timed_out,
+  })?)
 }
 
-#[wasm_bindgen(js_name = "deduceFacts")]
+#[wasm_bindgen(js_name = "deduceFacts", unchecked_return_type = "DeduceResult")]
 pub fn deduce_facts(
   grid: &Grid,
-  eliminations: wasm_bindgen::JsValue,
+  #[wasm_bindgen(unchecked_param_type = "readonly EliminationConstraint[] | undefined")]
+  eliminations: JsValue,
   max_time_ms: Option<f64>,
-) -> wasm_bindgen::JsValue {
-  let constraints: Option<Vec<Vec<WasmAsgmt>>> =
-    if eliminations.is_undefined() || eliminations.is_null() {
-      None
-    } else {
-      Some(serde_wasm_bindgen::from_value(eliminations).unwrap())
-    };
+  variant: Option<Variant>,
+) -> Result<JsValue, JsError> {
+  let constraints = read_constraints(eliminations)?;
 
-  let mut finder = FactFinder::new(grid);
+  let mut finder = FactFinder::new_counting_solutions(grid, variant.unwrap_or_default());
   if let Some(ref c) = constraints {
-    apply_constraints(&mut finder, c);
+    apply_constraints(&mut finder, c)?;
   }
 
   let (facts, timed_out) = finder.deduce_all_with_timeout(max_time_ms);
-  serde_wasm_bindgen::to_value(&DeduceResult { facts, timed_out }).unwrap()
+  Ok(serde_wasm_bindgen::to_value(&DeduceResult {
+    facts,
+    timed_out,
+  })?)
 }
 
 #[derive(Serialize, TS)]
@@ -489,25 +685,21 @@ pub struct ErroneousAssignmentProductivity {
 pub fn calculate_erroneous_productivity_native(
   grid: &Grid,
   solutions: &[SolvedGrid],
-  constraints: Option<&[Vec<WasmAsgmt>]>,
-) -> Vec<ErroneousAssignmentProductivity> {
-  let mut base_ledger = match Ledger::new(grid) {
-    Ok(l) => l,
-    Err(_) => return Vec::new(),
-  };
+  constraints: Option<&[EliminationConstraint]>,
+  variant: Variant,
+) -> Result<Vec<ErroneousAssignmentProductivity>, Error> {
+  let mut base_ledger = Ledger::new_for_variant(grid, variant)?;
 
   if let Some(c) = constraints {
     for constraint in c {
-      if constraint.len() == 1 {
-        let wa = &constraint[0];
-        base_ledger.eliminate(Num::new(wa.num).unwrap(), Loc::new(wa.loc).unwrap());
+      if let [wa] = constraint.0.as_slice() {
+        let asgmt = wa.to_asgmt()?;
+        base_ledger.eliminate(asgmt.num, asgmt.loc);
       }
     }
   }
 
-  if base_ledger.apply_implications().is_err() {
-    return Vec::new();
-  }
+  base_ledger.apply_implications()?;
 
   let base_solved = 81 - base_ledger.unset().len();
 
@@ -549,49 +741,58 @@ pub fn calculate_erroneous_productivity_native(
       .then_with(|| a.num.cmp(&b.num))
   });
 
-  results
+  Ok(results)
 }
 
-#[wasm_bindgen(js_name = "calculateErroneousProductivity")]
+#[wasm_bindgen(
+  js_name = "calculateErroneousProductivity",
+  unchecked_return_type = "ErroneousAssignmentProductivity[]"
+)]
 pub fn calculate_erroneous_productivity(
   grid: &Grid,
   solutions: Option<Vec<SolvedGrid>>,
-  eliminations: wasm_bindgen::JsValue,
-) -> wasm_bindgen::JsValue {
+  #[wasm_bindgen(unchecked_param_type = "readonly EliminationConstraint[] | undefined")]
+  eliminations: JsValue,
+  variant: Option<Variant>,
+) -> Result<JsValue, JsError> {
   let solutions = solutions.unwrap_or_default();
-  let constraints: Option<Vec<Vec<WasmAsgmt>>> =
-    if eliminations.is_undefined() || eliminations.is_null() {
-      None
-    } else {
-      Some(serde_wasm_bindgen::from_value(eliminations).unwrap())
-    };
+  let constraints = read_constraints(eliminations)?;
 
-  let results = calculate_erroneous_productivity_native(grid, &solutions, constraints.as_deref());
-  serde_wasm_bindgen::to_value(&results).unwrap()
+  let results = calculate_erroneous_productivity_native(
+    grid,
+    &solutions,
+    constraints.as_deref(),
+    variant.unwrap_or_default(),
+  )?;
+  Ok(serde_wasm_bindgen::to_value(&results)?)
 }
 
-#[wasm_bindgen(js_name = "disproveErroneousAssignment")]
+/// Looks for a disproof of the given assignment, returning it as an
+/// `Implication` fact whose first antecedent is the assignment, or undefined if
+/// none turns up within the time and depth limits.
+#[wasm_bindgen(
+  js_name = "disproveErroneousAssignment",
+  unchecked_return_type = "Fact | undefined"
+)]
 pub fn disprove_erroneous_assignment_wasm(
   grid: &Grid,
-  target: wasm_bindgen::JsValue,
+  #[wasm_bindgen(unchecked_param_type = "IndexedAsgmt")] target: JsValue,
   solutions: Option<Vec<SolvedGrid>>,
-  eliminations: wasm_bindgen::JsValue,
+  #[wasm_bindgen(unchecked_param_type = "readonly EliminationConstraint[] | undefined")]
+  eliminations: JsValue,
   max_time_ms: Option<f64>,
   max_depth: Option<usize>,
-) -> Option<String> {
-  let target: WasmAsgmt = serde_wasm_bindgen::from_value(target).unwrap();
-  let target_asgmt = Asgmt::new(Num::new(target.num).unwrap(), Loc::new(target.loc).unwrap());
+  variant: Option<Variant>,
+) -> Result<JsValue, JsError> {
+  let target: IndexedAsgmt =
+    serde_wasm_bindgen::from_value(target).map_err(|e| Error::MalformedAsgmt(e.to_string()))?;
+  let target_asgmt = target.to_asgmt()?;
 
-  let constraints: Option<Vec<Vec<WasmAsgmt>>> =
-    if eliminations.is_undefined() || eliminations.is_null() {
-      None
-    } else {
-      Some(serde_wasm_bindgen::from_value(eliminations).unwrap())
-    };
+  let constraints = read_constraints(eliminations)?;
 
-  let mut base_finder = FactFinder::new(grid);
+  let mut base_finder = FactFinder::new_counting_solutions(grid, variant.unwrap_or_default());
   if let Some(ref c) = constraints {
-    apply_constraints(&mut base_finder, c);
+    apply_constraints(&mut base_finder, c)?;
   }
 
   let solutions = solutions.unwrap_or_default();
@@ -605,10 +806,7 @@ pub fn disprove_erroneous_assignment_wasm(
     max_depth,
   );
 
-  fact_opt.map(|f| match serde_json::to_string(&f) {
-    Ok(s) => s,
-    Err(e) => format!(r#"{{"error": "Failed to serialize disproof: {}"}}"#, e),
-  })
+  Ok(serde_wasm_bindgen::to_value(&fact_opt)?)
 }
 
 pub fn disprove_erroneous_assignment(
@@ -670,6 +868,7 @@ fn disprove_recursive(
     base_finder.actual_asgmts,
     base_finder.sukaku_map,
   );
+  collector.variant = base_finder.variant;
 
   collector.max_time_ms = max_time_ms.map(|limit| {
     let elapsed = time::now() - start_time;
@@ -695,7 +894,7 @@ fn disprove_recursive(
       current_finder.sukaku_map = collector.sukaku_map;
 
       let base_grid = current_finder.to_grid();
-      let base_ledger = Ledger::new(&base_grid).ok()?;
+      let base_ledger = Ledger::new_for_variant(&base_grid, base_finder.variant).ok()?;
 
       enum Target {
         NoNum(Loc, NumSet),
@@ -716,7 +915,7 @@ fn disprove_recursive(
         }
       }
 
-      for unit in Unit::all() {
+      for unit in base_finder.variant.units() {
         for num in Num::all() {
           let locs = unit.locs() & current_finder.remaining_asgmts.num_locs(num);
           let len = locs.len();
@@ -827,14 +1026,51 @@ mod tests {
     let target_asg = Asgmt::new(N2, L11);
     assert!(finder.remaining_asgmts.contains(target_asg));
 
-    let constraints = vec![vec![WasmAsgmt {
-      loc: L11.index() as i8,
-      num: N2.get(),
-    }]];
-    apply_constraints(&mut finder, &constraints);
+    let constraints = vec![EliminationConstraint(vec![target_asg.into()])];
+    apply_constraints(&mut finder, &constraints).unwrap();
     assert!(!finder.remaining_asgmts.contains(target_asg));
   }
 
+  #[test]
+  fn test_apply_constraints_rejects_bad_input() {
+    let mut finder = FactFinder::new(&Grid::new());
+    let bad_num = vec![EliminationConstraint(vec![IndexedAsgmt {
+      loc: 0,
+      num: 10,
+    }])];
+    assert_eq!(
+      Err(Error::InvalidNum(10)),
+      apply_constraints(&mut finder, &bad_num)
+    );
+    let bad_loc = vec![EliminationConstraint(vec![IndexedAsgmt {
+      loc: 81,
+      num: 1,
+    }])];
+    assert_eq!(
+      Err(Error::InvalidLoc(81)),
+      apply_constraints(&mut finder, &bad_loc)
+    );
+    assert!(matches!(
+      apply_constraints(&mut finder, &[EliminationConstraint(vec![])]),
+      Err(Error::MalformedConstraints(_))
+    ));
+  }
+
+  #[test]
+  fn test_elimination_constraints_are_plain_arrays() {
+    let constraints: Vec<EliminationConstraint> = serde_json::from_str(
+      r#"[[{"loc": 0, "num": 2}], [{"loc": 1, "num": 3}, {"loc": 2, "num": 4}]]"#,
+    )
+    .unwrap();
+    assert_eq!(
+      vec![
+        EliminationConstraint(vec![Asgmt::new(N2, L11).into()]),
+        EliminationConstraint(vec![Asgmt::new(N3, L12).into(), Asgmt::new(N4, L13).into()]),
+      ],
+      constraints
+    );
+  }
+
   #[test]
   fn test_apply_constraints_multi() {
     let grid = Grid::from_str(
@@ -862,18 +1098,9 @@ mod tests {
     finder.actual_asgmts.insert(asg_a);
     finder.remaining_asgmts.remove(asg_a);
 
-    let constraints = vec![vec![
-      WasmAsgmt {
-        loc: L11.index() as i8,
-        num: N2.get(),
-      },
-      WasmAsgmt {
-        loc: L12.index() as i8,
-        num: N3.get(),
-      },
-    ]];
-
-    apply_constraints(&mut finder, &constraints);
+    let constraints = vec![EliminationConstraint(vec![asg_a.into(), asg_b.into()])];
+
+    apply_constraints(&mut finder, &constraints).unwrap();
     // Since asg_a is active, the constraint should propagate to eliminate asg_b
     assert!(!finder.remaining_asgmts.contains(asg_b));
   }
@@ -900,7 +1127,9 @@ mod tests {
     let summary = crate::solve::solve(&grid, 10, &mut helper);
     assert!(!summary.solutions.is_empty());
 
-    let results = calculate_erroneous_productivity_native(&grid, &summary.solutions, None);
+    let results =
+      calculate_erroneous_productivity_native(&grid, &summary.solutions, None, Variant::Standard)
+        .unwrap();
 
     let mut solutions_asgmt_set = AsgmtSet::new();
     for solution in &summary.solutions {
@@ -933,6 +1162,17 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_erroneous_productivity_of_invalid_grid() {
+    let mut grid = Grid::new();
+    grid[L11] = Some(N5);
+    grid[L19] = Some(N5);
+    assert!(matches!(
+      calculate_erroneous_productivity_native(&grid, &[], None, Variant::Standard),
+      Err(Error::InvalidGrid(_))
+    ));
+  }
+
   #[test]
   fn test_lunatic_nested_disproof() {
     // Lunatic puzzle from evaluate/internals.rs
@@ -1112,4 +1352,138 @@ mod tests {
     assert_eq!(facts.len(), 1);
     assert_eq!(facts[0].as_asgmt(), Some(target));
   }
+
+  #[test]
+  fn test_x_variant_facts() {
+    let grid = Grid::from_str(
+      "1.........2.........3.........4.........5.........6.........7.........8..........",
+    )
+    .unwrap();
+    let facts = FactFinder::new(&grid).deduce_all();
+    assert!(!facts
+      .iter()
+      .any(|f| f.as_asgmt().is_some_and(|a| a.loc == L99)));
+
+    let facts = FactFinder::new_for_variant(&grid, Variant::X).deduce_all();
+    assert!(facts.contains(&Fact::SingleLoc {
+      num: N9,
+      unit: Unit::Diag(D1),
+      loc: L99,
+    }));
+    assert!(facts.contains(&Fact::SingleNum { loc: L99, num: N9 }));
+  }
+
+  #[test]
+  fn test_x_variant_facts_are_sound() {
+    let id = crate::gen::PuzzleId::new(
+      crate::date::LogicalDate::from_ymd(2025, 3, 14),
+      1,
+      crate::gen::GENERATOR_VERSION,
+    );
+    let puzzle = crate::gen::generate_variant_puzzle(id, Variant::X).unwrap();
+    let solution = puzzle.solution_asgmts();
+    let mut finder = FactFinder::new_for_variant(&puzzle.clues, Variant::X);
+    let mut saw_diag = false;
+    // Applies facts until they run out, which for these sparse puzzles happens
+    // well before the end: the rest takes disproofs.
+    loop {
+      let facts = finder.deduce_valid();
+      let before = finder;
+      for fact in &facts {
+        assert!((fact.as_eliminations() & solution).is_empty(), "{:?}", fact);
+        if let Fact::SingleLoc { unit, .. }
+        | Fact::Overlap { unit, .. }
+        | Fact::Subset { unit, .. } = fact.nub()
+        {
+          saw_diag |= matches!(unit, Unit::Diag(_));
+        }
+      }
+      match facts.iter().find(|f| f.is_asgmt()) {
+        Some(fact) => {
+          assert!(solution.contains(fact.as_asgmt().unwrap()), "{:?}", fact);
+          finder.apply_fact(fact);
+        }
+        None => facts.iter().for_each(|fact| finder.apply_fact(fact)),
+      }
+      if finder == before {
+        break;
+      }
+    }
+    assert!(saw_diag);
+  }
+
+  #[test]
+  fn test_symmetric_placement_facts_are_sound() {
+    // These clues map to themselves under 180-degree rotation once the
+    // numerals are swapped in pairs.
+    let clues = Grid::from_str(
+      "26.....79.1...3..4...8..5.......678...........783.......9..7...1..6...4.58.....32",
+    )
+    .unwrap();
+    let solution = crate::solve::solve(&clues, 1, &mut crate::solve::DefaultHelper());
+    assert_eq!(1, solution.solutions.len());
+    let solution = AsgmtSet::simple_from_grid(&solution.solutions[0].grid());
+    let mut finder = FactFinder::new_with_unique_solution(&clues, Variant::Standard);
+    assert_eq!(Some(Sym::Rotation180), finder.digit_sym().map(|d| d.sym));
+    assert_eq!(
+      finder.digit_sym(),
+      FactFinder::new_counting_solutions(&clues, Variant::Standard).digit_sym()
+    );
+    assert_eq!(None, FactFinder::new(&clues).digit_sym());
+    assert_eq!(
+      None,
+      FactFinder::new_with_unique_solution(&clues, Variant::X).digit_sym()
+    );
+    let mut saw_symmetric = false;
+    loop {
+      let facts = finder.deduce_valid();
+      for fact in &facts {
+        assert!((fact.as_eliminations() & solution).is_empty(), "{:?}", fact);
+        saw_symmetric |= matches!(fact.nub(), Fact::SymmetricPlacement { .. });
+      }
+      let Some(fact) = facts.iter().find(|f| f.is_asgmt()) else {
+        break;
+      };
+      finder.apply_fact(fact);
+    }
+    assert!(saw_symmetric);
+    assert_eq!(81, finder.to_grid().len());
+  }
+
+  #[test]
+  fn test_no_symmetric_placements_without_unique_solution() {
+    // As symmetric as the clues above, but with six solutions, some of which
+    // break the symmetry.
+    let clues = Grid::from_str(
+      "26......9.1...3..4...8..5.......678...........783.......9..7...1..6...4.5......32",
+    )
+    .unwrap();
+    let summary = crate::solve::solve(&clues, 10, &mut crate::solve::DefaultHelper());
+    assert_eq!(6, summary.solutions.len());
+    let mut solutions = AsgmtSet::new();
+    for solution in &summary.solutions {
+      solutions |= AsgmtSet::simple_from_grid(&solution.grid());
+    }
+    assert_eq!(
+      Some(Sym::Rotation180),
+      FactFinder::new_with_unique_solution(&clues, Variant::Standard)
+        .digit_sym()
+        .map(|d| d.sym)
+    );
+
+    let finder = FactFinder::new_counting_solutions(&clues, Variant::Standard);
+    assert_eq!(None, finder.digit_sym());
+    for fact in finder.deduce_valid() {
+      assert!(
+        !matches!(fact.nub(), Fact::SymmetricPlacement { .. }),
+        "{:?}",
+        fact
+      );
+      assert!(
+        (fact.as_eliminations() & solutions).is_empty(),
+        "{:?}",
+        fact
+      );
+    }
+  }
 }
@@ -11,6 +11,7 @@ use crate::core::masks::*;
 use crate::core::set::*;
 use crate::core::*;
 use crate::define_set_operators;
+use crate::sym::DigitSym;
 use crate::time;
 
 use super::Fact;
@@ -25,6 +26,8 @@ pub struct Collector {
   pub start_time_ms: f64,
   pub timed_out: bool,
   pub min_overlap_size: i32,
+  pub variant: Variant,
+  pub digit_sym: Option<DigitSym>,
 }
 
 /// The ways that the collector can handle errors during deduction.
@@ -50,6 +53,8 @@ impl Collector {
       start_time_ms: time::now(),
       timed_out: false,
       min_overlap_size: 1,
+      variant: Variant::Standard,
+      digit_sym: None,
     }
   }
 
@@ -95,6 +100,7 @@ impl Collector {
       if self.check_timeout() {
         break;
       }
+      find_symmetric_placements(self);
       find_subsets(self, &mut set_state);
       if self.check_timeout() {
         break;
@@ -196,6 +202,7 @@ impl Collector {
       if self.check_timeout() {
         break;
       }
+      find_symmetric_placements(self);
       find_subsets(self, &mut set_state);
       if self.check_timeout() {
         break;
@@ -267,6 +274,7 @@ pub struct TreeCollector {
   pub start_time_ms: f64,
   pub timed_out: bool,
   pub min_overlap_size: i32,
+  pub variant: Variant,
 }
 
 impl TreeCollector {
@@ -282,6 +290,7 @@ impl TreeCollector {
       start_time_ms: time::now(),
       timed_out: false,
       min_overlap_size: 2,
+      variant: Variant::Standard,
     }
   }
 
@@ -317,6 +326,12 @@ impl TreeCollector {
         self.remaining_asgmts.remove(asgmt);
         self.actual_asgmts.insert(asgmt);
         self.sukaku_map.apply(asgmt);
+        apply_variant_peers(
+          self.variant,
+          asgmt,
+          &mut self.remaining_asgmts,
+          &mut self.sukaku_map,
+        );
       }
     }
     for elim in &self.levels.last().unwrap().eliminations {
@@ -335,6 +350,7 @@ impl TreeCollector {
       Collector::new(self.remaining_asgmts, self.actual_asgmts, self.sukaku_map);
     temp_collector.found = self.found.clone();
     temp_collector.min_overlap_size = self.min_overlap_size;
+    temp_collector.variant = self.variant;
 
     let _ = find_errors(&mut temp_collector, false);
     for fact in &temp_collector.facts {
@@ -404,6 +420,21 @@ impl TreeCollector {
   }
 }
 
+/// Eliminates the assignments the given one rules out under the variant's
+/// rules beyond the standard ones: its numeral in the rest of its extra units,
+/// and whatever its extra relations exclude.  `AsgmtSet::apply` and
+/// `SukakuMap::apply` take care of the standard peers.
+pub fn apply_variant_peers(
+  variant: Variant,
+  asgmt: Asgmt,
+  remaining_asgmts: &mut AsgmtSet,
+  sukaku_map: &mut SukakuMap,
+) {
+  let exclusions = variant.extra_exclusions(asgmt);
+  *remaining_asgmts -= exclusions;
+  sukaku_map.eliminate(&exclusions);
+}
+
 fn fact_is_implied(
   fact: &Fact,
   remaining_asgmts: &AsgmtSet,
@@ -594,6 +625,30 @@ impl Fact {
         elimination_locs,
         ..
       } => (remaining_asgmts.num_locs(*num) & *elimination_locs).is_empty(),
+      Fact::RelationClaim {
+        relation,
+        num,
+        locs,
+        ..
+      } => relation
+        .excluded_nums(*num)
+        .iter()
+        .all(|n| (remaining_asgmts.num_locs(n) & *locs).is_empty()),
+      Fact::RelationCover { num, locs, .. } => (remaining_asgmts.num_locs(*num) & *locs).is_empty(),
+      Fact::SymmetricPlacement {
+        sym,
+        mapping,
+        num,
+        locs,
+      } => {
+        let Some(digit_sym) = DigitSym::from_mapping(*sym, mapping) else {
+          return false;
+        };
+        let possible = *remaining_asgmts | *actual_asgmts;
+        locs
+          .iter()
+          .all(|loc| digit_sym.excludes(Asgmt::new(*num, loc), &possible))
+      }
     }
   }
 }
@@ -609,18 +664,18 @@ fn find_errors(collector: &mut Collector, short_circuit: bool) -> Result<(), Inv
     }
     if nums.len() > 1 {
       if short_circuit {
-        return Err(Invalid);
+        return Err(Invalid::Loc(loc));
       }
       collector.add_fact(Fact::ConflictLoc { loc, nums });
     }
   }
-  for unit in Unit::all() {
+  for unit in collector.variant.units() {
     let unit_locs = unit.locs();
     for num in Num::all() {
       let actual_locs = collector.actual_asgmts.num_locs(num) & unit_locs;
       if actual_locs.len() > 1 {
         if short_circuit {
-          return Err(Invalid);
+          return Err(Invalid::Unit(unit, num));
         }
         collector.add_fact(Fact::Conflict {
           num,
@@ -631,7 +686,7 @@ fn find_errors(collector: &mut Collector, short_circuit: bool) -> Result<(), Inv
       let possible_locs = possible_asgmts.num_locs(num) & unit_locs;
       if possible_locs.is_empty() {
         if short_circuit {
-          return Err(Invalid);
+          return Err(Invalid::Unit(unit, num));
         }
         collector.add_fact(Fact::NoLoc { num, unit });
       }
@@ -640,7 +695,7 @@ fn find_errors(collector: &mut Collector, short_circuit: bool) -> Result<(), Inv
   for loc in (!collector.actual_asgmts.naked_singles()).iter() {
     if collector.sukaku_map[loc].is_empty() {
       if short_circuit {
-        return Err(Invalid);
+        return Err(Invalid::Loc(loc));
       }
       collector.add_fact(Fact::NoNum { loc });
     }
@@ -665,6 +720,8 @@ fn find_overlaps(collector: &mut Collector) {
         blk_row_bits_to_overlaps(blk_row_bits, num, band, collector, &remaining_asgmts);
         blk_col_bits_to_overlaps(blk_col_bits, num, band, collector, &remaining_asgmts);
       }
+      find_variant_overlaps(num, collector, &remaining_asgmts);
+      find_relation_eliminations(num, collector, &remaining_asgmts);
       let eliminations: Vec<AsgmtSet> = collector.facts[start..]
         .iter()
         .map(|fact| fact.as_eliminations())
@@ -703,21 +760,137 @@ fn find_overlaps(collector: &mut Collector) {
 
 pub const MAX_SET_SIZE: i32 = 4;
 
+/// Finds the overlaps between the variant's extra units and the blocks they
+/// pass through.  (An extra unit's overlap with a line is a single location, so
+/// any deduction from it is a hidden single.)
+fn find_variant_overlaps(num: Num, collector: &mut Collector, remaining_asgmts: &AsgmtSet) {
+  let num_locs = remaining_asgmts.num_locs(num);
+  for &extra_unit in collector.variant.extra_units() {
+    let extra_locs = num_locs & extra_unit.locs();
+    for blk in Blk::all() {
+      if (extra_unit.locs() & blk.locs()).len() < 2 {
+        continue;
+      }
+      let blk_locs = num_locs & blk.locs();
+      let common = extra_locs & blk_locs;
+      if common.is_empty() || common.len() < collector.min_overlap_size {
+        continue;
+      }
+      if extra_locs == common && blk_locs != common {
+        collector.add_fact(Fact::Overlap {
+          num,
+          unit: extra_unit,
+          cross_unit: blk.to_unit(),
+        });
+      }
+      if blk_locs == common && extra_locs != common {
+        collector.add_fact(Fact::Overlap {
+          num,
+          unit: blk.to_unit(),
+          cross_unit: extra_unit,
+        });
+      }
+    }
+  }
+}
+
+/// Finds the eliminations that the variant's extra relations imply for the
+/// given numeral: wherever it goes in a unit, or whichever of its remaining
+/// numerals a location gets, some related locations are ruled out.
+fn find_relation_eliminations(num: Num, collector: &mut Collector, remaining_asgmts: &AsgmtSet) {
+  let num_locs = remaining_asgmts.num_locs(num);
+  for &relation in collector.variant.extra_relations() {
+    let excluded_nums = relation.excluded_nums(num);
+    let mut excluded_locs = LocSet::new();
+    for excluded in excluded_nums.iter() {
+      excluded_locs |= remaining_asgmts.num_locs(excluded);
+    }
+    for unit in collector.variant.units() {
+      let unit_locs = num_locs & unit.locs();
+      if unit_locs.len() < 2 {
+        continue;
+      }
+      let mut locs = excluded_locs - unit_locs;
+      for loc in unit_locs.iter() {
+        locs &= relation.locs(loc);
+      }
+      if !locs.is_empty() {
+        collector.add_fact(Fact::RelationClaim {
+          relation,
+          num,
+          unit,
+          locs,
+        });
+      }
+    }
+    if relation.forbids_sharing() {
+      // A location with one numeral left is a naked single, and excludes its
+      // numeral from related locations once it's assigned.
+      continue;
+    }
+    for loc in Loc::all() {
+      let nums: NumSet = Num::all()
+        .filter(|n| remaining_asgmts.num_locs(*n).contains(loc))
+        .collect();
+      if nums.len() < 2 || !nums.iter().all(|n| relation.excluded_nums(n).contains(num)) {
+        continue;
+      }
+      let locs = num_locs & relation.locs(loc);
+      if !locs.is_empty() {
+        collector.add_fact(Fact::RelationCover {
+          relation,
+          loc,
+          nums,
+          num,
+          locs,
+        });
+      }
+    }
+  }
+}
+
+/// Finds the eliminations that the grid's digit-mapping symmetry implies,
+/// if it has one: each numeral is ruled out wherever one of its symmetric
+/// images is.
+fn find_symmetric_placements(collector: &mut Collector) {
+  let Some(digit_sym) = collector.digit_sym else {
+    return;
+  };
+  let possible = collector.remaining_asgmts | collector.actual_asgmts;
+  for num in Num::all() {
+    let locs: LocSet = collector
+      .remaining_asgmts
+      .num_locs(num)
+      .iter()
+      .filter(|loc| digit_sym.excludes(Asgmt::new(num, *loc), &possible))
+      .collect();
+    if !locs.is_empty() {
+      collector.add_fact(Fact::SymmetricPlacement {
+        sym: digit_sym.sym,
+        mapping: digit_sym.mapping(),
+        num,
+        locs,
+      });
+    }
+  }
+}
+
 fn find_subsets(collector: &mut Collector, set_state: &mut SetState) {
+  let variant = collector.variant;
   for size in 2..=MAX_SET_SIZE {
-    for unit_id in UnitId::all() {
+    for unit in variant.units() {
       if collector.check_timeout() {
         return;
       }
-      find_hidden_sets(collector, set_state, unit_id.to_unit(), size);
+      find_hidden_sets(collector, set_state, unit, size);
     }
   }
   for size in 2..=MAX_SET_SIZE {
-    for unit_id in UnitId::all() {
+    for unit in variant.units() {
       if collector.check_timeout() {
         return;
       }
-      find_naked_sets(collector, set_state, unit_id.to_unit(), size);
+      find_naked_sets(collector, set_state, unit, size);
     }
   }
 }
@@ -733,6 +906,9 @@ fn find_hidden_singles(collector: &mut Collector) {
       units_to_check |=
         blk_line_bits_to_possible_hidden_single_units(blk_row_bits, blk_col_bits, band);
     }
+    for &unit in collector.variant.extra_units() {
+      units_to_check.insert(unit);
+    }
     let mut locs_found = LocSet::default();
     for unit in units_to_check.iter() {
       let unit_locs = num_locs & unit.locs();
@@ -1204,6 +1380,14 @@ impl SukakuMap {
     answer
   }
 
+  pub fn from_asgmts(asgmts: &AsgmtSet) -> Self {
+    let mut answer = Self([NumSet::new(); 81]);
+    for asgmt in asgmts.iter() {
+      answer[asgmt.loc].insert(asgmt.num);
+    }
+    answer
+  }
+
   pub fn apply(&mut self, asgmt: Asgmt) {
     self[asgmt.loc] = NumSet::new(); // Leave the location empty.
     for peer in asgmt.loc.peers().iter() {
@@ -1305,7 +1489,7 @@ fn find_hidden_sets(collector: &mut Collector, set_state: &mut SetState, unit: U
         nums.insert(*num);
       }
       if locs.len() == size {
-        let cross_unit = find_overlapping_unit(unit, locs);
+        let cross_unit = find_overlapping_unit(unit, locs, collector.variant);
         collector.add_fact(Fact::Subset {
           nums,
           unit,
@@ -1349,7 +1533,7 @@ fn find_naked_sets(collector: &mut Collector, set_state: &mut SetState, unit: Un
         nums |= collector.sukaku_map[*loc];
       }
       if nums.len() == size {
-        let cross_unit = find_overlapping_unit(unit, locs);
+        let cross_unit = find_overlapping_unit(unit, locs, collector.variant);
         collector.add_fact(Fact::Subset {
           nums,
           unit,
@@ -1367,7 +1551,10 @@ fn find_naked_sets(collector: &mut Collector, set_state: &mut SetState, unit: Un
   }
 }
 
-fn find_overlapping_unit(unit: Unit, locs: LocSet) -> Option<Unit> {
+/// Finds the unit other than the given one that contains all the given
+/// locations, if there is one.  Prefers standard units to the variant's extra
+/// ones.
+fn find_overlapping_unit(unit: Unit, locs: LocSet, variant: Variant) -> Option<Unit> {
   let mut overlapping_unit = None;
   match unit {
     Unit::Blk(blk) => {
@@ -1419,8 +1606,19 @@ fn find_overlapping_unit(unit: Unit, locs: LocSet) -> Option<Unit> {
         overlapping_unit = Some(Blk::from_bands(row_band, col.band()).to_unit());
       }
     }
+    Unit::Diag(_) => {
+      overlapping_unit = Blk::all()
+        .find(|blk| locs <= blk.locs())
+        .map(|blk| blk.to_unit());
+    }
   };
-  overlapping_unit
+  overlapping_unit.or_else(|| {
+    variant
+      .extra_units()
+      .iter()
+      .copied()
+      .find(|&extra_unit| extra_unit != unit && locs <= extra_unit.locs())
+  })
 }
 
 #[cfg(test)]
@@ -1630,6 +1828,44 @@ mod tests {
     assert_eq!(collector.facts, vec![o1, o2, o3, o4, o5,]);
   }
 
+  #[test]
+  fn test_find_relation_eliminations() {
+    // A knight's move from both of the 1's places in row 1.
+    let mut collector = make_collector(&Grid::new());
+    collector.variant = Variant::AntiKnight;
+    collector
+      .remaining_asgmts
+      .intersect_in_place(N1, !(R1.locs() - loc_set![L13, L17]));
+    find_overlaps(&mut collector);
+    let claim = Fact::RelationClaim {
+      relation: PeerRelation::Knight,
+      num: N1,
+      unit: R1.to_unit(),
+      locs: loc_set![L25],
+    };
+    assert!(collector.facts.contains(&claim));
+    assert_eq!(loc_set![L25], claim.as_eliminations().num_locs(N1));
+
+    // Either numeral left in the middle excludes 5 from its neighbors.
+    let mut collector = make_collector(&Grid::new());
+    collector.variant = Variant::NonConsecutive;
+    for num in Num::all().filter(|num| *num != N4 && *num != N6) {
+      collector.remaining_asgmts.remove(Asgmt::new(num, L55));
+    }
+    find_overlaps(&mut collector);
+    assert!(collector.facts.contains(&Fact::RelationCover {
+      relation: PeerRelation::NonConsecutive,
+      loc: L55,
+      nums: [N4, N6].into_iter().collect(),
+      num: N5,
+      locs: loc_set![L45, L54, L56, L65],
+    }));
+    assert!(!collector
+      .facts
+      .iter()
+      .any(|fact| matches!(fact, Fact::RelationCover { loc, .. } if *loc != L55)));
+  }
+
   #[test]
   fn test_find_overlaps_min_overlap_size_2() {
     let grid = Grid::from_str(
@@ -1909,6 +2145,9 @@ mod tests {
         Fact::EmptyRectangle { .. } => "EmptyRectangle",
         Fact::Skyscraper { .. } => "Skyscraper",
         Fact::TwoStringKite { .. } => "TwoStringKite",
+        Fact::RelationClaim { .. } => "RelationClaim",
+        Fact::RelationCover { .. } => "RelationCover",
+        Fact::SymmetricPlacement { .. } => "SymmetricPlacement",
       };
       *nub_counts.entry(name.to_string()).or_insert(0) += 1;
     }
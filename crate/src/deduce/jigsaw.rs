@@ -0,0 +1,437 @@
+//! Deduces facts about Jigsaw Sudokus, whose blocks are replaced by irregular
+//! regions.  The main deduction code is built around the blocks' alignment
+//! with the bands, so this is a separate, simpler implementation of the basic
+//! patterns: singles, overlaps and subsets.  Its facts are exported to
+//! TypeScript alongside `Fact`, and `deduceJigsawFacts` hands them to the UI.
+
+use itertools::Itertools;
+use serde::Serialize;
+use ts_rs::TS;
+use wasm_bindgen::prelude::{wasm_bindgen, JsError, JsValue};
+
+use crate::core::*;
+use crate::error::Error;
+
+/// One of a Jigsaw Sudoku's units.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, TS)]
+#[ts(export, export_to = "../../www/src/facts/")]
+#[serde(tag = "type", content = "id")]
+pub enum JigsawUnit {
+  Row(Row),
+  Col(Col),
+  Region(Region),
+}
+
+impl JigsawUnit {
+  /// Iterates all the units: rows, then columns, then regions.
+  pub fn all() -> impl Iterator<Item = Self> {
+    Row::all()
+      .map(JigsawUnit::Row)
+      .chain(Col::all().map(JigsawUnit::Col))
+      .chain(Region::all().map(JigsawUnit::Region))
+  }
+
+  /// The locations of this unit under the given layout.
+  pub fn locs(self, regions: &Regions) -> LocSet {
+    match self {
+      JigsawUnit::Row(row) => row.locs(),
+      JigsawUnit::Col(col) => col.locs(),
+      JigsawUnit::Region(region) => regions.locs(region),
+    }
+  }
+}
+
+/// A fact that can be deduced from a Jigsaw Sudoku grid.  These correspond to
+/// the `Fact` kinds of the same names, and serialize the same way.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, TS)]
+#[ts(export, export_to = "../../www/src/facts/")]
+#[serde(tag = "type")]
+pub enum JigsawFact {
+  /// Assignment: the given numeral has only one possible location in the given
+  /// unit.
+  SingleLoc {
+    num: Num,
+    unit: JigsawUnit,
+    loc: Loc,
+  },
+  /// Assignment: the given location has only one possible numeral.
+  SingleNum { loc: Loc, num: Num },
+  /// Elimination: the given numeral can only be within the intersection of the
+  /// two given units, one of which is a region and the other a line, so all
+  /// other locations in the `cross_unit` can be eliminated.
+  Overlap {
+    num: Num,
+    unit: JigsawUnit,
+    cross_unit: JigsawUnit,
+  },
+  /// Elimination: the given numerals can only occupy the given locations within
+  /// the unit, so all other locations in the unit can be eliminated for those
+  /// numerals, and all other numerals can be eliminated from those locations.
+  Subset {
+    nums: NumSet,
+    unit: JigsawUnit,
+    locs: LocSet,
+    is_naked: bool,
+  },
+}
+
+impl JigsawFact {
+  /// Returns the assignment this fact makes, if it's an assignment.
+  pub fn as_asgmt(&self) -> Option<Asgmt> {
+    match *self {
+      JigsawFact::SingleLoc { num, loc, .. } | JigsawFact::SingleNum { loc, num } => {
+        Some(Asgmt { num, loc })
+      }
+      _ => None,
+    }
+  }
+
+  /// Returns the assignments this fact eliminates under the given layout.
+  /// Some of them may already have been eliminated.
+  pub fn as_eliminations(&self, regions: &Regions) -> AsgmtSet {
+    let mut answer = AsgmtSet::new();
+    match self {
+      JigsawFact::SingleLoc { .. } | JigsawFact::SingleNum { .. } => {}
+      JigsawFact::Overlap {
+        num,
+        unit,
+        cross_unit,
+      } => answer.union_in_place(*num, cross_unit.locs(regions) - unit.locs(regions)),
+      JigsawFact::Subset {
+        nums, unit, locs, ..
+      } => {
+        for num in Num::all() {
+          if nums.contains(num) {
+            answer.union_in_place(num, unit.locs(regions) - *locs);
+          } else {
+            answer.union_in_place(num, *locs);
+          }
+        }
+      }
+    }
+    answer
+  }
+}
+
+// The TypeScript type of the facts the entry point below returns, as exported
+// by ts-rs.
+#[wasm_bindgen(typescript_custom_section)]
+const TS_TYPES: &str = r#"
+import type {JigsawFact} from '../../www/src/facts/JigsawFact';
+"#;
+
+/// Deduces the facts that make progress in a Jigsaw Sudoku with the given
+/// clues, whose layout is given as 81 region ordinals in row-major order.
+#[wasm_bindgen(js_name = "deduceJigsawFacts", unchecked_return_type = "JigsawFact[]")]
+pub fn deduce_jigsaw_facts(clues: &Grid, regions: &str) -> Result<JsValue, JsError> {
+  let regions: Regions = regions.parse().map_err(Error::InvalidRegions)?;
+  let facts = JigsawFactFinder::new(clues, &regions).deduce();
+  Ok(serde_wasm_bindgen::to_value(&facts)?)
+}
+
+/// The largest subsets we look for.
+const MAX_SUBSET_SIZE: usize = 4;
+
+/// Finds facts about a Jigsaw Sudoku, and tracks the effects of applying them.
+#[derive(Clone, Debug)]
+pub struct JigsawFactFinder {
+  regions: Regions,
+  /// The numerals assigned so far.
+  grid: Grid,
+  /// The possible numerals for each unassigned location.
+  candidates: [NumSet; 81],
+}
+
+impl JigsawFactFinder {
+  /// Creates a new `JigsawFactFinder` for the given clues and layout.
+  pub fn new(clues: &Grid, regions: &Regions) -> Self {
+    let mut finder = Self {
+      regions: *regions,
+      grid: Grid::new(),
+      candidates: [NumSet::all(); 81],
+    };
+    for asgmt in clues.iter() {
+      finder.apply(asgmt);
+    }
+    finder
+  }
+
+  /// Returns the current state of the grid.
+  pub fn to_grid(&self) -> Grid {
+    self.grid
+  }
+
+  /// The locations where the given numeral may still go, not counting those
+  /// where it's already assigned.
+  pub fn num_locs(&self, num: Num) -> LocSet {
+    Loc::all()
+      .filter(|loc| self.candidates[loc.index()].contains(num))
+      .collect()
+  }
+
+  /// Returns the facts deducible from the current state of the grid, each of
+  /// which makes progress.
+  pub fn deduce(&self) -> Vec<JigsawFact> {
+    let mut facts = Vec::new();
+    let num_locs: Vec<LocSet> = Num::all().map(|num| self.num_locs(num)).collect();
+    self.find_singles(&num_locs, &mut facts);
+    self.find_overlaps(&num_locs, &mut facts);
+    self.find_subsets(&num_locs, &mut facts);
+    facts
+  }
+
+  /// Makes the given fact's assignment, or removes the candidates it rules
+  /// out; the regions say which locations an overlap or subset covers.
+  pub fn apply_fact(&mut self, fact: &JigsawFact) {
+    if let Some(asgmt) = fact.as_asgmt() {
+      self.apply(asgmt);
+    } else {
+      let eliminations = fact.as_eliminations(&self.regions);
+      for num in Num::all() {
+        for loc in eliminations.num_locs(num).iter() {
+          self.candidates[loc.index()].remove(num);
+        }
+      }
+    }
+  }
+
+  /// Assigns the given numeral to the given location, and eliminates it from
+  /// the location's peers.
+  pub fn apply(&mut self, asgmt: Asgmt) {
+    self.grid[asgmt.loc] = Some(asgmt.num);
+    self.candidates[asgmt.loc.index()] = NumSet::new();
+    for peer in self.regions.peers(asgmt.loc).iter() {
+      self.candidates[peer.index()].remove(asgmt.num);
+    }
+  }
+
+  /// Applies every fact of each round of deductions, skipping assignments an
+  /// earlier fact of the round already made, until a round finds nothing.
+  /// Returns whether every location ended up assigned.
+  pub fn solve(&mut self) -> bool {
+    loop {
+      let facts = self.deduce();
+      if facts.is_empty() {
+        return self.grid.len() == 81;
+      }
+      for fact in &facts {
+        if fact
+          .as_asgmt()
+          .is_none_or(|asgmt| self.grid[asgmt.loc].is_none())
+        {
+          self.apply_fact(fact);
+        }
+      }
+    }
+  }
+
+  fn find_singles(&self, num_locs: &[LocSet], facts: &mut Vec<JigsawFact>) {
+    let mut found = LocSet::new();
+    for unit in JigsawUnit::all() {
+      let unit_locs = unit.locs(&self.regions);
+      for num in Num::all() {
+        let locs = num_locs[num.index()] & unit_locs;
+        if locs.len() == 1 {
+          let loc = locs.smallest_item().unwrap();
+          if found.insert(loc) {
+            facts.push(JigsawFact::SingleLoc { num, unit, loc });
+          }
+        }
+      }
+    }
+    for loc in Loc::all() {
+      let candidates = self.candidates[loc.index()];
+      if candidates.len() == 1 && found.insert(loc) {
+        let num = candidates.smallest_item().unwrap();
+        facts.push(JigsawFact::SingleNum { loc, num });
+      }
+    }
+  }
+
+  fn find_overlaps(&self, num_locs: &[LocSet], facts: &mut Vec<JigsawFact>) {
+    for num in Num::all() {
+      let num_locs = num_locs[num.index()];
+      for region in Region::all() {
+        let region_unit = JigsawUnit::Region(region);
+        let region_locs = num_locs & self.regions.locs(region);
+        let lines = Row::all()
+          .map(JigsawUnit::Row)
+          .chain(Col::all().map(JigsawUnit::Col));
+        for line in lines {
+          let line_locs = num_locs & line.locs(&self.regions);
+          let common = region_locs & line_locs;
+          if common.len() < 2 {
+            // A single location in common would make a hidden single.
+            continue;
+          }
+          if common == region_locs && common != line_locs {
+            facts.push(JigsawFact::Overlap {
+              num,
+              unit: region_unit,
+              cross_unit: line,
+            });
+          }
+          if common == line_locs && common != region_locs {
+            facts.push(JigsawFact::Overlap {
+              num,
+              unit: line,
+              cross_unit: region_unit,
+            });
+          }
+        }
+      }
+    }
+  }
+
+  fn find_subsets(&self, num_locs: &[LocSet], facts: &mut Vec<JigsawFact>) {
+    for unit in JigsawUnit::all() {
+      let unit_locs = unit.locs(&self.regions);
+      let open_locs: Vec<Loc> = unit_locs
+        .iter()
+        .filter(|loc| self.grid[*loc].is_none())
+        .collect();
+      let open_nums: Vec<Num> = Num::all()
+        .filter(|num| !(num_locs[num.index()] & unit_locs).is_empty())
+        .collect();
+      for size in 2..=MAX_SUBSET_SIZE.min(open_locs.len().saturating_sub(1)) {
+        for locs in open_locs.iter().copied().combinations(size) {
+          let nums: NumSet = locs
+            .iter()
+            .fold(NumSet::new(), |acc, loc| acc | self.candidates[loc.index()]);
+          if nums.len() as usize != size {
+            continue;
+          }
+          let locs: LocSet = locs.into_iter().collect();
+          let others = unit_locs - locs;
+          if nums
+            .iter()
+            .any(|num| !(num_locs[num.index()] & others).is_empty())
+          {
+            facts.push(JigsawFact::Subset {
+              nums,
+              unit,
+              locs,
+              is_naked: true,
+            });
+          }
+        }
+        for nums in open_nums.iter().copied().combinations(size) {
+          let locs = nums
+            .iter()
+            .fold(LocSet::new(), |acc, num| acc | num_locs[num.index()])
+            & unit_locs;
+          if locs.len() as usize != size {
+            continue;
+          }
+          let nums: NumSet = nums.into_iter().collect();
+          if locs
+            .iter()
+            .any(|loc| !(self.candidates[loc.index()] - nums).is_empty())
+          {
+            facts.push(JigsawFact::Subset {
+              nums,
+              unit,
+              locs,
+              is_naked: false,
+            });
+          }
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::solve::generic::UnitsSolver;
+  use std::str::FromStr;
+
+  #[test]
+  fn test_deductions_are_sound() {
+    let regions: Regions = TRADED_REGIONS.parse().unwrap();
+    let solver = UnitsSolver::for_regions(&regions);
+    // Finds a solution, then keeps 3 of every 4 of its numerals as clues.
+    let mut random = crate::random::new_random("deduce");
+    let solution = solver
+      .random_solution(&Grid::new(), &mut random, 1000)
+      .unwrap();
+    let mut clues = solution;
+    for loc in Loc::all().filter(|loc| loc.index() % 4 == 0) {
+      clues[loc] = None;
+    }
+    assert_eq!(1, solver.solve(&clues, 1).solutions.len());
+    let mut finder = JigsawFactFinder::new(&clues, &regions);
+    loop {
+      let facts = finder.deduce();
+      if facts.is_empty() {
+        break;
+      }
+      for fact in &facts {
+        if let Some(asgmt) = fact.as_asgmt() {
+          assert_eq!(solution[asgmt.loc], Some(asgmt.num), "{:?}", fact);
+        }
+        let eliminations = fact.as_eliminations(&regions);
+        for asgmt in solution.iter() {
+          assert!(
+            !eliminations.num_locs(asgmt.num).contains(asgmt.loc),
+            "{:?}",
+            fact
+          );
+        }
+      }
+      finder.apply_fact(&facts[0]);
+    }
+    assert_eq!(solution, finder.to_grid());
+  }
+
+  #[test]
+  fn test_subsets_and_overlaps() {
+    let regions = Regions::from_str(TRADED_REGIONS).unwrap();
+    let eight = Num::new(8).unwrap();
+    let nine = Num::new(9).unwrap();
+
+    // With only 8 and 9 possible in L18 and L19, the rest of row 1 and of
+    // region 3 can't have them.
+    let mut finder = JigsawFactFinder::new(&Grid::new(), &regions);
+    for loc in [L18, L19] {
+      finder.candidates[loc.index()] = [eight, nine].into_iter().collect();
+    }
+    let facts = finder.deduce();
+    for unit in [JigsawUnit::Row(R1), JigsawUnit::Region(regions.region(L19))] {
+      assert!(facts.contains(&JigsawFact::Subset {
+        nums: [eight, nine].into_iter().collect(),
+        unit,
+        locs: [L18, L19].into_iter().collect(),
+        is_naked: true,
+      }));
+    }
+
+    // With 8 confined to row 1 within region 3, the rest of row 1 can't have
+    // it.
+    let mut finder = JigsawFactFinder::new(&Grid::new(), &regions);
+    for loc in [L27, L28, L29, L37, L38, L39] {
+      finder.candidates[loc.index()].remove(eight);
+    }
+    let facts = finder.deduce();
+    assert!(facts.contains(&JigsawFact::Overlap {
+      num: eight,
+      unit: JigsawUnit::Region(regions.region(L19)),
+      cross_unit: JigsawUnit::Row(R1),
+    }));
+  }
+
+  #[test]
+  fn test_facts_serialize_like_fact() {
+    let regions = Regions::from_str(TRADED_REGIONS).unwrap();
+    let fact = JigsawFact::Overlap {
+      num: Num::new(8).unwrap(),
+      unit: JigsawUnit::Region(regions.region(L19)),
+      cross_unit: JigsawUnit::Row(R1),
+    };
+    assert_eq!(
+      r#"{"type":"Overlap","num":8,"unit":{"type":"Region","id":2},"cross_unit":{"type":"Row","id":0}}"#,
+      serde_json::to_string(&fact).unwrap()
+    );
+  }
+}
@@ -0,0 +1,393 @@
+//! Deduces facts about Killer Sudokus, whose cages add up to given sums.  The
+//! standard deductions come from `FactFinder`; this adds the ones that
+//! depend on the cages: their possible combinations, and the "45 rule."
+
+use crate::core::*;
+
+use super::{Fact, FactFinder};
+
+/// A run of units whose numerals add up to a multiple of 45, for the 45 rule.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum UnitGroup {
+  Blk(Blk),
+  /// The rows from the first through the second, inclusive.
+  Rows(Row, Row),
+  /// The columns from the first through the second, inclusive.
+  Cols(Col, Col),
+}
+
+impl UnitGroup {
+  /// Iterates the groups the 45 rule is applied to: the blocks, and the runs
+  /// of up to `MAX_GROUP_LINES` consecutive rows or columns.
+  pub fn all() -> impl Iterator<Item = Self> {
+    let runs =
+      (1..=MAX_GROUP_LINES as i8).flat_map(|len| (0..=9 - len).map(move |i| (i, i + len - 1)));
+    Blk::all()
+      .map(UnitGroup::Blk)
+      .chain(runs.flat_map(|(first, last)| {
+        [
+          UnitGroup::Rows(Row::new(first).unwrap(), Row::new(last).unwrap()),
+          UnitGroup::Cols(Col::new(first).unwrap(), Col::new(last).unwrap()),
+        ]
+      }))
+  }
+
+  /// The locations of the group's units.
+  pub fn locs(self) -> LocSet {
+    match self {
+      UnitGroup::Blk(blk) => blk.locs(),
+      UnitGroup::Rows(first, last) => Row::all()
+        .filter(|row| (first..=last).contains(row))
+        .fold(LocSet::new(), |acc, row| acc | row.locs()),
+      UnitGroup::Cols(first, last) => Col::all()
+        .filter(|col| (first..=last).contains(col))
+        .fold(LocSet::new(), |acc, col| acc | col.locs()),
+    }
+  }
+
+  /// What the group's numerals add up to.
+  pub fn sum(self) -> i32 {
+    45 * self.locs().len() / 9
+  }
+}
+
+/// A fact that can be deduced from a Killer Sudoku grid.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum KillerFact {
+  /// A fact that holds for the grid's standard rules alone.
+  Standard { fact: Fact },
+  /// Elimination: the given assignments can't be part of any combination of
+  /// numerals that adds up to the sum of the cage with the given index.
+  CageCombinations { cage: usize, eliminations: AsgmtSet },
+  /// Elimination: the given locations are the ones in the group that aren't
+  /// in a cage lying wholly within it, so their open locations add up to the
+  /// given sum; the given assignments can't be part of that sum.
+  Innies {
+    group: UnitGroup,
+    locs: LocSet,
+    sum: i32,
+    eliminations: AsgmtSet,
+  },
+  /// Elimination: the given locations are outside the group but in cages that
+  /// cross into it, so their open locations add up to the given sum; the given
+  /// assignments can't be part of that sum.
+  Outies {
+    group: UnitGroup,
+    locs: LocSet,
+    sum: i32,
+    eliminations: AsgmtSet,
+  },
+}
+
+impl KillerFact {
+  /// Returns the assignment this fact makes, if it's an assignment.
+  pub fn as_asgmt(&self) -> Option<Asgmt> {
+    match self {
+      KillerFact::Standard { fact } => fact.as_asgmt(),
+      _ => None,
+    }
+  }
+
+  /// Returns the assignments this fact eliminates.  Some of them may already
+  /// have been eliminated.
+  pub fn as_eliminations(&self) -> AsgmtSet {
+    match self {
+      KillerFact::Standard { fact } => fact.as_eliminations(),
+      KillerFact::CageCombinations { eliminations, .. }
+      | KillerFact::Innies { eliminations, .. }
+      | KillerFact::Outies { eliminations, .. } => *eliminations,
+    }
+  }
+}
+
+/// The most consecutive rows or columns we apply the 45 rule to.
+const MAX_GROUP_LINES: usize = 3;
+
+/// The most open locations we look at in an innie or outie sum.
+const MAX_SUM_LOCS: usize = 4;
+
+/// Finds facts about a Killer Sudoku, and tracks the effects of applying them.
+#[derive(Clone, Debug)]
+pub struct KillerFactFinder {
+  cages: Cages,
+  finder: FactFinder,
+}
+
+impl KillerFactFinder {
+  /// Creates a new `KillerFactFinder` for the given clues and cages.
+  pub fn new(clues: &Grid, cages: &Cages) -> Self {
+    Self {
+      cages: cages.clone(),
+      // Not `new_with_unique_solution`: the cages needn't share the clues'
+      // symmetry, so the standard finder mustn't assume it.
+      finder: FactFinder::new(clues),
+    }
+  }
+
+  /// The finder for the standard rules, which tracks the grid's state.
+  pub fn fact_finder(&self) -> &FactFinder {
+    &self.finder
+  }
+
+  /// Returns the current state of the grid.
+  pub fn to_grid(&self) -> Grid {
+    self.finder.to_grid()
+  }
+
+  /// Returns all remaining and actual assignments.
+  pub fn possible_asgmts(&self) -> AsgmtSet {
+    self.finder.possible_asgmts()
+  }
+
+  /// Returns the facts that depend on the cages, each of which makes
+  /// progress, followed by the standard facts.  The grid should be valid.
+  pub fn deduce(&self) -> Vec<KillerFact> {
+    let mut facts = self.deduce_cage_facts();
+    facts.extend(
+      self
+        .finder
+        .deduce_valid()
+        .into_iter()
+        .map(|fact| KillerFact::Standard { fact }),
+    );
+    facts
+  }
+
+  /// Returns just the facts that depend on the cages, each of which makes
+  /// progress.
+  pub fn deduce_cage_facts(&self) -> Vec<KillerFact> {
+    let mut facts = Vec::new();
+    let possibles = self.possible_asgmts();
+    for (cage, c) in self.cages.iter().enumerate() {
+      let eliminations = sum_eliminations(&possibles, c.locs, c.sum, c.distinct);
+      if !eliminations.is_empty() {
+        facts.push(KillerFact::CageCombinations { cage, eliminations });
+      }
+    }
+    let covered = self.cages.covered();
+    let grid = self.to_grid();
+    for group in UnitGroup::all() {
+      let group_locs = group.locs();
+      let (inside, crossing): (Vec<&Cage>, Vec<&Cage>) = self
+        .cages
+        .iter()
+        .filter(|c| !(c.locs & group_locs).is_empty())
+        .partition(|c| c.locs <= group_locs);
+      let inside_sum: i32 = inside.iter().map(|c| c.sum).sum();
+      let innies = inside.iter().fold(group_locs, |acc, c| acc - c.locs);
+      let innie_sum = group.sum() - inside_sum;
+      if let Some((locs, sum)) = open_sum(&grid, innies, innie_sum) {
+        let eliminations = sum_eliminations(&possibles, locs, sum, is_within_unit(locs));
+        if !eliminations.is_empty() {
+          facts.push(KillerFact::Innies {
+            group,
+            locs,
+            sum,
+            eliminations,
+          });
+        }
+      }
+      // The outies only add up if the whole group is caged.
+      if !crossing.is_empty() && group_locs <= covered {
+        let crossing_sum: i32 = crossing.iter().map(|c| c.sum).sum();
+        let outies = crossing.iter().fold(LocSet::new(), |acc, c| acc | c.locs) - group_locs;
+        if let Some((locs, sum)) = open_sum(&grid, outies, crossing_sum - innie_sum) {
+          let eliminations = sum_eliminations(&possibles, locs, sum, is_within_unit(locs));
+          if !eliminations.is_empty() {
+            facts.push(KillerFact::Outies {
+              group,
+              locs,
+              sum,
+              eliminations,
+            });
+          }
+        }
+      }
+    }
+    facts
+  }
+
+  /// Hands a standard fact to the inner finder, and otherwise eliminates the
+  /// cage fact's assignments that are still possible.
+  pub fn apply_fact(&mut self, fact: &KillerFact) {
+    match fact {
+      KillerFact::Standard { fact } => self.finder.apply_fact(fact),
+      _ => {
+        let eliminations = fact.as_eliminations() & self.finder.possible_asgmts();
+        for asgmt in eliminations.iter() {
+          self.finder.eliminate(asgmt);
+        }
+      }
+    }
+  }
+
+  /// Runs rounds of standard and cage deductions until one comes up empty,
+  /// dropping any assignment that an earlier fact of its round made or ruled
+  /// out.  Returns whether the grid is full.
+  pub fn solve(&mut self) -> bool {
+    loop {
+      let facts = self.deduce();
+      if facts.is_empty() {
+        return self.to_grid().len() == 81;
+      }
+      for fact in &facts {
+        match fact.as_asgmt() {
+          Some(asgmt) if self.to_grid()[asgmt.loc].is_some() => {}
+          Some(asgmt) if !self.possible_asgmts().contains(asgmt) => {}
+          _ => self.apply_fact(fact),
+        }
+      }
+    }
+  }
+}
+
+/// Drops the assigned locations from the given ones, and their numerals from
+/// the given sum.  Returns None if that leaves no locations or too many.
+fn open_sum(grid: &Grid, locs: LocSet, sum: i32) -> Option<(LocSet, i32)> {
+  let mut open = LocSet::new();
+  let mut sum = sum;
+  for loc in locs.iter() {
+    match grid[loc] {
+      Some(num) => sum -= num.get() as i32,
+      None => {
+        open.insert(loc);
+      }
+    }
+  }
+  (!open.is_empty() && open.len() as usize <= MAX_SUM_LOCS).then_some((open, sum))
+}
+
+/// Tells whether the given locations all lie within a single row, column or
+/// block, and so must have distinct numerals.
+fn is_within_unit(locs: LocSet) -> bool {
+  let Some(loc) = locs.smallest_item() else {
+    return true;
+  };
+  locs <= loc.row().locs() || locs <= loc.col().locs() || locs <= loc.blk().locs()
+}
+
+/// Returns the possible assignments to the given locations that can't be part
+/// of their numerals adding up to the given sum.
+fn sum_eliminations(possibles: &AsgmtSet, locs: LocSet, sum: i32, distinct: bool) -> AsgmtSet {
+  let locs: Vec<Loc> = locs.iter().collect();
+  let candidates: Vec<NumSet> = locs
+    .iter()
+    .map(|loc| {
+      Num::all()
+        .filter(|num| possibles.num_locs(*num).contains(*loc))
+        .collect()
+    })
+    .collect();
+  let narrowed = sum_candidates(&candidates, sum, distinct);
+  let mut eliminations = AsgmtSet::new();
+  for (i, loc) in locs.iter().enumerate() {
+    for num in (candidates[i] - narrowed[i]).iter() {
+      eliminations.insert(Asgmt { num, loc: *loc });
+    }
+  }
+  eliminations
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::random::new_random;
+  use crate::solve::generic::UnitsSolver;
+
+  /// Makes a solution, and cages of up to 3 locations along its rows' blocks.
+  fn killer() -> (SolvedGrid, Cages) {
+    let mut random = new_random("killer deductions");
+    let solution = UnitsSolver::for_regions(&Regions::standard())
+      .random_solution(&Grid::new(), &mut random, 1000)
+      .and_then(|grid| grid.solved_grid())
+      .unwrap();
+    let cages = Loc::all()
+      .filter(|loc| loc.col().index() % 3 == 0)
+      .map(|loc| Cage::from_solution(loc.row().locs() & loc.blk().locs(), &solution))
+      .collect();
+    (solution, Cages::new(cages).unwrap())
+  }
+
+  #[test]
+  fn test_deductions_are_sound() {
+    let (solution, cages) = killer();
+    let mut clues = solution.grid();
+    for loc in Loc::all().filter(|loc| loc.index() % 2 != 0) {
+      clues[loc] = None;
+    }
+    let mut finder = KillerFactFinder::new(&clues, &cages);
+    let mut saw_cage_fact = false;
+    loop {
+      let facts = finder.deduce_cage_facts();
+      if facts.is_empty() {
+        break;
+      }
+      saw_cage_fact = true;
+      for fact in &facts {
+        let eliminations = fact.as_eliminations();
+        for asgmt in solution.grid().iter() {
+          assert!(!eliminations.contains(asgmt), "{:?}", fact);
+        }
+      }
+      finder.apply_fact(&facts[0]);
+    }
+    assert!(saw_cage_fact);
+  }
+
+  #[test]
+  fn test_45_rule() {
+    let locs = |locs: &[Loc]| locs.iter().copied().collect::<LocSet>();
+    let nums = |nums: &[i8]| {
+      nums
+        .iter()
+        .map(|n| Num::new(*n).unwrap())
+        .collect::<NumSet>()
+    };
+    // Row 1 is caged as 2 locations plus L21, then 4 and 3 locations.
+    let cages = Cages::new(vec![
+      Cage::new(locs(&[L11, L12, L21]), 10),
+      Cage::new(locs(&[L13, L14, L15, L16]), 30),
+      Cage::new(locs(&[L17, L18, L19]), 6),
+    ])
+    .unwrap();
+    let finder = KillerFactFinder::new(&Grid::new(), &cages);
+    let facts = finder.deduce_cage_facts();
+    let eliminations = |keep: &[(Loc, NumSet)]| {
+      let mut answer = AsgmtSet::new();
+      for (loc, nums) in keep {
+        for num in (NumSet::all() - *nums).iter() {
+          answer.insert(Asgmt { num, loc: *loc });
+        }
+      }
+      answer
+    };
+    // The row's other cages leave 9 for L11 and L12, so neither can be 9.
+    let innies = KillerFact::Innies {
+      group: UnitGroup::Rows(R1, R1),
+      locs: locs(&[L11, L12]),
+      sum: 9,
+      eliminations: eliminations(&[
+        (L11, nums(&[1, 2, 3, 4, 5, 6, 7, 8])),
+        (L12, nums(&[1, 2, 3, 4, 5, 6, 7, 8])),
+      ]),
+    };
+    assert!(facts.contains(&innies), "{:?}", facts);
+    // And their cage leaves 1 for L21.
+    let outies = KillerFact::Outies {
+      group: UnitGroup::Rows(R1, R1),
+      locs: locs(&[L21]),
+      sum: 1,
+      eliminations: eliminations(&[(L21, nums(&[1]))]),
+    };
+    assert!(facts.contains(&outies), "{:?}", facts);
+    // The 6 in 3 has to be 1, 2 and 3.
+    assert!(facts.contains(&KillerFact::CageCombinations {
+      cage: 2,
+      eliminations: eliminations(&[
+        (L17, nums(&[1, 2, 3])),
+        (L18, nums(&[1, 2, 3])),
+        (L19, nums(&[1, 2, 3]))
+      ]),
+    }));
+  }
+}
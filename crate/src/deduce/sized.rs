@@ -0,0 +1,388 @@
+//! Deduces facts about boards of other sizes than 9x9.  Like the Jigsaw
+//! deductions, these cover just the basic patterns: singles, overlaps and
+//! subsets.
+
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::core::*;
+
+/// A fact that can be deduced from a SizedGrid.  These correspond to the
+/// `Fact` kinds of the same names.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum SizedFact {
+  /// Assignment: the given numeral has only one possible cell in the given
+  /// unit.
+  SingleLoc {
+    num: u8,
+    unit: SizedUnit,
+    cell: usize,
+  },
+  /// Assignment: the given cell has only one possible numeral.
+  SingleNum { cell: usize, num: u8 },
+  /// Elimination: the given numeral can only be within the intersection of the
+  /// two given units, one of which is a block and the other a line, so all
+  /// other cells in the `cross_unit` can be eliminated.
+  Overlap {
+    num: u8,
+    unit: SizedUnit,
+    cross_unit: SizedUnit,
+  },
+  /// Elimination: the given numerals can only occupy the given cells within
+  /// the unit, so all other cells in the unit can be eliminated for those
+  /// numerals, and all other numerals can be eliminated from those cells.
+  Subset {
+    nums: SizedNumSet,
+    unit: SizedUnit,
+    cells: Vec<usize>,
+    is_naked: bool,
+  },
+}
+
+impl SizedFact {
+  /// Returns the cell and numeral this fact assigns, if it's an assignment.
+  pub fn as_asgmt(&self) -> Option<(usize, u8)> {
+    match *self {
+      SizedFact::SingleLoc { num, cell, .. } | SizedFact::SingleNum { cell, num } => {
+        Some((cell, num))
+      }
+      _ => None,
+    }
+  }
+
+  /// Returns the cells and numerals this fact eliminates on a board whose
+  /// blocks have `BR` rows and `BC` columns.  Some of them may already have
+  /// been eliminated.
+  pub fn as_eliminations<const BR: usize, const BC: usize>(&self) -> Vec<(usize, u8)> {
+    match self {
+      SizedFact::SingleLoc { .. } | SizedFact::SingleNum { .. } => vec![],
+      SizedFact::Overlap {
+        num,
+        unit,
+        cross_unit,
+      } => {
+        let unit_cells = SizedGrid::<BR, BC>::unit_cells(*unit);
+        SizedGrid::<BR, BC>::unit_cells(*cross_unit)
+          .into_iter()
+          .filter(|cell| !unit_cells.contains(cell))
+          .map(|cell| (cell, *num))
+          .collect()
+      }
+      SizedFact::Subset {
+        nums, unit, cells, ..
+      } => {
+        let mut answer = vec![];
+        for cell in SizedGrid::<BR, BC>::unit_cells(*unit) {
+          let inside = cells.contains(&cell);
+          for num in SizedNumSet::all(SizedGrid::<BR, BC>::SIZE).iter() {
+            if nums.contains(num) != inside {
+              answer.push((cell, num));
+            }
+          }
+        }
+        answer
+      }
+    }
+  }
+}
+
+/// The largest subsets we look for.
+const MAX_SUBSET_SIZE: usize = 4;
+
+/// Finds facts about a SizedGrid puzzle, and tracks the effects of applying
+/// them.
+#[derive(Clone, Debug)]
+pub struct SizedFactFinder<const BR: usize, const BC: usize> {
+  /// The numerals assigned so far.
+  grid: SizedGrid<BR, BC>,
+  /// The possible numerals for each unassigned cell.
+  candidates: [SizedNumSet; MAX_SIZED_CELLS],
+}
+
+impl<const BR: usize, const BC: usize> SizedFactFinder<BR, BC> {
+  /// Creates a new `SizedFactFinder` for the given clues.
+  pub fn new(clues: &SizedGrid<BR, BC>) -> Self {
+    let mut finder = Self {
+      grid: SizedGrid::new(),
+      candidates: [SizedNumSet::new(); MAX_SIZED_CELLS],
+    };
+    for cell in 0..SizedGrid::<BR, BC>::CELLS {
+      finder.candidates[cell] = SizedNumSet::all(SizedGrid::<BR, BC>::SIZE);
+    }
+    for (cell, num) in clues.iter() {
+      finder.apply(cell, num);
+    }
+    finder
+  }
+
+  /// Returns the current state of the board.
+  pub fn to_grid(&self) -> SizedGrid<BR, BC> {
+    self.grid
+  }
+
+  /// The possible numerals for the given cell, which are none once it's
+  /// assigned.
+  pub fn candidates(&self, cell: usize) -> SizedNumSet {
+    self.candidates[cell]
+  }
+
+  /// Returns the facts deducible from the current state of the board, each of
+  /// which makes progress.
+  pub fn deduce(&self) -> Vec<SizedFact> {
+    let mut facts = Vec::new();
+    self.find_singles(&mut facts);
+    self.find_overlaps(&mut facts);
+    self.find_subsets(&mut facts);
+    facts
+  }
+
+  /// Places the fact's numeral, or strikes the candidates it rules out from
+  /// their cells.
+  pub fn apply_fact(&mut self, fact: &SizedFact) {
+    if let Some((cell, num)) = fact.as_asgmt() {
+      self.apply(cell, num);
+    } else {
+      for (cell, num) in fact.as_eliminations::<BR, BC>() {
+        self.candidates[cell].remove(num);
+      }
+    }
+  }
+
+  /// Assigns the given numeral to the given cell, and eliminates it from the
+  /// cell's peers.
+  pub fn apply(&mut self, cell: usize, num: u8) {
+    self.grid.set(cell, Some(num));
+    self.candidates[cell] = SizedNumSet::new();
+    for peer in SizedGrid::<BR, BC>::peers(cell) {
+      self.candidates[peer].remove(num);
+    }
+  }
+
+  /// Keeps deducing and applying what it finds, skipping placements in cells
+  /// that are already filled, and tells whether the board filled up.
+  pub fn solve(&mut self) -> bool {
+    loop {
+      let facts = self.deduce();
+      if facts.is_empty() {
+        return self.grid.len() == SizedGrid::<BR, BC>::CELLS;
+      }
+      for fact in &facts {
+        if fact
+          .as_asgmt()
+          .is_none_or(|(cell, _)| self.grid.get(cell).is_none())
+        {
+          self.apply_fact(fact);
+        }
+      }
+    }
+  }
+
+  /// The cells of the given unit where the given numeral may still go.
+  fn num_cells(&self, num: u8, unit: SizedUnit) -> Vec<usize> {
+    SizedGrid::<BR, BC>::unit_cells(unit)
+      .into_iter()
+      .filter(|cell| self.candidates[*cell].contains(num))
+      .collect()
+  }
+
+  fn nums(&self) -> impl Iterator<Item = u8> {
+    SizedNumSet::all(SizedGrid::<BR, BC>::SIZE).iter()
+  }
+
+  fn find_singles(&self, facts: &mut Vec<SizedFact>) {
+    let mut found = SizedGrid::<BR, BC>::new();
+    for unit in SizedGrid::<BR, BC>::units() {
+      for num in self.nums() {
+        if let [cell] = self.num_cells(num, unit)[..] {
+          if found.get(cell).is_none() {
+            found.set(cell, Some(num));
+            facts.push(SizedFact::SingleLoc { num, unit, cell });
+          }
+        }
+      }
+    }
+    for cell in 0..SizedGrid::<BR, BC>::CELLS {
+      let candidates = self.candidates[cell];
+      if candidates.len() == 1 && found.get(cell).is_none() {
+        let num = candidates.smallest().unwrap();
+        facts.push(SizedFact::SingleNum { cell, num });
+      }
+    }
+  }
+
+  fn find_overlaps(&self, facts: &mut Vec<SizedFact>) {
+    let size = SizedGrid::<BR, BC>::SIZE;
+    for num in self.nums() {
+      for blk in (0..size).map(SizedUnit::Blk) {
+        let blk_cells = self.num_cells(num, blk);
+        let lines = (0..size)
+          .map(SizedUnit::Row)
+          .chain((0..size).map(SizedUnit::Col));
+        for line in lines {
+          let line_cells = self.num_cells(num, line);
+          let common: Vec<usize> = blk_cells
+            .iter()
+            .copied()
+            .filter(|cell| line_cells.contains(cell))
+            .collect();
+          if common.len() < 2 {
+            // A single cell in common would make a hidden single.
+            continue;
+          }
+          if common == blk_cells && common != line_cells {
+            facts.push(SizedFact::Overlap {
+              num,
+              unit: blk,
+              cross_unit: line,
+            });
+          }
+          if common == line_cells && common != blk_cells {
+            facts.push(SizedFact::Overlap {
+              num,
+              unit: line,
+              cross_unit: blk,
+            });
+          }
+        }
+      }
+    }
+  }
+
+  fn find_subsets(&self, facts: &mut Vec<SizedFact>) {
+    for unit in SizedGrid::<BR, BC>::units() {
+      let unit_cells = SizedGrid::<BR, BC>::unit_cells(unit);
+      let open_cells: Vec<usize> = unit_cells
+        .iter()
+        .copied()
+        .filter(|cell| self.grid.get(*cell).is_none())
+        .collect();
+      let open_nums: Vec<u8> = self
+        .nums()
+        .filter(|num| !self.num_cells(*num, unit).is_empty())
+        .collect();
+      for size in 2..=MAX_SUBSET_SIZE.min(open_cells.len().saturating_sub(1)) {
+        for cells in open_cells.iter().copied().combinations(size) {
+          let nums = cells
+            .iter()
+            .fold(SizedNumSet::new(), |acc, cell| acc | self.candidates[*cell]);
+          if nums.len() != size {
+            continue;
+          }
+          if open_cells
+            .iter()
+            .any(|cell| !cells.contains(cell) && !(self.candidates[*cell] & nums).is_empty())
+          {
+            facts.push(SizedFact::Subset {
+              nums,
+              unit,
+              cells,
+              is_naked: true,
+            });
+          }
+        }
+        for nums in open_nums.iter().copied().combinations(size) {
+          let nums: SizedNumSet = nums.into_iter().collect();
+          let cells: Vec<usize> = open_cells
+            .iter()
+            .copied()
+            .filter(|cell| !(self.candidates[*cell] & nums).is_empty())
+            .collect();
+          if cells.len() != size {
+            continue;
+          }
+          if cells
+            .iter()
+            .any(|cell| !(self.candidates[*cell] - nums).is_empty())
+          {
+            facts.push(SizedFact::Subset {
+              nums,
+              unit,
+              cells,
+              is_naked: false,
+            });
+          }
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::random::new_random;
+  use crate::solve::sized::SizedSolver;
+  use std::str::FromStr;
+
+  #[test]
+  fn test_deductions_are_sound() {
+    // Finds a 6x6 solution, then removes every clue it can while keeping the
+    // solution unique.
+    let solver = SizedSolver::<2, 3>::new();
+    let mut random = new_random("deduce");
+    let solution = solver
+      .random_solution(&Grid6::new(), &mut random, 1000)
+      .unwrap();
+    let mut clues = solution;
+    for cell in 0..Grid6::CELLS {
+      let num = clues.get(cell);
+      clues.set(cell, None);
+      if solver.solve(&clues, 1).too_many_solutions {
+        clues.set(cell, num);
+      }
+    }
+    let mut finder = SizedFactFinder::new(&clues);
+    loop {
+      let facts = finder.deduce();
+      if facts.is_empty() {
+        break;
+      }
+      for fact in &facts {
+        if let Some((cell, num)) = fact.as_asgmt() {
+          assert_eq!(solution.get(cell), Some(num), "{:?}", fact);
+        }
+        for (cell, num) in fact.as_eliminations::<2, 3>() {
+          assert_ne!(solution.get(cell), Some(num), "{:?}", fact);
+        }
+      }
+      finder.apply_fact(&facts[0]);
+    }
+    assert_eq!(solution, finder.to_grid());
+  }
+
+  #[test]
+  fn test_subsets_and_overlaps() {
+    // With only 3 and 4 possible in the first two cells, the rest of row 1 and
+    // of block 1 can't have them.
+    let mut finder = SizedFactFinder::new(&Grid4::new());
+    for cell in [0, 1] {
+      finder.candidates[cell] = [3, 4].into_iter().collect();
+    }
+    let facts = finder.deduce();
+    for unit in [SizedUnit::Row(0), SizedUnit::Blk(0)] {
+      assert!(facts.contains(&SizedFact::Subset {
+        nums: [3, 4].into_iter().collect(),
+        unit,
+        cells: vec![0, 1],
+        is_naked: true,
+      }));
+    }
+
+    // With 2 confined to the top row within block 2, the rest of that row
+    // can't have it.
+    let grid = Grid16::from_str(&".".repeat(256)).unwrap();
+    let mut finder = SizedFactFinder::new(&grid);
+    for cell in Grid16::unit_cells(SizedUnit::Blk(1)).into_iter().skip(4) {
+      finder.candidates[cell].remove(2);
+    }
+    let facts = finder.deduce();
+    let overlap = SizedFact::Overlap {
+      num: 2,
+      unit: SizedUnit::Blk(1),
+      cross_unit: SizedUnit::Row(0),
+    };
+    assert!(facts.contains(&overlap));
+    assert_eq!(12, overlap.as_eliminations::<4, 4>().len());
+  }
+}
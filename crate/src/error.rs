@@ -0,0 +1,102 @@
+//! Defines the errors Luke-doku reports to its callers, in particular across
+//! the wasm boundary.
+
+use std::fmt;
+
+use crate::core::Invalid;
+
+/// The ways a request to Luke-doku can fail.  The wasm entry points hand these
+/// to JavaScript as `Error` objects carrying the message.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+  /// A location index wasn't in the range 0 through 80.
+  InvalidLoc(i32),
+  /// A numeral wasn't in the range 1 through 9.
+  InvalidNum(i32),
+  /// A list of elimination constraints couldn't be read.
+  MalformedConstraints(String),
+  /// An assignment couldn't be read as a location index and a numeral.
+  MalformedAsgmt(String),
+  /// A grid contradicted the rules of Sudoku.
+  InvalidGrid(Invalid),
+  /// A Jigsaw Sudoku's layout of regions couldn't be read.
+  InvalidRegions(String),
+  /// A puzzle ID wasn't of the form `date:counter:version`.
+  MalformedPuzzleId(String),
+  /// A share code was garbled, or didn't name a puzzle.
+  MalformedShareCode(String),
+  /// Something was made by a version of Luke-doku this one doesn't support.
+  VersionMismatch {
+    /// What the version number is for.
+    what: &'static str,
+    /// The version asked for.
+    version: i32,
+    /// The versions this code supports, as an inclusive range.
+    supported: (i32, i32),
+  },
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Error::InvalidLoc(loc) => write!(f, "Location index {} is not in 0 through 80", loc),
+      Error::InvalidNum(num) => write!(f, "Numeral {} is not in 1 through 9", num),
+      Error::MalformedConstraints(message) => {
+        write!(f, "Malformed elimination constraints: {}", message)
+      }
+      Error::MalformedAsgmt(message) => write!(f, "Malformed assignment: {}", message),
+      Error::InvalidGrid(invalid) => write!(f, "Invalid grid: {}", invalid),
+      Error::InvalidRegions(message) => write!(f, "Invalid Jigsaw layout: {}", message),
+      Error::MalformedPuzzleId(message) => write!(f, "Malformed puzzle ID: {}", message),
+      Error::MalformedShareCode(message) => write!(f, "Malformed share code: {}", message),
+      Error::VersionMismatch {
+        what,
+        version,
+        supported: (min, max),
+      } => write!(
+        f,
+        "Version {} of the {} is not supported; this supports versions {} through {}.",
+        version, what, min, max
+      ),
+    }
+  }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Invalid> for Error {
+  fn from(invalid: Invalid) -> Self {
+    Error::InvalidGrid(invalid)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::core::*;
+
+  #[test]
+  fn messages() {
+    assert_eq!(
+      "Location index 81 is not in 0 through 80",
+      Error::InvalidLoc(81).to_string()
+    );
+    assert_eq!(
+      "Invalid grid: R3 has no consistent location for 7",
+      Error::from(Invalid::Unit(
+        Unit::Row(Row::new(2).unwrap()),
+        Num::new(7).unwrap()
+      ))
+      .to_string()
+    );
+    assert_eq!(
+      "Version 0 of the generator is not supported; this supports versions 1 through 1.",
+      Error::VersionMismatch {
+        what: "generator",
+        version: 0,
+        supported: (1, 1)
+      }
+      .to_string()
+    );
+  }
+}
@@ -1,16 +1,21 @@
 //! Defines the evaluator for Luke-doku.
 
 use num_derive::FromPrimitive;
+use serde::Serialize;
 use wasm_bindgen::prelude::wasm_bindgen;
 
-use crate::gen::Puzzle;
-use crate::deduce::Fact;
+use crate::core::{AsgmtSet, CandidateGrid};
+use crate::deduce::killer::KillerFact;
+use crate::deduce::{Fact, FactFinder};
+use crate::gen::killer::KillerPuzzle;
+use crate::gen::{Puzzle, MAX_SOLUTIONS};
+use crate::solve::{solve_candidates, DefaultHelper};
 
 mod internals;
 
 /// The evaluated complexity of a puzzle.  The numeric value corresponds to a
 /// number of stars.
-#[derive(Clone, Copy, Debug, Eq, FromPrimitive, Hash, Ord, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Debug, Eq, FromPrimitive, Hash, Ord, PartialEq, PartialOrd, Serialize)]
 #[wasm_bindgen]
 #[repr(C)]
 pub enum Complexity {
@@ -27,8 +32,9 @@ pub enum Complexity {
 }
 
 /// A puzzle's rating, the result of evaluating it.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
 #[wasm_bindgen]
+#[serde(rename_all = "camelCase")]
 pub struct Rating {
   /// How intrinsically hard the puzzle is.
   pub complexity: Complexity,
@@ -55,13 +61,61 @@ pub fn evaluate(puzzle: &Puzzle) -> Rating {
   evaluate_with_observer(puzzle, |_| {})
 }
 
-/// Evaluates a puzzle's complexity and also notifies an observer whenever a 
+/// Evaluates a puzzle's complexity and also notifies an observer whenever a
 /// deduction (Fact) is successfully applied during the evaluation.
 pub fn evaluate_with_observer<F>(puzzle: &Puzzle, observer: F) -> Rating
 where
   F: FnMut(&Fact),
 {
-  let complexity = internals::evaluate_complexity(puzzle, observer);
+  rating(internals::evaluate_complexity(puzzle, observer))
+}
+
+/// Evaluates a Killer puzzle's complexity.
+pub fn evaluate_killer(puzzle: &KillerPuzzle) -> Rating {
+  evaluate_killer_with_observer(puzzle, |_| {})
+}
+
+/// Evaluates a Killer puzzle like `evaluate_killer`, and also notifies an
+/// observer whenever a deduction is applied during the evaluation.
+pub fn evaluate_killer_with_observer<F>(puzzle: &KillerPuzzle, observer: F) -> Rating
+where
+  F: FnMut(&KillerFact),
+{
+  rating(internals::evaluate_killer_complexity(puzzle, observer))
+}
+
+/// Evaluates a Sukaku puzzle, or what's left of a puzzle after ruling out the
+/// missing candidates.  Returns None if the candidates have no solutions or
+/// more than a puzzle may.
+#[wasm_bindgen(js_name = "evaluateCandidates")]
+pub fn evaluate_candidates(candidates: &CandidateGrid) -> Option<Rating> {
+  evaluate_candidates_with_observer(candidates, |_| {})
+}
+
+/// Evaluates a Sukaku puzzle like `evaluate_candidates`, and also notifies an
+/// observer whenever a deduction (Fact) is applied during the evaluation.
+pub fn evaluate_candidates_with_observer<F>(
+  candidates: &CandidateGrid,
+  observer: F,
+) -> Option<Rating>
+where
+  F: FnMut(&Fact),
+{
+  let summary = solve_candidates(candidates, MAX_SOLUTIONS, &mut DefaultHelper());
+  if summary.solutions.is_empty() || summary.too_many_solutions {
+    return None;
+  }
+  let mut solution = AsgmtSet::new();
+  for s in &summary.solutions {
+    solution |= AsgmtSet::simple_from_grid(&s.grid());
+  }
+  let finder = FactFinder::from_candidates(candidates);
+  Some(rating(internals::evaluate_fact_finder(
+    finder, solution, observer,
+  )))
+}
+
+fn rating(complexity: Complexity) -> Rating {
   let estimated_time_ms = 0.0; // TODO: implement this
   Rating {
     complexity,
@@ -3,15 +3,33 @@
 use super::*;
 use crate::{
   core::{AsgmtSet, Invalid, NumSet, Set, Unit},
+  deduce::killer::{KillerFact, KillerFactFinder},
   deduce::{Fact, FactFinder},
+  gen::killer::KillerPuzzle,
 };
 
-pub fn evaluate_complexity<F>(puzzle: &Puzzle, mut observer: F) -> Complexity
+pub fn evaluate_complexity<F>(puzzle: &Puzzle, observer: F) -> Complexity
+where
+  F: FnMut(&Fact),
+{
+  let fact_finder = if puzzle.solutions.len() == 1 {
+    FactFinder::new_with_unique_solution(&puzzle.clues, puzzle.variant)
+  } else {
+    FactFinder::new_for_variant(&puzzle.clues, puzzle.variant)
+  };
+  evaluate_fact_finder(fact_finder, puzzle.solution_asgmts(), observer)
+}
+
+/// Evaluates the complexity of getting from the fact finder's state to the
+/// given solution assignments.
+pub fn evaluate_fact_finder<F>(
+  mut fact_finder: FactFinder,
+  solution: AsgmtSet,
+  mut observer: F,
+) -> Complexity
 where
   F: FnMut(&Fact),
 {
-  let solution = puzzle.solution_asgmts();
-  let mut fact_finder = FactFinder::new(&puzzle.clues);
   let mut answer = Complexity::Simple;
   loop {
     let facts = find_facts(&fact_finder, answer);
@@ -51,6 +69,64 @@ where
   answer
 }
 
+/// Evaluates the complexity of a Killer puzzle.  Standard assignments are
+/// rated as usual, and the facts that depend on the cages are moderate.  A
+/// puzzle these can't finish is rated expert: the Killer path doesn't look for
+/// disproofs.
+pub fn evaluate_killer_complexity<F>(puzzle: &KillerPuzzle, mut observer: F) -> Complexity
+where
+  F: FnMut(&KillerFact),
+{
+  let mut finder = KillerFactFinder::new(&puzzle.clues, &puzzle.cages);
+  let solution = AsgmtSet::simple_from_grid(&puzzle.solution.grid());
+  let mut answer = Complexity::Simple;
+  loop {
+    let singles = finder.fact_finder().deduce_singles();
+    let facts: Vec<(KillerFact, Complexity)> = if !singles.is_empty() {
+      singles
+        .into_iter()
+        .map(|fact| (KillerFact::Standard { fact }, Complexity::Simple))
+        .collect()
+    } else {
+      let cage_facts = finder.deduce_cage_facts();
+      if !cage_facts.is_empty() {
+        cage_facts
+          .into_iter()
+          .map(|fact| (fact, Complexity::Moderate))
+          .collect()
+      } else {
+        finder
+          .fact_finder()
+          .deduce_all()
+          .into_iter()
+          .filter(|fact| fact.is_asgmt())
+          .map(|fact| {
+            let complexity = fact.complexity();
+            (KillerFact::Standard { fact }, complexity)
+          })
+          .collect()
+      }
+    };
+    let Some(min_complexity) = facts.iter().map(|(_, complexity)| *complexity).min() else {
+      break;
+    };
+    answer = answer.max(min_complexity);
+    for (fact, complexity) in &facts {
+      let stale = fact
+        .as_asgmt()
+        .is_some_and(|asgmt| !finder.possible_asgmts().contains(asgmt));
+      if *complexity <= answer && !stale {
+        observer(fact);
+        finder.apply_fact(fact);
+      }
+    }
+  }
+  if finder.possible_asgmts() != solution {
+    answer = Complexity::Expert;
+  }
+  answer
+}
+
 fn find_facts(fact_finder: &FactFinder, answer: Complexity) -> Vec<Fact> {
   if answer == Complexity::Simple {
     let singles = fact_finder.deduce_singles();
@@ -163,7 +239,10 @@ impl Fact {
         }
       }
       Fact::TwoStringKite { .. } => Complexity::Moderate,
-      Fact::EmptyRectangle { .. } | Fact::Skyscraper { .. } => Complexity::Complex,
+      Fact::RelationClaim { .. } | Fact::RelationCover { .. } => Complexity::Moderate,
+      Fact::EmptyRectangle { .. } | Fact::Skyscraper { .. } | Fact::SymmetricPlacement { .. } => {
+        Complexity::Complex
+      }
       _ => Complexity::Complex,
     }
   }
@@ -189,6 +268,10 @@ impl Fact {
       | Fact::EmptyRectangle { num, .. }
       | Fact::Skyscraper { num, .. }
       | Fact::TwoStringKite { num, .. } => NumSet::singleton(*num),
+      Fact::RelationClaim { relation, num, .. } => relation.excluded_nums(*num),
+      Fact::RelationCover { num, .. } | Fact::SymmetricPlacement { num, .. } => {
+        NumSet::singleton(*num)
+      }
       _ => NumSet::new(),
     }
   }
@@ -197,7 +280,12 @@ impl Fact {
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::{core::Grid, gen::Puzzle};
+  use crate::{
+    core::{Asgmt, CandidateGrid, Grid, Num, L11},
+    date::LogicalDate,
+    gen::killer::generate_killer_puzzle,
+    gen::{Puzzle, PuzzleId},
+  };
   use std::str::FromStr;
 
   fn eval_complexity(s: &str) -> Complexity {
@@ -266,6 +354,45 @@ mod tests {
     assert_eq!(complexity, Complexity::Complex);
   }
 
+  #[test]
+  fn test_evaluate_candidates() {
+    let grid = Grid::from_str(
+      r"
+      7 . 6 | . 8 . | . 5 2
+      . . . | 5 4 . | . . .
+      . 9 5 | . . . | . . 8
+      - - - + - - - + - - -
+      . . 4 | 6 . . | 5 8 .
+      . 2 . | 4 7 5 | . 9 1
+      . 5 . | 8 . 3 | 2 4 .
+      - - - + - - - + - - -
+      3 . . | . . 4 | 8 2 5
+      . . 1 | . 5 8 | . . .
+      5 . . | . . . | 7 1 4
+    ",
+    )
+    .unwrap();
+    // The puzzle's candidates make a Sukaku of the same complexity.
+    let mut candidates = CandidateGrid::from_grid(&grid);
+    let rating = evaluate_candidates(&candidates).unwrap();
+    assert_eq!(Complexity::Complex, rating.complexity);
+
+    // Ruling out all but one wrong candidate leaves only singles.
+    let solution = Puzzle::new(&grid, None).unwrap().solution_asgmts();
+    let wrong: Vec<_> = (candidates.asgmts() - solution).iter().collect();
+    for &asgmt in &wrong[1..] {
+      candidates.remove(asgmt);
+    }
+    let rating = evaluate_candidates(&candidates).unwrap();
+    assert_eq!(Complexity::Simple, rating.complexity);
+
+    // Without any candidates for a location, there's no puzzle.
+    for num in Num::all() {
+      candidates.remove(Asgmt::new(num, L11));
+    }
+    assert_eq!(None, evaluate_candidates(&candidates));
+  }
+
   #[test]
   fn test_evaluate_complexity_expert() {
     let complexity = eval_complexity(
@@ -305,4 +432,20 @@ mod tests {
     );
     assert_eq!(complexity, Complexity::Lunatic);
   }
+
+  #[test]
+  fn test_evaluate_killer() {
+    let id = PuzzleId::new(LogicalDate::from_ymd(2025, 3, 14), 1, 1);
+    let puzzle = generate_killer_puzzle(id).unwrap();
+    let solution = puzzle.solution.grid();
+    let mut saw_cage_fact = false;
+    let complexity = evaluate_killer_complexity(&puzzle, |fact| {
+      saw_cage_fact |= !matches!(fact, KillerFact::Standard { .. });
+      if let Some(asgmt) = fact.as_asgmt() {
+        assert_eq!(Some(asgmt.num), solution[asgmt.loc], "{:?}", fact);
+      }
+    });
+    assert!(saw_cage_fact);
+    assert_eq!(Complexity::Moderate, complexity);
+  }
 }
@@ -0,0 +1,645 @@
+//! Reads and writes Sudoku puzzles in the text formats used by other Sudoku
+//! programs.
+//!
+//! Besides the plain 81-character form that `Grid::from_str` reads, this
+//! handles:
+//!
+//! - HoDoKu's library format, `:tttt:c:<grid>:<deleted candidates>:...`, where
+//!   a `+` before a digit marks it as placed rather than given, and deleted
+//!   candidates are listed as digit-row-column triples.
+//! - Pencil-mark grids, with each location's candidates written out between
+//!   `|` separators.  HoDoKu draws their borders with `.`, `:` and `'`, and
+//!   marks placed digits with `+`; SudokuWiki draws them with `*` and `+`.
+//! - Simple Sudoku's `.ss` files: rows like `..3|.1.|...`, with lines of `-`
+//!   between bands, and optionally a second grid holding the current state.
+//! - SadMan Sudoku's `.sdk` files: nine rows of nine characters, optionally
+//!   preceded by `#` metadata lines and a `[Puzzle]` header, and followed by a
+//!   `[State]` section holding the current state.
+//! - 729-character pencil marks: nine characters per location, with the digit
+//!   itself for a candidate and `.` or `0` for a non-candidate.
+
+use std::fmt::Write;
+
+use wasm_bindgen::prelude::*;
+
+use crate::core::*;
+
+/// The formats this module reads and writes.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[wasm_bindgen]
+pub enum Format {
+  /// 81 characters, with digits for clues and `.` or `0` for blanks.
+  Plain,
+  /// A line from a HoDoKu puzzle library.
+  HoDoKuLibrary,
+  /// A pencil-mark grid as HoDoKu draws it.
+  HoDoKuGrid,
+  /// A pencil-mark grid as SudokuWiki draws it.
+  SudokuWikiGrid,
+  /// A Simple Sudoku `.ss` file.
+  SimpleSudoku,
+  /// A SadMan Sudoku `.sdk` file.
+  SadMan,
+  /// 729 characters of pencil marks.
+  PencilMarks,
+}
+
+/// A puzzle read from text, possibly partway through being solved.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[wasm_bindgen]
+pub struct Imported {
+  /// The format the text was in.
+  pub format: Format,
+  /// The puzzle's clues.
+  pub givens: Grid,
+  /// The numerals the solver has filled in, which never overlap the givens.
+  pub placed: Grid,
+  /// The candidates the solver has ruled out in the locations that are still
+  /// empty, for formats that have candidates.  This includes candidates ruled
+  /// out just by the numerals in the grid.
+  #[wasm_bindgen(skip)]
+  pub eliminations: Option<AsgmtSet>,
+}
+
+#[wasm_bindgen]
+impl Imported {
+  /// Returns the givens and the placed numerals together.
+  pub fn current(&self) -> Grid {
+    let mut grid = self.givens;
+    for asgmt in self.placed.iter() {
+      grid[asgmt.loc] = Some(asgmt.num);
+    }
+    grid
+  }
+
+  /// Tells whether the text recorded which candidates the solver has ruled
+  /// out.
+  #[wasm_bindgen(js_name = "hasEliminations")]
+  pub fn has_eliminations(&self) -> bool {
+    self.eliminations.is_some()
+  }
+
+  /// Returns the candidates that remain: the numerals in filled locations,
+  /// and whatever hasn't been ruled out in the empty ones.
+  pub fn candidates(&self) -> CandidateGrid {
+    let possibles = AsgmtSet::possibles_from_grid(&self.current());
+    CandidateGrid::from_asgmts(possibles - self.eliminations.unwrap_or_default())
+  }
+}
+
+/// Parses a puzzle in any of the supported formats, working out which one it
+/// is.
+pub fn parse(text: &str) -> Result<Imported, String> {
+  let trimmed = text.trim();
+  if trimmed.starts_with(':') {
+    return parse_hodoku_library(trimmed);
+  }
+  let marks: String = trimmed.chars().filter(|c| !c.is_whitespace()).collect();
+  if marks.len() == 729 && marks.chars().all(|c| c == '.' || c.is_ascii_digit()) {
+    return parse_pencil_marks(&marks);
+  }
+  if let Some(imported) = parse_candidate_grid(trimmed)? {
+    return Ok(imported);
+  }
+  if trimmed.lines().any(is_ss_separator) {
+    return parse_simple_sudoku(trimmed);
+  }
+  if trimmed.contains("[Puzzle]") || trimmed.lines().any(|line| line.starts_with('#')) {
+    return parse_sadman(trimmed);
+  }
+  Ok(Imported {
+    format: Format::Plain,
+    givens: trimmed.parse()?,
+    placed: Grid::new(),
+    eliminations: None,
+  })
+}
+
+/// Writes a puzzle in the given format.  Anything the format can't represent
+/// is dropped: the candidates, for formats without them; the distinction
+/// between given and placed numerals, for SudokuWiki grids; and the placed
+/// numerals, for the plain format.  729-character pencil marks write the
+/// numerals as single candidates.
+pub fn write(imported: &Imported, format: Format) -> String {
+  let current = imported.current();
+  let eliminations = imported
+    .eliminations
+    .unwrap_or_else(|| implied_eliminations(&current));
+  match format {
+    Format::Plain => imported.givens.to_flat_string(),
+    Format::HoDoKuLibrary => {
+      let mut grid = String::new();
+      for loc in Loc::all() {
+        if let Some(num) = imported.placed[loc] {
+          grid.push('+');
+          grid.push(digit(num));
+        } else {
+          grid.push(imported.givens[loc].map_or('.', digit));
+        }
+      }
+      // HoDoKu lists only the deletions the numerals don't already imply.
+      let deleted = eliminations - implied_eliminations(&current);
+      let deleted: Vec<String> = Loc::all()
+        .flat_map(|loc| Num::all().map(move |num| Asgmt::new(num, loc)))
+        .filter(|&asgmt| deleted.contains(asgmt))
+        .map(|a| {
+          format!(
+            "{}{}{}",
+            a.num.get(),
+            a.loc.row().ordinal(),
+            a.loc.col().ordinal()
+          )
+        })
+        .collect();
+      format!(":0000:x:{}:{}::", grid, deleted.join(" "))
+    }
+    Format::HoDoKuGrid | Format::SudokuWikiGrid => {
+      let tokens: Vec<String> = Loc::all()
+        .map(|loc| match (imported.givens[loc], imported.placed[loc]) {
+          (Some(num), _) => digit(num).to_string(),
+          (None, Some(num)) if format == Format::HoDoKuGrid => format!("+{}", digit(num)),
+          (None, Some(num)) => digit(num).to_string(),
+          (None, None) => Num::all()
+            .filter(|&num| !eliminations.contains(Asgmt::new(num, loc)))
+            .map(digit)
+            .collect(),
+        })
+        .collect();
+      write_candidate_grid(&tokens, format)
+    }
+    Format::SimpleSudoku => {
+      let mut text = write_rows(&imported.givens, '.', "|", "-----------");
+      if imported.placed.len() > 0 {
+        text.push('\n');
+        text += &write_rows(&current, '.', "|", "-----------");
+      }
+      text
+    }
+    Format::SadMan => {
+      let mut text = format!("[Puzzle]\n{}", write_rows(&imported.givens, '.', "", ""));
+      if imported.placed.len() > 0 {
+        text += &format!("[State]\n{}", write_rows(&current, '.', "", ""));
+      }
+      text
+    }
+    Format::PencilMarks => Loc::all()
+      .flat_map(|loc| Num::all().map(move |num| (loc, num)))
+      .map(|(loc, num)| match current[loc] {
+        Some(n) if n == num => digit(num),
+        None if !eliminations.contains(Asgmt::new(num, loc)) => digit(num),
+        _ => '.',
+      })
+      .collect(),
+  }
+}
+
+/// Parses a puzzle in any of the supported formats.
+#[wasm_bindgen(js_name = "importPuzzle")]
+pub fn import_puzzle(text: &str) -> Result<Imported, String> {
+  parse(text)
+}
+
+/// Writes a puzzle in the given format.
+#[wasm_bindgen(js_name = "exportPuzzle")]
+pub fn export_puzzle(imported: &Imported, format: Format) -> String {
+  write(imported, format)
+}
+
+fn digit(num: Num) -> char {
+  (b'0' + num.get() as u8) as char
+}
+
+fn num_of(c: char) -> Option<Num> {
+  c.to_digit(10).and_then(|d| Num::new(d as i8))
+}
+
+/// Returns the candidates of the grid's empty locations that are ruled out by
+/// the numerals in the grid.
+fn implied_eliminations(grid: &Grid) -> AsgmtSet {
+  let mut filled = AsgmtSet::new();
+  for loc in Loc::all().filter(|&loc| grid[loc].is_some()) {
+    for num in Num::all() {
+      filled.insert(Asgmt::new(num, loc));
+    }
+  }
+  !AsgmtSet::possibles_from_grid(grid) - filled
+}
+
+/// Restricts a set of eliminations to the grid's empty locations.
+fn unfilled_only(eliminations: AsgmtSet, grid: &Grid) -> AsgmtSet {
+  let mut answer = eliminations;
+  for asgmt in grid.iter() {
+    for num in Num::all() {
+      answer.remove(Asgmt::new(num, asgmt.loc));
+    }
+  }
+  answer
+}
+
+fn parse_hodoku_library(text: &str) -> Result<Imported, String> {
+  let fields: Vec<&str> = text.split(':').collect();
+  if fields.len() < 5 {
+    return Err(format!("Too few fields in HoDoKu library entry {}", text));
+  }
+  let mut givens = Grid::new();
+  let mut placed = Grid::new();
+  let mut chars = fields[3].chars().filter(|c| !c.is_whitespace());
+  for loc in Loc::all() {
+    match chars.next() {
+      Some('+') => {
+        let c = chars.next().unwrap_or('.');
+        placed[loc] = Some(num_of(c).ok_or_else(|| format!("Bad placed digit {}", c))?);
+      }
+      Some('.' | '0') => {}
+      Some(c) => givens[loc] = Some(num_of(c).ok_or_else(|| format!("Bad digit {}", c))?),
+      None => return Err(format!("Fewer than 81 locations in {}", fields[3])),
+    }
+  }
+  if chars.next().is_some() {
+    return Err(format!("More than 81 locations in {}", fields[3]));
+  }
+  let mut imported = Imported {
+    format: Format::HoDoKuLibrary,
+    givens,
+    placed,
+    eliminations: None,
+  };
+  let current = imported.current();
+  let mut eliminations = implied_eliminations(&current);
+  for triple in fields[4].split_whitespace() {
+    let digits: Vec<Num> = triple.chars().filter_map(num_of).collect();
+    let [num, row, col] = digits[..] else {
+      return Err(format!("Bad deleted candidate {}", triple));
+    };
+    let loc = Loc::from_index(row.index() * 9 + col.index()).unwrap();
+    eliminations.insert(Asgmt::new(num, loc));
+  }
+  imported.eliminations = Some(unfilled_only(eliminations, &current));
+  Ok(imported)
+}
+
+fn parse_pencil_marks(marks: &str) -> Result<Imported, String> {
+  let mut eliminations = AsgmtSet::new();
+  for (i, c) in marks.chars().enumerate() {
+    let (loc, num) = (
+      Loc::from_index(i / 9).unwrap(),
+      Num::from_index(i % 9).unwrap(),
+    );
+    match num_of(c) {
+      Some(n) if n == num => {}
+      Some(_) => return Err(format!("Digit {} in the place of {}", c, num.get())),
+      None => {
+        eliminations.insert(Asgmt::new(num, loc));
+      }
+    }
+  }
+  Ok(Imported {
+    format: Format::PencilMarks,
+    givens: Grid::new(),
+    placed: Grid::new(),
+    eliminations: Some(eliminations),
+  })
+}
+
+/// Parses a pencil-mark grid, returning None if the text doesn't look like
+/// one.  A location holding a single digit counts as a given (or as placed, if
+/// marked with `+`), so a single remaining candidate can't be represented.
+fn parse_candidate_grid(text: &str) -> Result<Option<Imported>, String> {
+  let tokens: Vec<&str> = text
+    .lines()
+    .filter(|line| line.chars().any(|c| c.is_ascii_digit()))
+    .flat_map(|line| line.split(|c: char| c == '|' || c.is_whitespace()))
+    .filter(|token| !token.is_empty())
+    .collect();
+  let is_token = |t: &str| {
+    let digits = t.strip_prefix('+').unwrap_or(t);
+    !digits.is_empty() && digits.chars().all(|c| num_of(c).is_some())
+  };
+  if tokens.len() != 81
+    || !tokens.iter().all(|t| is_token(t))
+    || !tokens.iter().any(|t| t.len() > 1)
+  {
+    return Ok(None);
+  }
+  let hodoku = tokens.iter().any(|t| t.starts_with('+'))
+    || text
+      .lines()
+      .any(|line| line.trim_start().starts_with(['.', ':', '\'']));
+  let mut givens = Grid::new();
+  let mut placed = Grid::new();
+  let mut candidates = AsgmtSet::new();
+  for (loc, token) in Loc::all().zip(tokens) {
+    let nums: Vec<Num> = token.chars().filter_map(num_of).collect();
+    match (token.starts_with('+'), &nums[..]) {
+      (true, [num]) => placed[loc] = Some(*num),
+      (true, _) => return Err(format!("Bad placed digit {}", token)),
+      (false, [num]) => givens[loc] = Some(*num),
+      (false, _) => {
+        for num in nums {
+          if !candidates.insert(Asgmt::new(num, loc)) {
+            return Err(format!("Repeated candidate in {}", token));
+          }
+        }
+      }
+    }
+  }
+  let mut imported = Imported {
+    format: if hodoku {
+      Format::HoDoKuGrid
+    } else {
+      Format::SudokuWikiGrid
+    },
+    givens,
+    placed,
+    eliminations: None,
+  };
+  imported.eliminations = Some(unfilled_only(!candidates, &imported.current()));
+  Ok(Some(imported))
+}
+
+fn write_candidate_grid(tokens: &[String], format: Format) -> String {
+  let widths: Vec<usize> = (0..9)
+    .map(|col| (0..9).map(|row| tokens[row * 9 + col].len()).max().unwrap())
+    .collect();
+  let border = |left: char, mid: char, right: char| {
+    let mut line = String::new();
+    for b in 0..3 {
+      line.push(if b == 0 { left } else { mid });
+      let width: usize = widths[b * 3..b * 3 + 3].iter().map(|w| w + 2).sum();
+      line += &"-".repeat(width + 1);
+    }
+    line.push(right);
+    line
+  };
+  let (top, mid, bottom) = match format {
+    Format::HoDoKuGrid => (
+      border('.', '.', '.'),
+      border(':', '+', ':'),
+      border('\'', '\'', '\''),
+    ),
+    _ => (
+      border('*', '-', '*'),
+      border('|', '+', '|'),
+      border('*', '-', '*'),
+    ),
+  };
+  let mut text = top + "\n";
+  for row in 0..9 {
+    if row == 3 || row == 6 {
+      text += &mid;
+      text.push('\n');
+    }
+    for col in 0..9 {
+      if col % 3 == 0 {
+        text += "| ";
+      }
+      // Writing to a String can't fail.
+      write!(text, "{:w$}  ", tokens[row * 9 + col], w = widths[col]).unwrap();
+    }
+    text += "|\n";
+  }
+  text + &bottom
+}
+
+/// Writes a grid as nine rows, with `blank` for empty locations, `sep`
+/// between blocks, and `band_sep` lines between bands (unless it's empty).
+fn write_rows(grid: &Grid, blank: char, sep: &str, band_sep: &str) -> String {
+  let mut text = String::new();
+  for row in 0..9 {
+    if !band_sep.is_empty() && (row == 3 || row == 6) {
+      text += band_sep;
+      text.push('\n');
+    }
+    for col in 0..9 {
+      if col == 3 || col == 6 {
+        text += sep;
+      }
+      text.push(grid[Loc::from_index(row * 9 + col).unwrap()].map_or(blank, digit));
+    }
+    text.push('\n');
+  }
+  text
+}
+
+fn is_ss_separator(line: &str) -> bool {
+  let line = line.trim();
+  line.len() >= 3 && line.chars().all(|c| c == '-' || c == '+')
+}
+
+/// Reads the cells of a grid written a row per line, with `.`, `0`, `X` or `x`
+/// for blanks and any other non-digit characters ignored.
+fn read_cells(lines: &[&str]) -> Vec<Option<Num>> {
+  lines
+    .iter()
+    .flat_map(|line| line.chars())
+    .filter(|&c| c.is_ascii_digit() || matches!(c, '.' | 'X' | 'x'))
+    .map(num_of)
+    .collect()
+}
+
+/// Splits cells into givens and placed numerals, given the cells of the
+/// puzzle and optionally the cells of its current state.
+fn givens_and_placed(
+  puzzle: &[Option<Num>],
+  state: Option<&[Option<Num>]>,
+) -> Result<(Grid, Grid), String> {
+  if puzzle.len() != 81 || state.is_some_and(|s| s.len() != 81) {
+    return Err("Grids must have 81 locations".to_string());
+  }
+  let mut givens = Grid::new();
+  let mut placed = Grid::new();
+  for loc in Loc::all() {
+    givens[loc] = puzzle[loc.index()];
+    if let Some(state) = state {
+      match (givens[loc], state[loc.index()]) {
+        (None, num) => placed[loc] = num,
+        (Some(a), Some(b)) if a == b => {}
+        _ => return Err(format!("State doesn't match the clue at {}", loc)),
+      }
+    }
+  }
+  Ok((givens, placed))
+}
+
+fn parse_simple_sudoku(text: &str) -> Result<Imported, String> {
+  let lines: Vec<&str> = text.lines().filter(|line| !is_ss_separator(line)).collect();
+  let cells = read_cells(&lines);
+  let (givens, placed) = match cells.len() {
+    81 => givens_and_placed(&cells, None)?,
+    162 => givens_and_placed(&cells[..81], Some(&cells[81..]))?,
+    n => return Err(format!("Simple Sudoku file has {} locations", n)),
+  };
+  Ok(Imported {
+    format: Format::SimpleSudoku,
+    givens,
+    placed,
+    eliminations: None,
+  })
+}
+
+fn parse_sadman(text: &str) -> Result<Imported, String> {
+  let mut puzzle = Vec::new();
+  let mut state = Vec::new();
+  let mut section = "[Puzzle]";
+  for line in text.lines().map(str::trim) {
+    if line.starts_with('[') {
+      section = line;
+    } else if !line.starts_with('#') {
+      match section {
+        "[Puzzle]" => puzzle.push(line),
+        "[State]" => state.push(line),
+        _ => {}
+      }
+    }
+  }
+  let state = read_cells(&state);
+  let (givens, placed) = givens_and_placed(
+    &read_cells(&puzzle),
+    if state.is_empty() { None } else { Some(&state) },
+  )?;
+  Ok(Imported {
+    format: Format::SadMan,
+    givens,
+    placed,
+    eliminations: None,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::str::FromStr;
+
+  const CLUES: &str =
+    "..7..9.1....25....1....6.9..8......9.6..32...4.9.1..35.......5.3.6..59.8...8.7.4.";
+
+  fn sample() -> Imported {
+    // Pencil-mark grids can't tell naked singles from numerals, so we keep the
+    // sample sparse enough to have none.
+    let givens = Grid::from_str(SPARSE_CLUES).unwrap();
+    let mut placed = Grid::new();
+    placed[L14] = Some(N3);
+    placed[L21] = Some(N9);
+    Imported {
+      format: Format::Plain,
+      givens,
+      placed,
+      eliminations: None,
+    }
+  }
+
+  const SPARSE_CLUES: &str =
+    "..7..9.1....25....1....6.9..8.........................................5.3.6..59.8";
+
+  fn with_candidates(mut imported: Imported) -> Imported {
+    let current = imported.current();
+    let mut eliminations = implied_eliminations(&current);
+    eliminations.insert(Asgmt::new(N2, L11));
+    eliminations.insert(Asgmt::new(N5, L55));
+    imported.eliminations = Some(eliminations);
+    imported
+  }
+
+  fn assert_round_trip(expected: Imported, format: Format) {
+    let text = write(&expected, format);
+    let parsed = parse(&text).unwrap();
+    assert_eq!(Imported { format, ..expected }, parsed, "\n{}", text);
+  }
+
+  #[test]
+  fn test_round_trips() {
+    let sample = sample();
+    let no_placed = Imported {
+      placed: Grid::new(),
+      ..sample
+    };
+    assert_round_trip(no_placed, Format::Plain);
+    assert_round_trip(sample, Format::SimpleSudoku);
+    assert_round_trip(no_placed, Format::SimpleSudoku);
+    assert_round_trip(sample, Format::SadMan);
+    assert_round_trip(no_placed, Format::SadMan);
+    assert_round_trip(with_candidates(sample), Format::HoDoKuLibrary);
+    assert_round_trip(with_candidates(sample), Format::HoDoKuGrid);
+    assert_round_trip(with_candidates(no_placed), Format::SudokuWikiGrid);
+    let mut candidates_only = with_candidates(Imported {
+      givens: Grid::new(),
+      ..no_placed
+    });
+    candidates_only
+      .eliminations
+      .as_mut()
+      .unwrap()
+      .insert(Asgmt::new(N7, L55));
+    assert_round_trip(candidates_only, Format::PencilMarks);
+
+    // Pencil marks are how candidate grids print themselves.
+    let sample = with_candidates(sample);
+    let marks = write(&sample, Format::PencilMarks);
+    assert_eq!(marks, sample.candidates().to_string());
+    assert_eq!(Ok(sample.candidates()), marks.parse());
+  }
+
+  #[test]
+  fn test_hodoku_library() {
+    let text = format!(":0000:x:{}+3{}:211 599::", &CLUES[..3], &CLUES[4..]);
+    let imported = parse(&text).unwrap();
+    assert_eq!(Format::HoDoKuLibrary, imported.format);
+    assert_eq!(Some(N3), imported.placed[L14]);
+    let eliminations = imported.eliminations.unwrap();
+    assert!(eliminations.contains(Asgmt::new(N2, L11)));
+    assert!(eliminations.contains(Asgmt::new(N5, L99)));
+    // Implied by the 7 in the same row.
+    assert!(eliminations.contains(Asgmt::new(N7, L11)));
+    assert!(!eliminations.contains(Asgmt::new(N6, L11)));
+  }
+
+  #[test]
+  fn test_simple_sudoku() {
+    let text = "
+      ..7|..9|.1.
+      ...|25.|...
+      1..|..6|.9.
+      -----------
+      .8.|...|..9
+      .6.|.32|...
+      4.9|.1.|.35
+      -----------
+      ...|...|.5.
+      3.6|..5|9.8
+      ...|8.7|.4.";
+    let imported = parse(text).unwrap();
+    assert_eq!(Format::SimpleSudoku, imported.format);
+    assert_eq!(Grid::from_str(CLUES).unwrap(), imported.givens);
+    assert!(imported.eliminations.is_none());
+  }
+
+  #[test]
+  fn test_sadman() {
+    let text = "#AAuthor\n#DA description\n[Puzzle]\n..7..9.1.\n...25....\n1....6.9.\n\
+                .8......9\n.6..32...\n4.9.1..35\n.......5.\n3.6..59.8\n...8.7.4.\n";
+    let imported = parse(text).unwrap();
+    assert_eq!(Format::SadMan, imported.format);
+    assert_eq!(Grid::from_str(CLUES).unwrap(), imported.givens);
+  }
+
+  #[test]
+  fn test_sudokuwiki_grid() {
+    let imported = with_candidates(Imported {
+      placed: Grid::new(),
+      ..sample()
+    });
+    let text = write(&imported, Format::SudokuWikiGrid);
+    assert!(text.starts_with('*'));
+    assert_eq!(Format::SudokuWikiGrid, parse(&text).unwrap().format);
+  }
+
+  #[test]
+  fn test_errors() {
+    assert!(parse("123").is_err());
+    assert!(parse(":0000:x:123::").is_err());
+    assert!(parse(&format!(":0000:x:{}:2x1::", CLUES)).is_err());
+    let mut marks = "123456789".repeat(81);
+    marks.replace_range(0..1, "2");
+    assert!(parse(&marks).is_err());
+  }
+}
@@ -1,18 +1,34 @@
 //! Code that generates solved Sudoku grids, and Sudoku puzzles.
 
-use once_cell::sync::Lazy;
-use rand::distr::weighted::WeightedIndex;
-use rand::prelude::Distribution;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
 use std::fmt::Display;
-use wasm_bindgen::prelude::wasm_bindgen;
+use std::str::FromStr;
+use wasm_bindgen::prelude::*;
 
 use crate::core::*;
 use crate::date::LogicalDate;
+use crate::error::Error;
 use crate::permute::{ExternalGridPermutation, GridPermutation};
 use crate::random::*;
+use crate::solve::ambiguity::Ambiguity;
 use crate::solve::ledger::Ledger;
 use crate::solve::*;
-use crate::sym::{Axis, Diagonal, Sym};
+use crate::sym::{Axis, Diagonal, Sym, Symmetry};
+
+pub mod automorphic;
+pub mod corpus;
+pub mod jigsaw;
+pub mod killer;
+pub mod share;
+pub mod sized;
+pub mod versions;
+
+use automorphic::*;
+use jigsaw::*;
+use killer::*;
+use sized::*;
+use versions::{current_generator, generator};
 
 /// Describes a Sudoku puzzle.
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
@@ -26,6 +42,8 @@ pub struct Puzzle {
   pub gen_opts: Option<GenOpts>,
   /// All the solutions to the puzzle.
   pub solutions: Vec<SolvedGrid>,
+  /// The rules the puzzle follows.
+  pub variant: Variant,
 }
 
 /// Possible results of testing a set of clues to see if it works as a puzzle.
@@ -45,6 +63,16 @@ impl Puzzle {
   /// Attempts to make a puzzle, either by solving it, given just its clues, or
   /// by verifying that the given solutions are consistent with the given clues.
   pub fn new(clues: &Grid, solutions: Option<Vec<SolvedGrid>>) -> Option<Self> {
+    Self::new_for_variant(clues, solutions, Variant::Standard)
+  }
+
+  /// Like `new`, but for a puzzle following the given variant's rules.
+  #[wasm_bindgen(js_name = "newForVariant")]
+  pub fn new_for_variant(
+    clues: &Grid,
+    solutions: Option<Vec<SolvedGrid>>,
+    variant: Variant,
+  ) -> Option<Self> {
     if let Some(solutions) = solutions {
       // If the caller provided solutions, we validate them.
       if solutions.is_empty() || solutions.len() > MAX_SOLUTIONS as usize {
@@ -61,10 +89,11 @@ impl Puzzle {
         clues: *clues,
         gen_opts: None,
         solutions,
+        variant,
       });
     }
     let mut helper = DefaultHelper();
-    let summary = solve(&clues, MAX_SOLUTIONS, &mut helper);
+    let summary = solve_variant(&clues, variant, MAX_SOLUTIONS, &mut helper);
     if summary.too_many_solutions || summary.solutions.len() == 0 {
       return None;
     }
@@ -72,6 +101,7 @@ impl Puzzle {
       clues: summary.clues,
       gen_opts: None,
       solutions: summary.solutions,
+      variant,
     })
   }
 
@@ -96,6 +126,7 @@ impl Puzzle {
           clues: summary.clues,
           gen_opts: None,
           solutions: summary.solutions,
+          variant: Variant::Standard,
         }),
         incomplete: false,
       }
@@ -106,9 +137,56 @@ impl Puzzle {
   pub fn solutions_count(&self) -> i32 {
     self.solutions.len() as _
   }
+
+  /// Describes where and how the puzzle's solutions differ, as an `Ambiguity`
+  /// object.
+  #[wasm_bindgen(js_name = "ambiguity")]
+  pub fn ambiguity_js(&self) -> Result<JsValue, JsError> {
+    Ok(serde_wasm_bindgen::to_value(&self.ambiguity())?)
+  }
+
+  /// Returns the indices of the locations of the clues that could be removed
+  /// without changing the puzzle's solutions.
+  #[wasm_bindgen(js_name = "redundantClues")]
+  pub fn redundant_clues_js(&self) -> Box<[u8]> {
+    self.redundant_clues().as_boxed_indices()
+  }
+
+  /// Tells whether every one of the puzzle's clues is necessary.
+  #[wasm_bindgen(js_name = "isMinimal")]
+  pub fn is_minimal(&self) -> bool {
+    self.redundant_clues().is_empty()
+  }
 }
 
 impl Puzzle {
+  /// Describes where and how the puzzle's solutions differ.
+  pub fn ambiguity(&self) -> Ambiguity {
+    Ambiguity::new(&self.solutions)
+  }
+
+  /// Finds the clues that could be removed, one at a time, without changing
+  /// the puzzle's solutions.  Re-solves the puzzle once per clue.
+  pub fn redundant_clues(&self) -> LocSet {
+    let mut answer = LocSet::new();
+    let max_solutions = self.solutions.len() as i32;
+    let mut helper = DefaultHelper();
+    for loc in Loc::all() {
+      if self.clues[loc].is_none() {
+        continue;
+      }
+      let mut clues = self.clues;
+      clues[loc] = None;
+      // Removing a clue can only add solutions, so if the count stays the same,
+      // so do the solutions.
+      let summary = solve_variant(&clues, self.variant, max_solutions, &mut helper);
+      if !summary.too_many_solutions && summary.solutions.len() == self.solutions.len() {
+        answer.insert(loc);
+      }
+    }
+    answer
+  }
+
   /// Returns the solutions in the form of a set of assignments.  If there are
   /// more than one solution, the resulting set will be the union of all the
   /// solutions.
@@ -142,6 +220,18 @@ impl PuzzleId {
       generator_version,
     }
   }
+
+  /// Parses an ID in the form `toString` produces.
+  #[wasm_bindgen(js_name = "fromString")]
+  pub fn from_string(s: &str) -> Result<PuzzleId, JsError> {
+    Ok(s.parse()?)
+  }
+
+  /// Renders this ID in its canonical form, `date:counter:version`.
+  #[wasm_bindgen(js_name = "toString")]
+  pub fn to_string_js(&self) -> String {
+    self.to_string()
+  }
 }
 
 /// The base solution for a given date.  All puzzles generated by this module
@@ -152,6 +242,8 @@ pub struct DailySolution {
   pub date: LogicalDate,
   pub generator_version: i32,
   pub solution: SolvedGrid,
+  /// The rules the solution, and the puzzles made from it, follow.
+  pub variant: Variant,
 }
 
 /// Options for generating a puzzle.
@@ -186,7 +278,8 @@ pub struct GenOpts {
 }
 
 impl GenOpts {
-  /// Returns the ID of the generated puzzle.
+  /// Returns the ID of the generated puzzle.  The ID doesn't record the
+  /// variant; regenerate a variant puzzle with `generate_variant_puzzle`.
   pub fn id(&self) -> PuzzleId {
     PuzzleId {
       date: self.daily_solution.date,
@@ -196,21 +289,41 @@ impl GenOpts {
   }
 }
 
-/// Generates the puzzle with the given ID.
-pub fn generate_puzzle(id: PuzzleId) -> Result<Puzzle, String> {
-  daily_solution(&id.date).generate(id.counter)
+/// Generates the puzzle with the given ID, using the generator version the ID
+/// names.
+pub fn generate_puzzle(id: PuzzleId) -> Result<Puzzle, Error> {
+  generate_variant_puzzle(id, Variant::Standard)
+}
+
+/// Generates the puzzle of the given variant with the given ID.  Each variant
+/// has its own daily solution, and so its own puzzles.
+pub fn generate_variant_puzzle(id: PuzzleId, variant: Variant) -> Result<Puzzle, Error> {
+  generator(id.generator_version)?
+    .daily_solution(&id.date, variant)
+    .generate(id.counter)
 }
 
+/// Makes the base solution for the given date with the current generator.
 #[wasm_bindgen(js_name = "dailySolution")]
 pub fn daily_solution(date: &LogicalDate) -> DailySolution {
-  let seed = date.to_string(); // yyyy-mm-dd
-  let mut random = new_random(&seed);
-  let solution = gen_solved_grid(&mut random);
-  DailySolution {
-    date: *date,
-    generator_version: GENERATOR_VERSION,
-    solution,
-  }
+  daily_variant_solution(date, Variant::Standard)
+}
+
+/// Makes the base solution of the given variant for the given date with the
+/// current generator.
+#[wasm_bindgen(js_name = "dailyVariantSolution")]
+pub fn daily_variant_solution(date: &LogicalDate, variant: Variant) -> DailySolution {
+  current_generator().daily_solution(date, variant)
+}
+
+/// Makes the base solution for the given date with the given version of the
+/// generator.
+#[wasm_bindgen(js_name = "dailySolutionForVersion")]
+pub fn daily_solution_for_version(
+  date: &LogicalDate,
+  generator_version: i32,
+) -> Result<DailySolution, JsError> {
+  Ok(generator(generator_version)?.daily_solution(date, Variant::Standard))
 }
 
 #[wasm_bindgen(js_name = "generatorVersion")]
@@ -220,109 +333,137 @@ pub fn generator_version() -> i32 {
 
 #[wasm_bindgen]
 impl DailySolution {
-  /// Generates one of this day's puzzles.
-  pub fn generate(&self, counter: i32) -> Result<Puzzle, String> {
-    if self.generator_version != GENERATOR_VERSION {
-      return Err(format!(
-        "This solution was generated with version {} of the generator, but this is version {}.",
-        self.generator_version, GENERATOR_VERSION
-      ));
-    }
-    let id = PuzzleId {
-      date: self.date,
-      counter,
-      generator_version: GENERATOR_VERSION,
-    };
-    let seed = id.to_string();
-    let mut random = new_random(&seed);
-    let permutation = GridPermutation::random(&mut random);
-    let solution = permutation.apply_to_solved(&self.solution);
-
-    let sym = SYM_WEIGHTS[SYM_DIST.sample(&mut random)].0;
-    let broken = random.random_bool(BROKEN_SYMMETRY_PROB);
-    let improper = random.random_bool(IMPROPER_PROB);
-    let summary = gen_puzzle(&solution, sym, broken, improper, &mut random);
-    Ok(Puzzle {
-      clues: summary.clues,
-      gen_opts: Some(GenOpts {
-        daily_solution: *self,
-        counter,
-        permutation: *permutation.external(),
-        sym,
-        broken,
-        improper,
-      }),
-      solutions: summary.solutions,
-    })
+  /// Generates one of this day's puzzles, using the generator version that
+  /// made this solution.
+  #[wasm_bindgen(js_name = "generate")]
+  pub fn generate_js(&self, counter: i32) -> Result<Puzzle, JsError> {
+    Ok(self.generate(counter)?)
+  }
+}
+
+impl DailySolution {
+  /// Generates one of this day's puzzles, using the generator version that
+  /// made this solution.
+  pub fn generate(&self, counter: i32) -> Result<Puzzle, Error> {
+    Ok(generator(self.generator_version)?.generate(self, counter))
   }
 }
 
-/// The version of the Luke-doku puzzle generator.  This must change whenever
-/// any of the parameters listed below change, or when the Rust `rand` crate has
-/// a breaking change to the parts of it we use.
+/// The current version of the Luke-doku puzzle generator, the last one in
+/// `versions::GENERATORS`.
 pub const GENERATOR_VERSION: i32 = 1;
 
-// -------------------------------
-// The parameters of the Luke-doku generator.  Changing any of these requires
-// bumping GENERATOR_VERSION.
-const BROKEN_SYMMETRY_PROB: f64 = 0.9;
-const IMPROPER_PROB: f64 = 0.125;
-const MAX_SOLUTIONS: i32 = 3;
-const MAX_HOLES: i32 = 7;
-static SYM_WEIGHTS: &[(Sym, i32)] = &[
-  (Sym::Rotation180, 100),
-  (Sym::Rotation90, 50),
-  (Sym::Mirror(Axis::X), 50),
-  (Sym::Mirror(Axis::Y), 50),
-  (Sym::DoubleMirror, 25),
-  (Sym::Diagonal(Diagonal::Main), 50),
-  (Sym::Diagonal(Diagonal::Anti), 50),
-  (Sym::DoubleDiagonal, 25),
-  (Sym::FullyReflective, 10),
-  (Sym::Blockwise(Diagonal::Main), 25),
-  (Sym::Blockwise(Diagonal::Anti), 25),
-  (Sym::None, 50),
-];
-static SYM_DIST: Lazy<WeightedIndex<i32>> =
-  Lazy::new(|| WeightedIndex::new(SYM_WEIGHTS.iter().map(|item| item.1)).unwrap());
-// -------------------------------
+/// The most solutions a puzzle may have, and the most holes its solutions may
+/// have.  No generator version makes puzzles with more.
+pub const MAX_SOLUTIONS: i32 = 3;
+pub const MAX_HOLES: i32 = 7;
 
 impl Display for PuzzleId {
+  /// Writes the ID in its canonical form, `date:counter:version`, as in
+  /// `2024-06-01:3:1`.
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    write!(f, "{}:{}", self.date, self.counter)
+    write!(
+      f,
+      "{}:{}:{}",
+      self.date, self.counter, self.generator_version
+    )
   }
 }
 
-/// Uses the given random number generator to make a SolvedGrid.
-pub fn gen_solved_grid<R: Rng>(random: &mut R) -> SolvedGrid {
+impl FromStr for PuzzleId {
+  type Err = Error;
+
+  /// Parses an ID written as `date:counter:version`, where the date is
+  /// formatted as YYYY-MM-DD, the counter is positive, and the generator
+  /// version is not negative.  The version may be left off, along with its
+  /// colon, for IDs from before the generator had versions; these get version
+  /// 0.
+  fn from_str(s: &str) -> Result<PuzzleId, Error> {
+    let parts: Vec<&str> = s.split(':').collect();
+    if parts.len() < 2 || parts.len() > 3 {
+      return Err(Error::MalformedPuzzleId(format!(
+        "{:?} is not of the form date:counter:version",
+        s
+      )));
+    }
+    let date = NaiveDate::parse_from_str(parts[0], "%Y-%m-%d").map_err(|e| {
+      Error::MalformedPuzzleId(format!("{:?} has a bad date {:?}: {}", s, parts[0], e))
+    })?;
+    let counter = match parts[1].parse::<i32>() {
+      Ok(counter) if counter >= 1 => counter,
+      _ => {
+        return Err(Error::MalformedPuzzleId(format!(
+          "{:?} has a bad counter {:?}: it must be a positive integer",
+          s, parts[1]
+        )))
+      }
+    };
+    let generator_version = match parts.get(2).map(|v| v.parse::<i32>()) {
+      None => 0,
+      Some(Ok(version)) if version >= 0 => version,
+      Some(_) => {
+        return Err(Error::MalformedPuzzleId(format!(
+          "{:?} has a bad generator version {:?}: it must be a non-negative integer",
+          s, parts[2]
+        )))
+      }
+    };
+    Ok(PuzzleId::new(date.into(), counter, generator_version))
+  }
+}
+
+impl Serialize for PuzzleId {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    serializer.collect_str(self)
+  }
+}
+
+impl<'de> Deserialize<'de> for PuzzleId {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: serde::Deserializer<'de>,
+  {
+    let s = String::deserialize(deserializer)?;
+    s.parse().map_err(serde::de::Error::custom)
+  }
+}
+
+/// Uses the given random number generator to make a SolvedGrid that follows
+/// the given variant's rules.
+pub fn gen_solved_grid(variant: Variant, random: &mut Random) -> SolvedGrid {
   let mut helper = GenHelper(random);
-  let factory = SearcherFactory::new(&Grid::new());
+  let factory = SearcherFactory::new_for_variant(&Grid::new(), variant);
   let mut searcher = factory.new_searcher(&mut helper);
   searcher.run(None);
   searcher.found.unwrap()
 }
 
-/// The guts of `generate_puzzle`.
-pub fn gen_puzzle<R: Rng>(
+/// The guts of `generate_puzzle`: makes a puzzle with the given solution
+/// whose clues honor the given symmetry, unless `broken` is true, in which case
+/// further clues are removed at random.
+pub fn gen_puzzle(
   solution: &SolvedGrid,
+  variant: Variant,
   sym: Sym,
   broken: bool,
-  improper: bool,
-  random: &mut R,
+  max_solutions: i32,
+  max_holes: i32,
+  random: &mut Random,
 ) -> SolutionSummary {
-  let clues = gen_simple_puzzle(solution, sym, random);
-  let max_solutions;
-  let max_holes;
-  if improper {
-    max_solutions = MAX_SOLUTIONS;
-    max_holes = MAX_HOLES;
-  } else {
-    max_solutions = 1;
-    max_holes = 0;
-  }
-  let mut summary = improve_puzzle(&clues, sym, random, max_solutions, max_holes);
+  let clues = gen_simple_puzzle(solution, variant, &sym, random);
+  let mut summary = improve_puzzle(&clues, variant, &sym, random, max_solutions, max_holes);
   if broken && sym != Sym::None {
-    summary = improve_puzzle(&summary.clues, Sym::None, random, max_solutions, max_holes);
+    summary = improve_puzzle(
+      &summary.clues,
+      variant,
+      &Sym::None,
+      random,
+      max_solutions,
+      max_holes,
+    );
   }
   summary
 }
@@ -333,9 +474,14 @@ pub fn gen_puzzle<R: Rng>(
 ///
 /// # Panics
 ///
-/// The given solution grid must be a solved Sudoku.
-pub fn gen_simple_puzzle<R: Rng>(solution: &SolvedGrid, sym: Sym, random: &mut R) -> Grid {
-  let mut ledger = Ledger::new(&Grid::new()).unwrap();
+/// The given solution grid must be a solved Sudoku under the variant's rules.
+pub fn gen_simple_puzzle(
+  solution: &SolvedGrid,
+  variant: Variant,
+  sym: &dyn Symmetry,
+  random: &mut Random,
+) -> Grid {
+  let mut ledger = Ledger::new_for_variant(&Grid::new(), variant).unwrap();
   let mut answer = Grid::new();
   let orbits = sym.shuffled_orbits(random);
   for orbit in orbits {
@@ -352,24 +498,30 @@ pub fn gen_simple_puzzle<R: Rng>(solution: &SolvedGrid, sym: Sym, random: &mut R
   answer
 }
 
-/// Subtracts clues from the given grid, honoring the given symmetry.
-pub fn improve_puzzle<R: Rng>(
+/// Subtracts clues from the given grid, honoring the given symmetry and
+/// solving under the given variant's rules, and leaving at least the
+/// variant's minimum number of clues.
+pub fn improve_puzzle(
   clues: &Grid,
-  sym: Sym,
-  random: &mut R,
+  variant: Variant,
+  sym: &dyn Symmetry,
+  random: &mut Random,
   max_solutions: i32,
   max_holes: i32,
 ) -> SolutionSummary {
   let mut clues = *clues;
   let orbits = sym.shuffled_orbits(random);
   let mut helper = DefaultHelper();
-  let mut summary = solve(&clues, max_solutions, &mut helper);
+  let mut summary = solve_variant(&clues, variant, max_solutions, &mut helper);
   for orbit in orbits {
+    if clues.len().saturating_sub(orbit.len()) < variant.min_clues() {
+      continue;
+    }
     let prev = clues;
     for loc in orbit {
       clues[*loc] = None;
     }
-    let next_summary = solve(&clues, max_solutions, &mut helper);
+    let next_summary = solve_variant(&clues, variant, max_solutions, &mut helper);
     if (0..=max_solutions).contains(&(next_summary.solutions.len() as i32))
       && next_summary.num_holes() <= max_holes
     {
@@ -383,16 +535,16 @@ pub fn improve_puzzle<R: Rng>(
 
 /// A `SearchHelper` that shuffles the numerals for each pivot point, thereby
 /// randomizing the grid.
-struct GenHelper<'a, R: Rng>(&'a mut R);
+struct GenHelper<'a>(&'a mut Random);
 
-impl<'a, R: Rng> SearchHelper for GenHelper<'a, R> {
+impl SearchHelper for GenHelper<'_> {
   fn choose_pivot_loc(&mut self, ledger: &Ledger, doubles: &LocSet) -> Loc {
     let mut helper = JczHelper();
     helper.choose_pivot_loc(ledger, doubles)
   }
 
   fn order_pivot_nums(&mut self, nums: &mut [Option<Num>]) {
-    nums.shuffle(self.0);
+    self.0.shuffle(nums);
   }
 }
 
@@ -409,7 +561,7 @@ mod tests {
   #[test]
   fn test_gen_solved_grid() {
     let mut random = new_random("test");
-    let sg = gen_solved_grid(&mut random);
+    let sg = gen_solved_grid(Variant::Standard, &mut random);
     let g = sg.grid(); // Let's ensure these unsafe conversions are legit
     assert_eq!(81, g.len());
     assert_eq!(GridState::Solved(&g), g.state());
@@ -432,16 +584,16 @@ mod tests {
       .unwrap()
     );
     random = new_random("test2");
-    assert_ne!(sg, gen_solved_grid(&mut random));
+    assert_ne!(sg, gen_solved_grid(Variant::Standard, &mut random));
     random = new_random("test");
-    assert_eq!(sg, gen_solved_grid(&mut random));
+    assert_eq!(sg, gen_solved_grid(Variant::Standard, &mut random));
   }
 
   #[test]
   fn test_gen_simple_puzzle() {
     let mut random = new_random("test");
-    let g = gen_solved_grid(&mut random);
-    let p = gen_simple_puzzle(&g, Sym::FullyReflective, &mut random);
+    let g = gen_solved_grid(Variant::Standard, &mut random);
+    let p = gen_simple_puzzle(&g, Variant::Standard, &Sym::FullyReflective, &mut random);
     assert_eq!(
       p,
       Grid::from_str(
@@ -465,7 +617,7 @@ mod tests {
     let s = ledger.to_grid();
     assert_eq!(s.state(), GridState::Solved(&s));
 
-    let p = gen_simple_puzzle(&g, Sym::None, &mut random);
+    let p = gen_simple_puzzle(&g, Variant::Standard, &Sym::None, &mut random);
     assert_eq!(
       p,
       Grid::from_str(
@@ -517,6 +669,7 @@ mod tests {
       .unwrap()
       .solved_grid()
       .unwrap(),
+      variant: Variant::Standard,
     };
     assert_eq!(super::daily_solution(&id.date), daily_solution);
     assert_eq!(
@@ -581,6 +734,7 @@ mod tests {
         .unwrap()
         .solved_grid()
         .unwrap()],
+        variant: Variant::Standard,
       })
     );
 
@@ -647,10 +801,67 @@ mod tests {
         .unwrap()
         .solved_grid()
         .unwrap()],
+        variant: Variant::Standard,
       })
     );
   }
 
+  #[test]
+  fn test_redundant_clues() {
+    let clues = Grid::from_str(
+      ".6.5.4.3.1...9...8.........9...5...6.4.6.2.7.7...4...5.........4...8...1.5.2.3.4.",
+    )
+    .unwrap();
+    let mut puzzle = Puzzle::new(&clues, None).unwrap();
+    // Adding a clue from the solution makes it redundant.
+    puzzle.clues[L11] = Some(puzzle.solutions[0][L11]);
+    let redundant = puzzle.redundant_clues();
+    assert!(redundant.contains(L11));
+    assert!(!puzzle.is_minimal());
+
+    // Removing redundant clues one at a time eventually yields a minimal
+    // puzzle with the same solution.
+    while let Some(loc) = puzzle.redundant_clues().smallest_item() {
+      puzzle.clues[loc] = None;
+    }
+    assert!(puzzle.is_minimal());
+    assert_eq!(
+      Puzzle::new(&puzzle.clues, None).unwrap().solutions,
+      puzzle.solutions
+    );
+  }
+
+  #[test]
+  fn test_puzzle_id_strings() {
+    let id = PuzzleId::new(LogicalDate::from_ymd(2024, 6, 1), 3, 1);
+    assert_eq!("2024-06-01:3:1", id.to_string());
+    assert_eq!(Ok(id), "2024-06-01:3:1".parse());
+    assert_eq!(Ok(PuzzleId::new(id.date, 3, 0)), "2024-06-01:3".parse());
+
+    let json = serde_json::to_string(&id).unwrap();
+    assert_eq!("\"2024-06-01:3:1\"", json);
+    assert_eq!(id, serde_json::from_str(&json).unwrap());
+
+    for (s, error) in [
+      ("2024-06-01", "not of the form"),
+      ("2024-06-01:3:1:1", "not of the form"),
+      ("2024-02-30:3:1", "bad date"),
+      ("2024-06-01:0:1", "bad counter"),
+      ("2024-06-01:x:1", "bad counter"),
+      ("2024-06-01:3:-1", "bad generator version"),
+      ("2024-06-01:3:", "bad generator version"),
+    ] {
+      let result = s.parse::<PuzzleId>();
+      assert!(
+        matches!(&result, Err(Error::MalformedPuzzleId(message)) if message.contains(error)),
+        "{}: {:?}",
+        s,
+        result
+      );
+    }
+    assert!(serde_json::from_str::<PuzzleId>("\"2024-06-01\"").is_err());
+  }
+
   #[test]
   fn test_generate_puzzle_error() {
     let solution = daily_solution(&LogicalDate::from_ymd(1961, 9, 20));
@@ -660,10 +871,97 @@ mod tests {
     };
     assert_eq!(
       solution.generate(1),
-      Err(
-        "This solution was generated with version 0 of the generator, but this is version 1."
-          .to_string(),
-      )
+      Err(Error::VersionMismatch {
+        what: "generator",
+        version: 0,
+        supported: (1, 1),
+      })
     );
   }
+
+  #[test]
+  fn test_generate_x_puzzles() {
+    let date = LogicalDate::from_ymd(2025, 3, 14);
+    let daily = daily_variant_solution(&date, Variant::X);
+    assert_eq!(Variant::X, daily.variant);
+    let grid = daily.solution.grid();
+    assert_eq!(GridState::Solved(&grid), grid.variant_state(Variant::X));
+    for counter in 1..=4 {
+      let id = PuzzleId::new(date, counter, GENERATOR_VERSION);
+      let puzzle = generate_variant_puzzle(id, Variant::X).unwrap();
+      assert_eq!(Variant::X, puzzle.variant);
+      let opts = puzzle.gen_opts.unwrap();
+      assert!(opts.sym.preserves(Variant::X));
+      for solution in &puzzle.solutions {
+        let grid = solution.grid();
+        assert_eq!(
+          GridState::Solved(&grid),
+          grid.variant_state(Variant::X),
+          "{}",
+          id
+        );
+      }
+      let summary = solve_variant(
+        &puzzle.clues,
+        Variant::X,
+        MAX_SOLUTIONS,
+        &mut DefaultHelper(),
+      );
+      assert_eq!(puzzle.solutions, summary.solutions, "{}", id);
+      // The clues only pin down the solutions with the diagonals' help.
+      let standard = solve(&puzzle.clues, MAX_SOLUTIONS, &mut DefaultHelper());
+      assert!(standard.too_many_solutions, "{}", id);
+    }
+  }
+
+  #[test]
+  fn test_generate_relation_puzzles() {
+    let date = LogicalDate::from_ymd(2025, 3, 14);
+    for variant in [Variant::AntiKnight, Variant::AntiKing] {
+      for counter in 1..=2 {
+        check_relation_puzzle(PuzzleId::new(date, counter, GENERATOR_VERSION), variant);
+      }
+    }
+  }
+
+  #[test]
+  fn test_generate_non_consecutive_puzzle() {
+    // These are slow enough to generate that one will do.
+    let date = LogicalDate::from_ymd(2025, 3, 14);
+    check_relation_puzzle(
+      PuzzleId::new(date, 1, GENERATOR_VERSION),
+      Variant::NonConsecutive,
+    );
+  }
+
+  fn check_relation_puzzle(id: PuzzleId, variant: Variant) {
+    let daily = daily_variant_solution(&id.date, variant);
+    let grid = daily.solution.grid();
+    assert_eq!(GridState::Solved(&grid), grid.variant_state(variant));
+    assert_eq!(
+      GridState::Solved(&grid),
+      grid.variant_state(Variant::Standard)
+    );
+    let puzzle = generate_variant_puzzle(id, variant).unwrap();
+    assert_eq!(variant, puzzle.variant);
+    assert!(puzzle.gen_opts.unwrap().sym.preserves(variant));
+    assert!(
+      puzzle.clues.len() >= variant.min_clues(),
+      "{} {}",
+      variant,
+      id
+    );
+    let summary = solve_variant(&puzzle.clues, variant, MAX_SOLUTIONS, &mut DefaultHelper());
+    assert_eq!(puzzle.solutions, summary.solutions, "{} {}", variant, id);
+    for solution in &puzzle.solutions {
+      let grid = solution.grid();
+      assert_eq!(
+        GridState::Solved(&grid),
+        grid.variant_state(variant),
+        "{} {}",
+        variant,
+        id
+      );
+    }
+  }
 }
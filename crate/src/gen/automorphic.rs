@@ -0,0 +1,203 @@
+//! Generates puzzles with automorphic solutions: solutions that some
+//! permutation of locations and numerals maps to themselves.
+
+use super::*;
+use crate::cycle;
+use crate::permute::{
+  BandPermutation, BlkLinePermutation, FullPermutation, GroupElement, LocPermutation,
+  NumPermutation, Permutable,
+};
+
+/// Describes a generated puzzle whose solution is automorphic.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct AutomorphicPuzzle {
+  /// The ID that generates this puzzle via `generate_automorphic_puzzle`.
+  pub id: PuzzleId,
+  /// The puzzle's clues.
+  pub clues: Grid,
+  /// The puzzle's unique solution.
+  pub solution: SolvedGrid,
+  /// The solution's automorphism group, which always has more than just the
+  /// identity.
+  pub automorphisms: Vec<GridPermutation>,
+  /// The symmetry the clues honor.
+  pub sym: Sym,
+}
+
+/// Generates the automorphic puzzle with the given ID, using the generator
+/// version the ID names.  These puzzles don't share a daily solution: each ID
+/// gets its own.
+pub fn generate_automorphic_puzzle(id: PuzzleId) -> Result<AutomorphicPuzzle, Error> {
+  Ok(generator(id.generator_version)?.generate_automorphic(&id.date, id.counter))
+}
+
+/// Chooses an automorphism for a solution to have, along with the symmetry
+/// its puzzle's clues should honor.  The automorphism is one of: a 180-degree
+/// rotation that swaps the numerals in pairs; a 90-degree rotation that
+/// cycles them in fours; or a shift of the row-bands that cycles them in
+/// threes.
+pub fn random_automorphism(random: &mut Random) -> (GridPermutation, Sym) {
+  let reversed_bands = BandPermutation::new([BAND3, BAND2, BAND1]).unwrap();
+  let reversed_lines = [BlkLinePermutation::new([BL3, BL2, BL1]).unwrap(); 3];
+  let (nums, locs, sym) = match random.random_range(0..3) {
+    0 => (
+      cycle!(Num; 1, 2)
+        .composed_with(&cycle!(Num; 3, 4))
+        .composed_with(&cycle!(Num; 5, 6))
+        .composed_with(&cycle!(Num; 7, 8)),
+      LocPermutation {
+        row_bands: reversed_bands,
+        col_bands: reversed_bands,
+        rows_in_bands: reversed_lines,
+        cols_in_bands: reversed_lines,
+        ..LocPermutation::identity()
+      },
+      Sym::Rotation180,
+    ),
+    1 => (
+      cycle!(Num; 1, 2, 3, 4).composed_with(&cycle!(Num; 5, 6, 7, 8)),
+      LocPermutation {
+        transpose: true,
+        col_bands: reversed_bands,
+        cols_in_bands: reversed_lines,
+        ..LocPermutation::identity()
+      },
+      Sym::Rotation90,
+    ),
+    _ => (
+      cycle!(Num; 1, 2, 3)
+        .composed_with(&cycle!(Num; 4, 5, 6))
+        .composed_with(&cycle!(Num; 7, 8, 9)),
+      LocPermutation {
+        row_bands: cycle!(Band; 0, 1, 2),
+        ..LocPermutation::identity()
+      },
+      Sym::Rotation180,
+    ),
+  };
+  // Disguises which numerals go together.
+  let disguise = NumPermutation::random(random);
+  let nums = disguise
+    .inverse()
+    .composed_with(&nums)
+    .composed_with(&disguise);
+  (GridPermutation { nums, locs }, sym)
+}
+
+/// Makes a random solution that the given permutation maps to itself, or
+/// returns None if the search runs into a dead end.
+///
+/// It adds clues an orbit of the permutation at a time, so the clues always
+/// map to themselves too, as long as the clues still have a solution.  Once
+/// there is only one solution, it must map to itself as well.
+pub fn gen_automorphic_solution(
+  automorphism: &GridPermutation,
+  random: &mut Random,
+) -> Option<SolvedGrid> {
+  let mut locs: Vec<Loc> = Loc::all().collect();
+  random.shuffle(&mut locs);
+  let mut clues = Grid::new();
+  for loc in locs {
+    if clues[loc].is_some() {
+      continue;
+    }
+    let mut nums: Vec<Num> = Num::all().collect();
+    random.shuffle(&mut nums);
+    let mut found = false;
+    for num in nums {
+      let Some(trial) = with_orbit(&clues, automorphism, loc, num) else {
+        continue;
+      };
+      let summary = solve(&trial, 1, &mut DefaultHelper());
+      if summary.solutions.is_empty() {
+        continue;
+      }
+      if !summary.too_many_solutions {
+        return summary.solutions.first().copied();
+      }
+      clues = trial;
+      found = true;
+      break;
+    }
+    if !found {
+      return None;
+    }
+  }
+  None
+}
+
+/// Adds the orbit of the given assignment under the given permutation to the
+/// given clues, or returns None if that orbit contradicts itself or them.
+fn with_orbit(clues: &Grid, automorphism: &GridPermutation, loc: Loc, num: Num) -> Option<Grid> {
+  let mut answer = *clues;
+  let (mut l, mut n) = (loc, num);
+  loop {
+    if answer[l].is_some_and(|existing| existing != n) {
+      return None;
+    }
+    answer[l] = Some(n);
+    l = automorphism.locs.apply(l);
+    n = automorphism.nums.apply(n);
+    if l == loc {
+      return (n == num).then_some(answer);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_random_automorphism() {
+    let mut random = new_random("automorphism");
+    for _ in 0..20 {
+      let (automorphism, sym) = random_automorphism(&mut random);
+      let mut power = automorphism;
+      let mut order = 1;
+      while power != GridPermutation::identity() {
+        power.compose(&automorphism);
+        order += 1;
+      }
+      let expected = match sym {
+        Sym::Rotation90 => 4,
+        // Only the shift of the row-bands leaves the columns alone.
+        _ if automorphism.locs.col_bands == BandPermutation::identity() => 3,
+        _ => 2,
+      };
+      assert_eq!(expected, order, "{:?}", automorphism);
+    }
+  }
+
+  #[test]
+  fn test_generate_automorphic_puzzles() {
+    let date = LogicalDate::from_ymd(2025, 3, 14);
+    for counter in 1..=3 {
+      let id = PuzzleId::new(date, counter, GENERATOR_VERSION);
+      let puzzle = generate_automorphic_puzzle(id).unwrap();
+      let summary = solve(&puzzle.clues, 1, &mut DefaultHelper());
+      assert_eq!(vec![puzzle.solution], summary.solutions, "{}", id);
+      assert!(puzzle.sym.evaluate(&puzzle.clues).is_complete(), "{}", id);
+
+      // The automorphisms leave the solution alone, and form a group: it has
+      // the identity, and is closed under composition.
+      assert!(puzzle.automorphisms.len() > 1, "{}", id);
+      assert!(
+        puzzle.automorphisms.contains(&GridPermutation::identity()),
+        "{}",
+        id
+      );
+      for a in &puzzle.automorphisms {
+        assert_eq!(
+          puzzle.solution,
+          a.apply_to_solved(&puzzle.solution),
+          "{}",
+          id
+        );
+        for b in &puzzle.automorphisms {
+          assert!(puzzle.automorphisms.contains(&a.composed_with(b)), "{}", id);
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,104 @@
+//! The golden corpus: a checked-in record of what every generator version
+//! makes for a spread of puzzle IDs, down to the rating each puzzle gets.
+//!
+//! The test below regenerates the whole corpus and compares it with the file,
+//! so a refactor that would silently change daily puzzles fails instead.  When
+//! the corpus should change, such as when a new generator version is added,
+//! rewrite the file with `luke-doku corpus -o testdata/puzzle-corpus.jsonl`.
+
+use serde::Serialize;
+
+use super::*;
+use crate::evaluate::{evaluate, Rating};
+use versions::GENERATORS;
+
+/// The dates the corpus covers, for every generator version.
+static DATES: &[&str] = &[
+  "1961-09-20",
+  "1999-12-31",
+  "2000-02-29",
+  "2024-06-02",
+  "2025-03-14",
+  "2025-11-02",
+  "2038-01-19",
+  "2100-07-04",
+];
+
+/// The counters the corpus covers, for every date.
+static COUNTERS: &[i32] = &[1, 2, 3, 17, 250];
+
+/// One entry in the corpus: everything generation produces for one ID.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CorpusRecord {
+  pub id: PuzzleId,
+  pub clues: String,
+  pub solutions: Vec<String>,
+  /// The solution that all of the date's puzzles are permutations of.
+  pub daily_solution: String,
+  /// The permutation's external form.
+  pub permutation: Vec<i8>,
+  pub sym: String,
+  pub broken: bool,
+  pub improper: bool,
+  pub rating: Rating,
+}
+
+/// The IDs the corpus covers, in the order they appear in it.
+pub fn corpus_ids() -> Vec<PuzzleId> {
+  let mut ids = vec![];
+  for generator in GENERATORS {
+    for date in DATES {
+      for &counter in COUNTERS {
+        ids.push(PuzzleId::new(
+          LogicalDate::from_str(date).unwrap(),
+          counter,
+          generator.version,
+        ));
+      }
+    }
+  }
+  ids
+}
+
+/// Generates and evaluates the puzzle with the given ID.
+pub fn corpus_record(id: PuzzleId) -> Result<CorpusRecord, Error> {
+  let puzzle = generate_puzzle(id)?;
+  let opts = puzzle.gen_opts.expect("generated puzzles have options");
+  Ok(CorpusRecord {
+    id,
+    clues: puzzle.clues.to_flat_string(),
+    solutions: puzzle.solutions.iter().map(|s| s.to_string()).collect(),
+    daily_solution: opts.daily_solution.solution.to_string(),
+    permutation: opts.permutation.bytes().to_vec(),
+    sym: format!("{:?}", opts.sym),
+    broken: opts.broken,
+    improper: opts.improper,
+    rating: evaluate(&puzzle),
+  })
+}
+
+impl CorpusRecord {
+  /// Renders this record as a line of the corpus file.
+  pub fn to_line(&self) -> String {
+    serde_json::to_string(self).expect("records are serializable")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  static CORPUS: &str = include_str!("../../testdata/puzzle-corpus.jsonl");
+
+  #[test]
+  fn test_corpus_matches() {
+    let ids = corpus_ids();
+    let lines: Vec<&str> = CORPUS.lines().collect();
+    assert_eq!(ids.len(), lines.len(), "the corpus is out of date");
+    for (id, line) in ids.into_iter().zip(lines) {
+      let record = corpus_record(id).unwrap();
+      assert_eq!(line, record.to_line(), "{}", id);
+    }
+  }
+}
@@ -0,0 +1,151 @@
+//! Generates Jigsaw Sudokus: random layouts of irregular regions, solutions
+//! that fit them, and puzzles that have those solutions.
+
+use super::*;
+use crate::solve::generic::UnitsSolver;
+
+/// Describes a generated Jigsaw Sudoku puzzle.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct JigsawPuzzle {
+  /// The ID that generates this puzzle via `generate_jigsaw_puzzle`.
+  pub id: PuzzleId,
+  /// The layout of the regions that take the place of the blocks.
+  pub regions: Regions,
+  /// The puzzle's clues.
+  pub clues: Grid,
+  /// The puzzle's unique solution, which fills every location.  Its rows,
+  /// columns and regions each hold every numeral, but its blocks needn't.
+  pub solution: Grid,
+  /// The symmetry the clues honor.
+  pub sym: Sym,
+}
+
+/// Generates the Jigsaw puzzle with the given ID, using the generator version
+/// the ID names.  Jigsaw puzzles don't share a daily solution: each ID gets
+/// its own layout.
+pub fn generate_jigsaw_puzzle(id: PuzzleId) -> Result<JigsawPuzzle, Error> {
+  Ok(generator(id.generator_version)?.generate_jigsaw(&id.date, id.counter))
+}
+
+/// Makes a random layout by trading locations between neighboring regions,
+/// starting from the blocks, until the given number of trades have been made.
+/// Each trade keeps both regions connected.  The result may not have any
+/// solutions, and may even be the standard layout again.
+pub fn gen_regions(trades: usize, random: &mut Random) -> Regions {
+  let mut regions = Regions::standard().to_array();
+  let region_locs = |regions: &[Region; 81], region: Region| -> LocSet {
+    Loc::all()
+      .filter(|loc| regions[loc.index()] == region)
+      .collect()
+  };
+  let mut made = 0;
+  while made < trades {
+    // Picks a location, `a`, to give to a neighboring region.
+    let a = Loc::from_index(random.random_range(0..81) as usize).unwrap();
+    let ra = regions[a.index()];
+    let neighbors: Vec<Loc> = orthogonal_neighbors(a)
+      .filter(|n| regions[n.index()] != ra)
+      .collect();
+    if neighbors.is_empty() {
+      continue;
+    }
+    let rb = regions[neighbors[random.random_range(0..neighbors.len() as i32) as usize].index()];
+    // Then picks a location, `c`, for `a`'s region to take in return.
+    let takers: Vec<Loc> = Loc::all()
+      .filter(|c| {
+        regions[c.index()] == rb
+          && orthogonal_neighbors(*c).any(|n| n != a && regions[n.index()] == ra)
+      })
+      .collect();
+    if takers.is_empty() {
+      continue;
+    }
+    let c = takers[random.random_range(0..takers.len() as i32) as usize];
+    regions[a.index()] = rb;
+    regions[c.index()] = ra;
+    if is_connected(region_locs(&regions, ra)) && is_connected(region_locs(&regions, rb)) {
+      made += 1;
+    } else {
+      regions[a.index()] = ra;
+      regions[c.index()] = rb;
+    }
+  }
+  Regions::new(regions).unwrap()
+}
+
+/// Makes a random solution for the given layout, or returns None if the
+/// search gives up after the given number of pivots; some layouts have no
+/// solutions at all.
+pub fn gen_jigsaw_solution(
+  regions: &Regions,
+  max_pivots: usize,
+  random: &mut Random,
+) -> Option<Grid> {
+  UnitsSolver::for_regions(regions).random_solution(&Grid::new(), random, max_pivots)
+}
+
+/// Removes clues from the given solution, an orbit of the given symmetry at a
+/// time, as long as the solution stays unique under the given layout.
+pub fn gen_jigsaw_clues(regions: &Regions, solution: &Grid, sym: Sym, random: &mut Random) -> Grid {
+  let solver = UnitsSolver::for_regions(regions);
+  let mut clues = *solution;
+  for orbit in sym.shuffled_orbits(random) {
+    let prev = clues;
+    for loc in orbit {
+      clues[*loc] = None;
+    }
+    if solver.solve(&clues, 1).too_many_solutions {
+      clues = prev;
+    }
+  }
+  clues
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_gen_regions() {
+    let mut random = new_random("regions");
+    for _ in 0..10 {
+      let regions = gen_regions(40, &mut random);
+      assert!(!regions.is_standard(), "{:?}", regions);
+      // `Regions::new` has checked the sizes and connectedness.
+      assert_eq!(regions, regions.to_string().parse().unwrap());
+    }
+  }
+
+  #[test]
+  fn test_generate_jigsaw_puzzles() {
+    let date = LogicalDate::from_ymd(2025, 3, 14);
+    let puzzles: Vec<JigsawPuzzle> = (1..=3)
+      .map(|counter| {
+        generate_jigsaw_puzzle(PuzzleId::new(date, counter, GENERATOR_VERSION)).unwrap()
+      })
+      .collect();
+    for puzzle in &puzzles {
+      // The solution fills the irregular regions, and they alone make it
+      // unique.
+      assert!(!puzzle.regions.is_standard());
+      for unit in puzzle.regions.units() {
+        let nums: NumSet = unit
+          .iter()
+          .map(|loc| puzzle.solution[loc].unwrap())
+          .collect();
+        assert_eq!(NumSet::all(), nums, "{}", puzzle.id);
+      }
+      let summary = solve_jigsaw(&puzzle.clues, &puzzle.regions, 1);
+      assert_eq!(vec![puzzle.solution], summary.solutions, "{}", puzzle.id);
+      assert!(puzzle.clues.len() < 40, "{}: {}", puzzle.id, puzzle.clues);
+      assert!(
+        puzzle.sym.evaluate(&puzzle.clues).is_complete(),
+        "{}",
+        puzzle.id
+      );
+    }
+    // Each puzzle of the day gets its own layout.
+    assert_ne!(puzzles[0].regions, puzzles[1].regions);
+    assert_ne!(puzzles[1].regions, puzzles[2].regions);
+  }
+}
@@ -0,0 +1,137 @@
+//! Generates Killer Sudokus: cages drawn over a permutation of the daily
+//! solution, with as few given numerals as it takes to make the solution
+//! unique.
+
+use super::*;
+
+/// Describes a generated Killer Sudoku puzzle.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct KillerPuzzle {
+  /// The ID that generates this puzzle via `generate_killer_puzzle`.
+  pub id: PuzzleId,
+  /// The cages, which cover the whole grid.
+  pub cages: Cages,
+  /// The numerals given outright, often none.
+  pub clues: Grid,
+  /// The puzzle's unique solution.
+  pub solution: SolvedGrid,
+}
+
+/// Generates the Killer puzzle with the given ID, using the generator version
+/// the ID names.  Killer puzzles are permutations of the standard daily
+/// solution.
+pub fn generate_killer_puzzle(id: PuzzleId) -> Result<KillerPuzzle, Error> {
+  let generator = generator(id.generator_version)?;
+  let daily_solution = generator.daily_solution(&id.date, Variant::Standard);
+  Ok(generator.generate_killer(&daily_solution, id.counter))
+}
+
+/// Divides the grid into random cages of orthogonally connected locations,
+/// each holding distinct numerals of the given solution, and at most
+/// `max_size` locations.
+pub fn gen_cages(solution: &SolvedGrid, max_size: i32, random: &mut Random) -> Cages {
+  let mut open = LocSet::all();
+  let mut cages = Vec::new();
+  while let Some(start) = open.smallest_item() {
+    let size = random.random_range(2..max_size + 1);
+    let mut locs = start.as_set();
+    let mut nums = solution[start].as_set();
+    open.remove(start);
+    while locs.len() < size {
+      let mut growth: Vec<Loc> = locs
+        .iter()
+        .flat_map(orthogonal_neighbors)
+        .filter(|loc| open.contains(*loc) && !nums.contains(solution[*loc]))
+        .collect();
+      growth.sort();
+      growth.dedup();
+      if growth.is_empty() {
+        break;
+      }
+      let loc = growth[random.random_range(0..growth.len() as i32) as usize];
+      locs.insert(loc);
+      nums.insert(solution[loc]);
+      open.remove(loc);
+    }
+    cages.push(Cage::from_solution(locs, solution));
+  }
+  Cages::new(cages).expect("the cages grown from the solution partition the grid")
+}
+
+/// Gives just enough of the solution's numerals to make it the cages' only
+/// solution: while there are others, gives a numeral at a random location
+/// where the first two solutions found differ.
+pub fn gen_killer_clues(cages: &Cages, solution: &SolvedGrid, random: &mut Random) -> Grid {
+  let mut clues = Grid::new();
+  loop {
+    let summary = solve_killer(&clues, cages, 1);
+    if !summary.too_many_solutions {
+      return clues;
+    }
+    let other = summary.solutions.iter().find(|s| *s != solution).unwrap();
+    let diffs: Vec<Loc> = Loc::all()
+      .filter(|loc| other[*loc] != solution[*loc])
+      .collect();
+    let loc = diffs[random.random_range(0..diffs.len() as i32) as usize];
+    clues[loc] = Some(solution[loc]);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::deduce::killer::{KillerFact, KillerFactFinder};
+
+  #[test]
+  fn test_gen_cages() {
+    let mut random = new_random("cages");
+    let solution = gen_solved_grid(Variant::Standard, &mut random);
+    let cages = gen_cages(&solution, 4, &mut random);
+    assert_eq!(LocSet::all(), cages.covered());
+    assert!(cages.are_satisfied_by(&solution));
+    for cage in cages.iter() {
+      assert!(cage.locs.len() <= 4);
+      assert!(is_connected(cage.locs));
+    }
+  }
+
+  #[test]
+  fn test_generate_killer_puzzles() {
+    let date = LogicalDate::from_ymd(2025, 3, 14);
+    let daily = generate_puzzle(PuzzleId::new(date, 1, 1))
+      .unwrap()
+      .gen_opts
+      .unwrap()
+      .daily_solution;
+    for counter in 1..=2 {
+      let id = PuzzleId::new(date, counter, GENERATOR_VERSION);
+      let puzzle = generate_killer_puzzle(id).unwrap();
+      let summary = solve_killer(&puzzle.clues, &puzzle.cages, 1);
+      assert_eq!(vec![puzzle.solution], summary.solutions, "{}", id);
+      assert!(puzzle.cages.are_satisfied_by(&puzzle.solution));
+      assert_eq!(
+        GridPermutation::minimizing(&daily.solution).1,
+        GridPermutation::minimizing(&puzzle.solution).1,
+        "{}",
+        id
+      );
+
+      // The clues alone are far from unique; the sums do the rest, starting
+      // with the combinations they rule out.
+      assert!(
+        solve(&puzzle.clues, 1, &mut DefaultHelper()).too_many_solutions,
+        "{}",
+        id
+      );
+      let finder = KillerFactFinder::new(&puzzle.clues, &puzzle.cages);
+      assert!(
+        finder
+          .deduce_cage_facts()
+          .iter()
+          .any(|fact| matches!(fact, KillerFact::CageCombinations { .. })),
+        "{}",
+        id
+      );
+    }
+  }
+}
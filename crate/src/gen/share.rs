@@ -0,0 +1,510 @@
+//! Encodes puzzles as short, URL-safe share codes, and decodes them again.
+//!
+//! A share code is base64url text (without padding) for these bytes:
+//!
+//! - a version byte, currently 2;
+//! - a bit-packed payload, which is the puzzle's variant followed by either
+//!   its clues or, for puzzles made by a supported generator version, its ID;
+//! - a two-byte CRC-16 (CCITT) checksum of everything before it, which catches
+//!   any single mistyped character.
+//!
+//! The payload starts with a bit saying which it is, then the variant's index
+//! in `Variant::ALL` (3 bits).  Clues are an 81-bit
+//! mask of the clue locations followed by the clue numerals, packed in pairs
+//! into 7 bits (since there are 81 pairs), with a leftover numeral taking 4
+//! bits.  An ID is the date's year (16 bits), month (4 bits) and day (5 bits),
+//! the generator version (8 bits), and the counter in 7-bit groups, each
+//! preceded by a bit saying whether another group follows.  Any bits left
+//! over in the last byte are zero.
+//!
+//! Version 1 codes, which had no variant and so name standard puzzles, still
+//! decode.
+
+use chrono::NaiveDate;
+use wasm_bindgen::prelude::*;
+
+use super::versions::generator;
+use super::{generate_variant_puzzle, Puzzle, PuzzleId};
+use crate::core::*;
+use crate::date::LogicalDate;
+use crate::error::Error;
+
+/// The version of the share-code format.
+pub const SHARE_CODE_VERSION: u8 = 2;
+
+/// What a share code holds.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Shared {
+  /// A puzzle's clues, and the variant whose rules they follow.
+  Clues(Grid, Variant),
+  /// The ID of a generated puzzle, and the variant it was generated for.
+  Id(PuzzleId, Variant),
+}
+
+/// Makes a share code for the given puzzle.  Uses the puzzle's ID if a
+/// supported generator version made it and the ID fits in a share code, and
+/// the clues otherwise.
+pub fn encode(puzzle: &Puzzle) -> String {
+  let by_id = match puzzle.gen_opts {
+    Some(opts) if generator(opts.daily_solution.generator_version).is_ok() => {
+      encode_shared(&Shared::Id(opts.id(), puzzle.variant)).ok()
+    }
+    _ => None,
+  };
+  by_id.unwrap_or_else(|| {
+    encode_shared(&Shared::Clues(puzzle.clues, puzzle.variant))
+      .expect("any clues fit in a share code")
+  })
+}
+
+/// Makes a share code for the given clues or ID.  Fails for an ID whose
+/// counter isn't positive, whose year doesn't fit in 16 signed bits, or whose
+/// generator version doesn't fit in 8 unsigned bits.
+pub fn encode_shared(shared: &Shared) -> Result<String, Error> {
+  if let Shared::Id(id, _) = shared {
+    check_shareable(id)?;
+  }
+  let mut out = BitWriter::default();
+  out.write(SHARE_CODE_VERSION as u32, 8);
+  match shared {
+    Shared::Clues(clues, variant) => {
+      out.write(0, 1);
+      out.write(*variant as u32, 3);
+      for loc in Loc::all() {
+        out.write(clues[loc].is_some() as u32, 1);
+      }
+      let nums: Vec<u32> = clues.iter().map(|a| a.num.index() as u32).collect();
+      for pair in nums.chunks(2) {
+        match pair {
+          [a, b] => out.write(a * 9 + b, 7),
+          [a] => out.write(*a, 4),
+          _ => unreachable!(),
+        }
+      }
+    }
+    Shared::Id(id, variant) => {
+      out.write(1, 1);
+      out.write(*variant as u32, 3);
+      out.write(id.date.year() as u16 as u32, 16);
+      out.write(id.date.month(), 4);
+      out.write(id.date.day(), 5);
+      out.write(id.generator_version as u8 as u32, 8);
+      let mut counter = id.counter as u32;
+      loop {
+        let more = counter >= 0x80;
+        out.write(more as u32, 1);
+        out.write(counter & 0x7f, 7);
+        counter >>= 7;
+        if !more {
+          break;
+        }
+      }
+    }
+  }
+  let mut bytes = out.bytes;
+  let sum = crc16(&bytes);
+  bytes.extend(sum.to_be_bytes());
+  Ok(to_base64url(&bytes))
+}
+
+/// Makes sure every field of the given ID fits in the bits a share code has
+/// for it.
+fn check_shareable(id: &PuzzleId) -> Result<(), Error> {
+  let problem = if id.counter < 1 {
+    "a counter below 1"
+  } else if i16::try_from(id.date.year()).is_err() {
+    "a year outside -32768 through 32767"
+  } else if u8::try_from(id.generator_version).is_err() {
+    "a generator version outside 0 through 255"
+  } else {
+    return Ok(());
+  };
+  Err(Error::MalformedPuzzleId(format!(
+    "{} has {}, which a share code can't hold",
+    id, problem
+  )))
+}
+
+/// Decodes a share code, rejecting any code that is malformed or corrupt.
+pub fn decode(code: &str) -> Result<Shared, Error> {
+  let bytes = from_base64url(code.trim())?;
+  if bytes.len() < 4 {
+    return Err(malformed(code, "is too short"));
+  }
+  let (data, sum) = bytes.split_at(bytes.len() - 2);
+  if crc16(data).to_be_bytes() != sum {
+    return Err(malformed(code, "is corrupt"));
+  }
+  let mut input = BitReader::new(code, data);
+  let version = input.read(8)? as u8;
+  if !(1..=SHARE_CODE_VERSION).contains(&version) {
+    return Err(Error::VersionMismatch {
+      what: "share code format",
+      version: version as i32,
+      supported: (1, SHARE_CODE_VERSION as i32),
+    });
+  }
+  let is_id = input.read(1)? == 1;
+  let variant = if version == 1 {
+    Variant::Standard
+  } else {
+    let index = input.read(3)? as usize;
+    *Variant::ALL
+      .get(index)
+      .ok_or_else(|| malformed(code, "has a bad variant"))?
+  };
+  let shared = if !is_id {
+    let mut locs = Vec::new();
+    for loc in Loc::all() {
+      if input.read(1)? == 1 {
+        locs.push(loc);
+      }
+    }
+    let mut clues = Grid::new();
+    for pair in locs.chunks(2) {
+      let (nums, width) = if pair.len() == 2 { (81, 7) } else { (9, 4) };
+      let value = input.read(width)?;
+      if value >= nums {
+        return Err(malformed(code, "has a bad numeral"));
+      }
+      let (a, b) = if pair.len() == 2 {
+        (value / 9, value % 9)
+      } else {
+        (value, 0)
+      };
+      for (&loc, index) in pair.iter().zip([a, b]) {
+        clues[loc] = Num::from_index(index as usize);
+      }
+    }
+    Shared::Clues(clues, variant)
+  } else {
+    let year = input.read(16)? as u16 as i16 as i32;
+    let month = input.read(4)?;
+    let day = input.read(5)?;
+    let generator_version = input.read(8)? as i32;
+    let mut counter: u32 = 0;
+    let mut shift = 0;
+    loop {
+      let more = input.read(1)? == 1;
+      let group = input.read(7)?;
+      if shift > 28 || (shift == 28 && group > 7) {
+        return Err(malformed(code, "has a bad counter"));
+      }
+      counter |= group << shift;
+      shift += 7;
+      if !more {
+        break;
+      }
+    }
+    let date =
+      NaiveDate::from_ymd_opt(year, month, day).ok_or_else(|| malformed(code, "has a bad date"))?;
+    let counter = i32::try_from(counter)
+      .ok()
+      .filter(|&counter| counter >= 1)
+      .ok_or_else(|| malformed(code, "has a bad counter"))?;
+    Shared::Id(
+      PuzzleId::new(LogicalDate::from(date), counter, generator_version),
+      variant,
+    )
+  };
+  if !input.is_done() {
+    return Err(malformed(code, "has extra data"));
+  }
+  Ok(shared)
+}
+
+/// Decodes a share code and reconstructs the puzzle, either by generating it
+/// from its ID or by solving its clues under its variant's rules.
+pub fn decode_puzzle(code: &str) -> Result<Puzzle, Error> {
+  match decode(code)? {
+    Shared::Clues(clues, variant) => Puzzle::new_for_variant(&clues, None, variant)
+      .ok_or_else(|| malformed(code, "is not a puzzle")),
+    Shared::Id(id, variant) => generate_variant_puzzle(id, variant),
+  }
+}
+
+/// Makes a share code for the given puzzle.
+#[wasm_bindgen(js_name = "shareCode")]
+pub fn share_code(puzzle: &Puzzle) -> String {
+  encode(puzzle)
+}
+
+/// Reconstructs the puzzle a share code was made for.
+#[wasm_bindgen(js_name = "puzzleFromShareCode")]
+pub fn puzzle_from_share_code(code: &str) -> Result<Puzzle, JsError> {
+  Ok(decode_puzzle(code)?)
+}
+
+/// Describes what's wrong with a share code.
+fn malformed(code: &str, problem: &str) -> Error {
+  Error::MalformedShareCode(format!("{:?} {}", code, problem))
+}
+
+/// Appends values to a byte vector, most significant bit first.
+#[derive(Default)]
+struct BitWriter {
+  bytes: Vec<u8>,
+  /// How many bits of the last byte are in use, 0 meaning all of them.
+  used: u32,
+}
+
+impl BitWriter {
+  fn write(&mut self, value: u32, width: u32) {
+    for i in (0..width).rev() {
+      if self.used == 0 {
+        self.bytes.push(0);
+      }
+      let bit = ((value >> i) & 1) as u8;
+      *self.bytes.last_mut().unwrap() |= bit << (7 - self.used);
+      self.used = (self.used + 1) % 8;
+    }
+  }
+}
+
+/// Reads the values a `BitWriter` wrote.
+struct BitReader<'a> {
+  /// The share code the bytes came from, for error messages.
+  code: &'a str,
+  bytes: &'a [u8],
+  pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+  fn new(code: &'a str, bytes: &'a [u8]) -> Self {
+    Self {
+      code,
+      bytes,
+      pos: 0,
+    }
+  }
+
+  fn read(&mut self, width: u32) -> Result<u32, Error> {
+    let mut value = 0;
+    for _ in 0..width {
+      let byte = self
+        .bytes
+        .get(self.pos / 8)
+        .ok_or_else(|| malformed(self.code, "is truncated"))?;
+      value = (value << 1) | ((byte >> (7 - self.pos % 8)) & 1) as u32;
+      self.pos += 1;
+    }
+    Ok(value)
+  }
+
+  /// Tells whether everything has been read, apart from zero padding.
+  fn is_done(&mut self) -> bool {
+    let rest = self.bytes.len() * 8 - self.pos;
+    rest < 8 && self.read(rest as u32) == Ok(0)
+  }
+}
+
+fn crc16(bytes: &[u8]) -> u16 {
+  let mut crc = 0xffffu16;
+  for &byte in bytes {
+    crc ^= (byte as u16) << 8;
+    for _ in 0..8 {
+      crc = if crc & 0x8000 != 0 {
+        (crc << 1) ^ 0x1021
+      } else {
+        crc << 1
+      };
+    }
+  }
+  crc
+}
+
+const BASE64URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+fn to_base64url(bytes: &[u8]) -> String {
+  let mut answer = String::new();
+  for chunk in bytes.chunks(3) {
+    let mut buf = [0u8; 3];
+    buf[..chunk.len()].copy_from_slice(chunk);
+    let n = u32::from_be_bytes([0, buf[0], buf[1], buf[2]]);
+    for i in 0..=chunk.len() {
+      answer.push(BASE64URL[(n >> (18 - 6 * i) & 63) as usize] as char);
+    }
+  }
+  answer
+}
+
+fn from_base64url(code: &str) -> Result<Vec<u8>, Error> {
+  let mut answer = Vec::new();
+  let (mut n, mut bits) = (0u32, 0);
+  for c in code.bytes() {
+    let value = BASE64URL
+      .iter()
+      .position(|&b| b == c)
+      .ok_or_else(|| malformed(code, &format!("has a bad character {:?}", c as char)))?;
+    n = (n << 6) | value as u32;
+    bits += 6;
+    if bits >= 8 {
+      bits -= 8;
+      answer.push((n >> bits) as u8);
+    }
+  }
+  if bits >= 6 || n & ((1 << bits) - 1) != 0 {
+    return Err(malformed(code, "has a bad length"));
+  }
+  Ok(answer)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::gen::{generate_puzzle, GENERATOR_VERSION};
+  use std::str::FromStr;
+
+  const CLUES: &str =
+    ".1..5..8.4.89.62.1..6...7....5.3.9.....8.7.....1.4.3....4...1..2.93.16.7.7..6..2.";
+
+  #[test]
+  fn test_clues_round_trip() {
+    let puzzle = Puzzle::new(&Grid::from_str(CLUES).unwrap(), None).unwrap();
+    let code = encode(&puzzle);
+    assert!(code.len() < 48, "{}", code);
+    assert!(code
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    assert_eq!(
+      Ok(Shared::Clues(puzzle.clues, Variant::Standard)),
+      decode(&code)
+    );
+    for clues in [Grid::new(), puzzle.solutions[0].grid()] {
+      for variant in Variant::ALL {
+        let shared = Shared::Clues(clues, variant);
+        assert_eq!(Ok(shared), decode(&encode_shared(&shared).unwrap()));
+      }
+    }
+    assert_eq!(Ok(puzzle), decode_puzzle(&code));
+  }
+
+  #[test]
+  fn test_x_puzzle_round_trip() {
+    let id = PuzzleId::new(LogicalDate::from_ymd(2025, 3, 14), 2, GENERATOR_VERSION);
+    let generated = generate_variant_puzzle(id, Variant::X).unwrap();
+    let code = encode(&generated);
+    assert_eq!(Ok(Shared::Id(id, Variant::X)), decode(&code));
+    assert_eq!(Ok(generated.clone()), decode_puzzle(&code));
+
+    // Standard Sudoku's rules leave the X puzzle's clues with other solutions.
+    assert_ne!(
+      Puzzle::new(&generated.clues, None).map(|p| p.solutions),
+      Some(generated.solutions.clone())
+    );
+    let entered = Puzzle::new_for_variant(&generated.clues, None, Variant::X).unwrap();
+    let code = encode(&entered);
+    assert_eq!(Ok(Shared::Clues(entered.clues, Variant::X)), decode(&code));
+    assert_eq!(Ok(entered), decode_puzzle(&code));
+  }
+
+  #[test]
+  fn test_decodes_version_1_codes() {
+    assert_eq!(
+      Ok(Shared::Clues(
+        Grid::from_str(CLUES).unwrap(),
+        Variant::Standard
+      )),
+      decode("ASStpEKgoKhEtqSCQo64K6NRQZUDKCvFwL1X")
+    );
+  }
+
+  #[test]
+  fn test_id_round_trip() {
+    for (date, counter) in [
+      (LogicalDate::from_ymd(2024, 2, 29), 1),
+      (LogicalDate::from_ymd(-40, 12, 31), 12345678),
+      (LogicalDate::from_ymd(9999, 1, 1), i32::MAX),
+    ] {
+      let shared = Shared::Id(
+        PuzzleId::new(date, counter, GENERATOR_VERSION),
+        Variant::Standard,
+      );
+      let code = encode_shared(&shared).unwrap();
+      assert!(code.len() <= 20, "{}", code);
+      assert_eq!(Ok(shared), decode(&code));
+    }
+
+    let id = PuzzleId::new(LogicalDate::from_ymd(2024, 6, 1), 3, GENERATOR_VERSION);
+    let puzzle = generate_puzzle(id).unwrap();
+    let code = encode(&puzzle);
+    assert_eq!(Ok(Shared::Id(id, Variant::Standard)), decode(&code));
+    assert_eq!(Ok(puzzle), decode_puzzle(&code));
+  }
+
+  #[test]
+  fn test_id_limits() {
+    let shared = |year, counter, version| {
+      let date = LogicalDate::from_ymd(year, 1, 1);
+      Shared::Id(PuzzleId::new(date, counter, version), Variant::Standard)
+    };
+    for fits in [shared(-32768, 1, 0), shared(32767, i32::MAX, 255)] {
+      assert_eq!(Ok(fits), decode(&encode_shared(&fits).unwrap()));
+    }
+    for (too_big, problem) in [
+      (shared(2024, 0, 1), "counter"),
+      (shared(2024, -1, 1), "counter"),
+      (shared(2024, i32::MIN, 1), "counter"),
+      (shared(-32769, 1, 1), "year"),
+      (shared(32768, 1, 1), "year"),
+      (shared(2024, 1, -1), "generator version"),
+      (shared(2024, 1, 256), "generator version"),
+    ] {
+      let result = encode_shared(&too_big);
+      assert!(
+        matches!(&result, Err(Error::MalformedPuzzleId(message)) if message.contains(problem)),
+        "{:?}: {:?}",
+        too_big,
+        result
+      );
+    }
+
+    // Puzzles whose IDs don't fit are shared by their clues instead.
+    let mut puzzle = generate_puzzle(PuzzleId::new(
+      LogicalDate::from_ymd(2024, 6, 1),
+      3,
+      GENERATOR_VERSION,
+    ))
+    .unwrap();
+    let mut opts = puzzle.gen_opts.unwrap();
+    opts.daily_solution.date = LogicalDate::from_ymd(40000, 6, 1);
+    puzzle.gen_opts = Some(opts);
+    assert_eq!(
+      Ok(Shared::Clues(puzzle.clues, Variant::Standard)),
+      decode(&encode(&puzzle))
+    );
+  }
+
+  #[test]
+  fn test_rejects_corrupt_codes() {
+    let code = encode_shared(&Shared::Clues(
+      Grid::from_str(CLUES).unwrap(),
+      Variant::Standard,
+    ))
+    .unwrap();
+    // Changing any single character breaks the checksum or the encoding.
+    for i in 0..code.len() {
+      let mut bytes = code.clone().into_bytes();
+      bytes[i] = if bytes[i] == b'A' { b'B' } else { b'A' };
+      let corrupt = String::from_utf8(bytes).unwrap();
+      assert!(decode(&corrupt).is_err(), "{}", corrupt);
+    }
+    assert!(decode("").is_err());
+    assert!(decode(&code[..code.len() - 1]).is_err());
+    assert!(decode(&code[1..]).is_err());
+    assert!(decode(&format!("{}A", code)).is_err());
+    assert!(decode(&format!("{}=", code)).is_err());
+
+    // A well-formed code of some other version.
+    let mut bytes = vec![SHARE_CODE_VERSION + 1, 0];
+    bytes.extend(crc16(&bytes).to_be_bytes());
+    assert_eq!(
+      Err(Error::VersionMismatch {
+        what: "share code format",
+        version: SHARE_CODE_VERSION as i32 + 1,
+        supported: (1, SHARE_CODE_VERSION as i32)
+      }),
+      decode(&to_base64url(&bytes))
+    );
+  }
+}
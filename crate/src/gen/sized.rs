@@ -0,0 +1,106 @@
+//! Generates puzzles on boards of other sizes than 9x9: random solutions, and
+//! puzzles that have those solutions.
+
+use super::*;
+use crate::solve::sized::SizedSolver;
+
+/// Describes a generated puzzle on a board whose blocks have `BR` rows and
+/// `BC` columns.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SizedPuzzle<const BR: usize, const BC: usize> {
+  /// The ID that generates this puzzle via `generate_sized_puzzle`.
+  pub id: PuzzleId,
+  /// The puzzle's clues.
+  pub clues: SizedGrid<BR, BC>,
+  /// The puzzle's unique solution.
+  pub solution: SizedGrid<BR, BC>,
+}
+
+/// Generates the puzzle of the given size with the given ID, using the
+/// generator version the ID names.  Each ID gets its own solution for each
+/// size.
+pub fn generate_sized_puzzle<const BR: usize, const BC: usize>(
+  id: PuzzleId,
+) -> Result<SizedPuzzle<BR, BC>, Error> {
+  Ok(generator(id.generator_version)?.generate_sized(&id.date, id.counter))
+}
+
+/// Makes a random solution, or returns None if the search gives up after the
+/// given number of pivots.
+pub fn gen_sized_solution<const BR: usize, const BC: usize>(
+  max_pivots: usize,
+  random: &mut Random,
+) -> Option<SizedGrid<BR, BC>> {
+  SizedSolver::new().random_solution(&SizedGrid::new(), random, max_pivots)
+}
+
+/// Removes clues from the given solution, a pair of cells opposite each other
+/// at a time, as long as the solution stays unique.  This gives the clues
+/// 180-degree rotational symmetry.
+pub fn gen_sized_clues<const BR: usize, const BC: usize>(
+  solution: &SizedGrid<BR, BC>,
+  random: &mut Random,
+) -> SizedGrid<BR, BC> {
+  let solver = SizedSolver::new();
+  let cells = SizedGrid::<BR, BC>::CELLS;
+  let mut orbits: Vec<usize> = (0..cells.div_ceil(2)).collect();
+  random.shuffle(&mut orbits);
+  let mut clues = *solution;
+  for cell in orbits {
+    let prev = clues;
+    clues.set(cell, None);
+    clues.set(cells - 1 - cell, None);
+    if solver.solve(&clues, 1).too_many_solutions {
+      clues = prev;
+    }
+  }
+  clues
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::collections::BTreeSet;
+
+  fn check_sized_puzzles<const BR: usize, const BC: usize>(
+    counters: std::ops::RangeInclusive<i32>,
+  ) {
+    let date = LogicalDate::from_ymd(2025, 3, 14);
+    let size = SizedGrid::<BR, BC>::SIZE;
+    let cells = SizedGrid::<BR, BC>::CELLS;
+    for counter in counters {
+      let id = PuzzleId::new(date, counter, GENERATOR_VERSION);
+      let puzzle = generate_sized_puzzle::<BR, BC>(id).unwrap();
+      let summary = SizedSolver::new().solve(&puzzle.clues, 1);
+      assert_eq!(vec![puzzle.solution], summary.solutions, "{}", id);
+      assert!(puzzle.clues.len() < cells / 2, "{}: {}", id, puzzle.clues);
+      for cell in 0..cells {
+        assert_eq!(
+          puzzle.clues.get(cell).is_some(),
+          puzzle.clues.get(cells - 1 - cell).is_some()
+        );
+      }
+
+      // Each block is `BR` rows by `BC` columns, and holds every numeral.
+      for unit in SizedGrid::<BR, BC>::units().filter(|unit| matches!(unit, SizedUnit::Blk(_))) {
+        let blk = SizedGrid::<BR, BC>::unit_cells(unit);
+        let rows: BTreeSet<usize> = blk.iter().map(|cell| cell / size).collect();
+        let cols: BTreeSet<usize> = blk.iter().map(|cell| cell % size).collect();
+        assert_eq!((BR, BC), (rows.len(), cols.len()), "{:?}", unit);
+        let nums: SizedNumSet = blk
+          .iter()
+          .map(|cell| puzzle.solution.get(*cell).unwrap())
+          .collect();
+        assert_eq!(SizedNumSet::all(size), nums, "{}: {:?}", id, unit);
+      }
+    }
+  }
+
+  #[test]
+  fn test_generate_sized_puzzles() {
+    check_sized_puzzles::<2, 2>(1..=3);
+    check_sized_puzzles::<2, 3>(1..=3);
+    check_sized_puzzles::<3, 4>(1..=1);
+    check_sized_puzzles::<4, 4>(1..=1);
+  }
+}
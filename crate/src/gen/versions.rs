@@ -0,0 +1,487 @@
+//! The registry of puzzle generator versions.  Each version pins down the
+//! parameters and algorithm that turn a date and counter into a puzzle, so
+//! that every puzzle ID keeps producing the same puzzle after the generator
+//! moves on.
+//!
+//! To change the generator, add a new version to `GENERATORS` rather than
+//! editing an existing one.  This includes changes to the sampling routines
+//! in `random` that alter the parts of them we use.
+//! Then regenerate the golden corpus in `corpus` to cover the new version.
+
+use super::*;
+
+/// One version of the Luke-doku puzzle generator.
+pub struct Generator {
+  /// The version number, which puzzle IDs record.
+  pub version: i32,
+  /// The probability of removing further clues after the symmetric ones.
+  pub broken_symmetry_prob: f64,
+  /// The probability of allowing more than one solution.
+  pub improper_prob: f64,
+  /// The most solutions an improper puzzle may have.
+  pub max_solutions: i32,
+  /// The most holes an improper puzzle's solutions may have.
+  pub max_holes: i32,
+  /// The symmetries to choose among, with their relative weights.
+  pub sym_weights: &'static [(Sym, i32)],
+  /// Makes the solution that all of a date's puzzles of a variant are
+  /// permutations of.
+  solve_daily: fn(&Generator, &LogicalDate, Variant) -> SolvedGrid,
+  /// Makes one of a date's puzzles.
+  generate: fn(&Generator, &DailySolution, i32) -> Puzzle,
+  /// Makes one of a date's Jigsaw puzzles.
+  generate_jigsaw: fn(&Generator, &LogicalDate, i32) -> JigsawPuzzle,
+  /// Makes one of a date's Killer puzzles from the standard base solution.
+  generate_killer: fn(&Generator, &DailySolution, i32) -> KillerPuzzle,
+  /// Makes one of a date's puzzles with an automorphic solution.
+  generate_automorphic: fn(&Generator, &LogicalDate, i32) -> AutomorphicPuzzle,
+  /// How to make one of a date's puzzles on a board of another size.
+  generate_sized: SizedGenerator,
+}
+
+/// The ways of making puzzles on boards of other sizes.  They can't be
+/// function pointers like the other parts of a `Generator`, because they're
+/// generic over the board's size.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum SizedGenerator {
+  V1,
+}
+
+impl Generator {
+  /// Makes the base solution of the given variant for the given date.
+  pub fn daily_solution(&self, date: &LogicalDate, variant: Variant) -> DailySolution {
+    DailySolution {
+      date: *date,
+      generator_version: self.version,
+      solution: (self.solve_daily)(self, date, variant),
+      variant,
+    }
+  }
+
+  /// Makes the puzzle with the given counter from the given base solution,
+  /// which must come from this generator.
+  pub fn generate(&self, daily_solution: &DailySolution, counter: i32) -> Puzzle {
+    debug_assert_eq!(self.version, daily_solution.generator_version);
+    (self.generate)(self, daily_solution, counter)
+  }
+
+  /// Makes the Jigsaw puzzle with the given counter for the given date.
+  pub fn generate_jigsaw(&self, date: &LogicalDate, counter: i32) -> JigsawPuzzle {
+    (self.generate_jigsaw)(self, date, counter)
+  }
+
+  /// Makes the Killer puzzle with the given counter from the given standard
+  /// base solution, which must come from this generator.
+  pub fn generate_killer(&self, daily_solution: &DailySolution, counter: i32) -> KillerPuzzle {
+    debug_assert_eq!(self.version, daily_solution.generator_version);
+    debug_assert_eq!(Variant::Standard, daily_solution.variant);
+    (self.generate_killer)(self, daily_solution, counter)
+  }
+
+  /// Makes the puzzle with an automorphic solution with the given counter for
+  /// the given date.
+  pub fn generate_automorphic(&self, date: &LogicalDate, counter: i32) -> AutomorphicPuzzle {
+    (self.generate_automorphic)(self, date, counter)
+  }
+
+  /// Makes the puzzle with the given counter for the given date on a board
+  /// whose blocks have `BR` rows and `BC` columns.
+  pub fn generate_sized<const BR: usize, const BC: usize>(
+    &self,
+    date: &LogicalDate,
+    counter: i32,
+  ) -> SizedPuzzle<BR, BC> {
+    match self.generate_sized {
+      SizedGenerator::V1 => generate_sized_v1(self, date, counter),
+    }
+  }
+}
+
+/// All the generator versions, oldest first.  The last is the current one.
+pub static GENERATORS: &[Generator] = &[Generator {
+  version: 1,
+  broken_symmetry_prob: 0.9,
+  improper_prob: 0.125,
+  max_solutions: 3,
+  max_holes: 7,
+  sym_weights: &[
+    (Sym::Rotation180, 100),
+    (Sym::Rotation90, 50),
+    (Sym::Mirror(Axis::X), 50),
+    (Sym::Mirror(Axis::Y), 50),
+    (Sym::DoubleMirror, 25),
+    (Sym::Diagonal(Diagonal::Main), 50),
+    (Sym::Diagonal(Diagonal::Anti), 50),
+    (Sym::DoubleDiagonal, 25),
+    (Sym::FullyReflective, 10),
+    (Sym::Blockwise(Diagonal::Main), 25),
+    (Sym::Blockwise(Diagonal::Anti), 25),
+    (Sym::None, 50),
+  ],
+  solve_daily: solve_daily_v1,
+  generate: generate_v1,
+  generate_jigsaw: generate_jigsaw_v1,
+  generate_killer: generate_killer_v1,
+  generate_automorphic: generate_automorphic_v1,
+  generate_sized: SizedGenerator::V1,
+}];
+
+/// Looks up the given version of the generator.
+pub fn generator(version: i32) -> Result<&'static Generator, Error> {
+  GENERATORS
+    .iter()
+    .find(|g| g.version == version)
+    .ok_or(Error::VersionMismatch {
+      what: "generator",
+      version,
+      supported: (GENERATORS[0].version, GENERATOR_VERSION),
+    })
+}
+
+/// The current version of the generator.
+pub fn current_generator() -> &'static Generator {
+  GENERATORS.last().unwrap()
+}
+
+fn solve_daily_v1(_generator: &Generator, date: &LogicalDate, variant: Variant) -> SolvedGrid {
+  let seed = date.to_string(); // yyyy-mm-dd
+  let mut random = new_random(&seed);
+  gen_solved_grid(variant, &mut random)
+}
+
+fn generate_v1(generator: &Generator, daily_solution: &DailySolution, counter: i32) -> Puzzle {
+  let seed = format!("{}:{}", daily_solution.date, counter);
+  let mut random = new_random(&seed);
+  let variant = daily_solution.variant;
+  let permutation = GridPermutation::random_for_variant(variant, &mut random);
+  let solution = permutation.apply_to_solved(&daily_solution.solution);
+
+  let sym_weights: Vec<(Sym, i32)> = generator
+    .sym_weights
+    .iter()
+    .copied()
+    .filter(|(sym, _)| sym.preserves(variant))
+    .collect();
+  let weights = sym_weights.iter().map(|item| item.1);
+  let sym_dist = WeightedIndex::new(weights).unwrap();
+  let sym = sym_weights[sym_dist.sample(&mut random)].0;
+  let broken = random.random_bool(generator.broken_symmetry_prob);
+  let improper = random.random_bool(generator.improper_prob);
+  let (max_solutions, max_holes) = if improper {
+    (generator.max_solutions, generator.max_holes)
+  } else {
+    (1, 0)
+  };
+  let summary = gen_puzzle(
+    &solution,
+    variant,
+    sym,
+    broken,
+    max_solutions,
+    max_holes,
+    &mut random,
+  );
+  Puzzle {
+    clues: summary.clues,
+    gen_opts: Some(GenOpts {
+      daily_solution: *daily_solution,
+      counter,
+      permutation: *permutation.external(),
+      sym,
+      broken,
+      improper,
+    }),
+    solutions: summary.solutions,
+    variant,
+  }
+}
+
+fn generate_jigsaw_v1(generator: &Generator, date: &LogicalDate, counter: i32) -> JigsawPuzzle {
+  let seed = format!("{}:{}:jigsaw", date, counter);
+  let mut random = new_random(&seed);
+  let (regions, solution) = loop {
+    let regions = gen_regions(40, &mut random);
+    if regions.is_standard() {
+      continue;
+    }
+    if let Some(solution) = gen_jigsaw_solution(&regions, 10_000, &mut random) {
+      break (regions, solution);
+    }
+  };
+  let sym = Sym::Rotation180;
+  JigsawPuzzle {
+    id: PuzzleId::new(*date, counter, generator.version),
+    regions,
+    clues: gen_jigsaw_clues(&regions, &solution, sym, &mut random),
+    solution,
+    sym,
+  }
+}
+
+fn generate_killer_v1(
+  generator: &Generator,
+  daily_solution: &DailySolution,
+  counter: i32,
+) -> KillerPuzzle {
+  let seed = format!("{}:{}:killer", daily_solution.date, counter);
+  let mut random = new_random(&seed);
+  let permutation = GridPermutation::random(&mut random);
+  let solution = permutation.apply_to_solved(&daily_solution.solution);
+  let cages = gen_cages(&solution, 4, &mut random);
+  KillerPuzzle {
+    id: PuzzleId::new(daily_solution.date, counter, generator.version),
+    clues: gen_killer_clues(&cages, &solution, &mut random),
+    cages,
+    solution,
+  }
+}
+
+fn generate_automorphic_v1(
+  generator: &Generator,
+  date: &LogicalDate,
+  counter: i32,
+) -> AutomorphicPuzzle {
+  let seed = format!("{}:{}:automorphic", date, counter);
+  let mut random = new_random(&seed);
+  let (automorphism, sym) = random_automorphism(&mut random);
+  let solution = loop {
+    if let Some(solution) = gen_automorphic_solution(&automorphism, &mut random) {
+      break solution;
+    }
+  };
+  let summary = gen_puzzle(&solution, Variant::Standard, sym, false, 1, 0, &mut random);
+  AutomorphicPuzzle {
+    id: PuzzleId::new(*date, counter, generator.version),
+    clues: summary.clues,
+    solution,
+    automorphisms: GridPermutation::automorphisms(&solution),
+    sym,
+  }
+}
+
+fn generate_sized_v1<const BR: usize, const BC: usize>(
+  generator: &Generator,
+  date: &LogicalDate,
+  counter: i32,
+) -> SizedPuzzle<BR, BC> {
+  let size = SizedGrid::<BR, BC>::SIZE;
+  let seed = format!("{}:{}:{}x{}", date, counter, size, size);
+  let mut random = new_random(&seed);
+  let solution = loop {
+    if let Some(solution) = gen_sized_solution(10_000, &mut random) {
+      break solution;
+    }
+  };
+  SizedPuzzle {
+    id: PuzzleId::new(*date, counter, generator.version),
+    clues: gen_sized_clues(&solution, &mut random),
+    solution,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// The kinds of puzzle a generator makes.
+  #[derive(Clone, Copy, Debug)]
+  enum Kind {
+    Rules(Variant),
+    Jigsaw,
+    Killer,
+    Automorphic,
+    Sized2x2,
+    Sized2x3,
+  }
+
+  use Kind::*;
+
+  /// Pins the output of each generator version, so that any change that would
+  /// alter an existing version's puzzles fails here.  Each entry is an ID's
+  /// version, the kind of puzzle, the ID's date and counter, and what the
+  /// puzzle it generates looks like; see `describe`.
+  static GOLDEN: &[(i32, Kind, &str, i32, &str)] = &[
+    (
+      1,
+      Rules(Variant::Standard),
+      "1961-09-20",
+      1,
+      "8...6..4.1.7........2...63....23....49...5.7....64......3...8........4..5...1...2",
+    ),
+    (
+      1,
+      Rules(Variant::Standard),
+      "2024-06-01",
+      3,
+      "2...5..195.........6.....4.....7.6..1..5.9.....8.4.....75..216...3...9.4.2..9...7",
+    ),
+    (
+      1,
+      Rules(Variant::Standard),
+      "2025-03-14",
+      12,
+      "...1.32..4..5....75.......8.6..7.......3..1...7..2..5.2........6....4..2..58..3..",
+    ),
+    (
+      1,
+      Rules(Variant::Standard),
+      "2025-03-14",
+      16,
+      ".....69.5..9....4..32.4..1.9.4.....8.7...2....6..91.2...78.5.......2...9.4..1..57",
+    ),
+    (
+      1,
+      Rules(Variant::Standard),
+      "2025-03-14",
+      19,
+      "1......5......5.1.6.3..2...9...1.8..3.4.7......5...7...8.7..6...2....9.5...6....4",
+    ),
+    (
+      1,
+      Rules(Variant::X),
+      "2025-03-14",
+      2,
+      "............2.4......3..2...52....6...........6....98...5..1..43..6.7....9....8..",
+    ),
+    (
+      1,
+      Rules(Variant::AntiKnight),
+      "2025-03-14",
+      3,
+      "....6...3..7........2.......5...2......8....6........8..1....8.9.............3...",
+    ),
+    (
+      1,
+      Rules(Variant::AntiKing),
+      "2025-03-14",
+      4,
+      "...8.7.234..952..7..........3.....8.1.........5.....3.............714....8.3.9..2",
+    ),
+    (
+      1,
+      Rules(Variant::NonConsecutive),
+      "2025-03-14",
+      5,
+      "4....5.........8................49..1.......8..91................8.........5....6",
+    ),
+    (
+      1,
+      Jigsaw,
+      "2025-03-14",
+      1,
+      concat!(
+        "111122233112123333142222533145555563445456666444456996778889966778888999777778899 ",
+        ".........5..9.31......2..6...74...2...........2...74...7..1......98.4..5.........",
+      ),
+    ),
+    (
+      1,
+      Killer,
+      "2025-03-14",
+      1,
+      concat!(
+        "ABBCDDEEFABGCDHEFFIIGCJHHKKLIIJJMHKNLLOOOMPQNRRSOTMPQUVVSSTTWUUXVSYTZWaaXXbYZZcad ",
+        "8,21,17,10,16,18,4,20,21,15,17,14,8,11,26,6,15,12,21,26,9,7,11,17,12,12,10,5,9,7 ",
+        "................................4.............7..................................",
+      ),
+    ),
+    (
+      1,
+      Automorphic,
+      "2025-03-14",
+      1,
+      "..6127........92...9..6...351......99.7...3.88......267...5..8...18........3125..",
+    ),
+    (1, Sized2x2, "2025-03-14", 1, "3......21......1"),
+    (
+      1,
+      Sized2x3,
+      "2025-03-14",
+      1,
+      ".3........35.14.2..6.45.24........6.",
+    ),
+  ];
+
+  /// Generates the puzzle of the given kind with the given ID, and describes
+  /// it in a line: the clues, preceded for a Jigsaw by the layout's region
+  /// ordinals, and for a Killer by each location's cage (as a letter) and the
+  /// cages' sums.
+  fn describe(kind: Kind, id: PuzzleId) -> (PuzzleId, String) {
+    const LETTERS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+    match kind {
+      Rules(variant) => {
+        let puzzle = generate_variant_puzzle(id, variant).unwrap();
+        let generated_id = puzzle.gen_opts.unwrap().id();
+        (generated_id, puzzle.clues.to_flat_string())
+      }
+      Jigsaw => {
+        let puzzle = generate_jigsaw_puzzle(id).unwrap();
+        let clues = puzzle.clues.to_flat_string();
+        (puzzle.id, format!("{} {}", puzzle.regions, clues))
+      }
+      Killer => {
+        let puzzle = generate_killer_puzzle(id).unwrap();
+        let mut map = ['?'; 81];
+        let mut sums = Vec::new();
+        for (i, cage) in puzzle.cages.iter().enumerate() {
+          let letter = LETTERS[i] as char;
+          for loc in cage.locs.iter() {
+            map[loc.index()] = letter;
+          }
+          sums.push(cage.sum.to_string());
+        }
+        let map: String = map.iter().collect();
+        let clues = puzzle.clues.to_flat_string();
+        (puzzle.id, format!("{} {} {}", map, sums.join(","), clues))
+      }
+      Automorphic => {
+        let puzzle = generate_automorphic_puzzle(id).unwrap();
+        (puzzle.id, puzzle.clues.to_flat_string())
+      }
+      Sized2x2 => {
+        let puzzle = generate_sized_puzzle::<2, 2>(id).unwrap();
+        (puzzle.id, puzzle.clues.to_string())
+      }
+      Sized2x3 => {
+        let puzzle = generate_sized_puzzle::<2, 3>(id).unwrap();
+        (puzzle.id, puzzle.clues.to_string())
+      }
+    }
+  }
+
+  #[test]
+  fn test_golden_puzzles() {
+    for &(version, kind, date, counter, expected) in GOLDEN {
+      let id = PuzzleId::new(LogicalDate::from_str(date).unwrap(), counter, version);
+      let (generated_id, actual) = describe(kind, id);
+      assert_eq!(expected, actual, "{:?} {}", kind, id);
+      assert_eq!(id, generated_id, "{:?}", kind);
+    }
+  }
+
+  #[test]
+  fn test_every_version_has_golden_puzzles() {
+    assert_eq!(GENERATOR_VERSION, current_generator().version);
+    for generator in GENERATORS {
+      assert!(
+        GOLDEN.iter().any(|g| g.0 == generator.version),
+        "no golden puzzles for version {}",
+        generator.version
+      );
+      assert!(generator.max_solutions <= MAX_SOLUTIONS);
+      assert!(generator.max_holes <= MAX_HOLES);
+    }
+  }
+
+  #[test]
+  fn test_unsupported_versions() {
+    let date = LogicalDate::from_ymd(1961, 9, 20);
+    assert!(generator(0).is_err());
+    assert!(generate_puzzle(PuzzleId::new(date, 1, 0)).is_err());
+    assert!(generate_puzzle(PuzzleId::new(date, 1, GENERATOR_VERSION + 1)).is_err());
+    assert!(generate_jigsaw_puzzle(PuzzleId::new(date, 1, 0)).is_err());
+    assert!(generate_killer_puzzle(PuzzleId::new(date, 1, 0)).is_err());
+    assert!(generate_sized_puzzle::<2, 2>(PuzzleId::new(date, 1, 0)).is_err());
+    assert!(generate_automorphic_puzzle(PuzzleId::new(date, 1, 0)).is_err());
+  }
+}
@@ -4,7 +4,9 @@
 pub mod core;
 pub mod date;
 pub mod deduce;
+pub mod error;
 pub mod evaluate;
+pub mod formats;
 pub mod gen;
 pub mod permute;
 pub mod random;
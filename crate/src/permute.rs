@@ -31,15 +31,46 @@ const PERM_SIZE: usize = size_of::<GridPermutation>();
 #[wasm_bindgen]
 pub struct ExternalGridPermutation([i8; PERM_SIZE]);
 
+impl ExternalGridPermutation {
+  /// The external representation's bytes.
+  pub fn bytes(&self) -> &[i8] {
+    &self.0
+  }
+}
+
 impl GridPermutation {
   /// Makes a random Sudoku permutation from the given Random.
-  pub fn random<R: Rng>(random: &mut R) -> Self {
+  pub fn random(random: &mut Random) -> Self {
     Self {
       nums: NumPermutation::random(random),
       locs: LocPermutation::random(random),
     }
   }
 
+  /// Makes a random permutation from the given Random that also preserves
+  /// the validity of grids under the given variant's rules.
+  pub fn random_for_variant(variant: Variant, random: &mut Random) -> Self {
+    match variant {
+      Variant::Standard => Self::random(random),
+      Variant::X => Self {
+        nums: NumPermutation::random(random),
+        locs: LocPermutation::random_diagonal_preserving(random),
+      },
+      Variant::AntiKnight | Variant::AntiKing => Self {
+        nums: NumPermutation::random(random),
+        locs: LocPermutation::random_geometric(random),
+      },
+      Variant::NonConsecutive => Self {
+        nums: if random.next_bool() {
+          NumPermutation::new(Num::identity().map(|num| Num::new(10 - num.get()).unwrap())).unwrap()
+        } else {
+          NumPermutation::identity()
+        },
+        locs: LocPermutation::random_geometric(random),
+      },
+    }
+  }
+
   /// Converts to the external representation.
   pub fn external(&self) -> &ExternalGridPermutation {
     unsafe {
@@ -105,6 +136,32 @@ impl GridPermutation {
       .unwrap();
     (answer, min, num_total)
   }
+
+  /// Finds all the permutations that map the given solved grid to itself,
+  /// which form its automorphism group under `composed_with`.  The identity
+  /// comes first.  There are as many of them as the count of minimizing
+  /// permutations that `minimizing` returns, since each of those is one of
+  /// them followed by the first.
+  pub fn automorphisms(grid: &SolvedGrid) -> Vec<Self> {
+    let candidates: Vec<(Self, SolvedGrid)> = band_minimizing(grid)
+      .into_iter()
+      .map(|perm| grid_minimizing(perm, grid))
+      .collect();
+    let min = candidates.iter().map(|(_, g)| *g).min().unwrap();
+    let mut minimizing = candidates
+      .into_iter()
+      .filter(|(_, g)| *g == min)
+      .map(|(perm, _)| perm);
+    let first = minimizing.next().unwrap();
+    let mut answer = vec![Self::identity()];
+    for perm in minimizing {
+      let automorphism = first.composed_with(&perm.inverse());
+      if !answer.contains(&automorphism) {
+        answer.push(automorphism);
+      }
+    }
+    answer
+  }
 }
 
 /// Implemented by types that can belong to a simple permutation array.
@@ -200,9 +257,9 @@ where
   }
 
   /// Makes a new random permutation.
-  pub fn random<R: Rng>(random: &mut R) -> Self {
+  pub fn random(random: &mut Random) -> Self {
     let mut array = T::identity();
-    array.shuffle(random);
+    random.shuffle(&mut array);
     Self(array)
   }
 
@@ -396,9 +453,9 @@ pub struct LocPermutation {
 }
 
 impl LocPermutation {
-  pub fn random<R: Rng>(random: &mut R) -> Self {
+  pub fn random(random: &mut Random) -> Self {
     Self {
-      transpose: random.random(),
+      transpose: random.next_bool(),
       row_bands: BandPermutation::random(random),
       col_bands: BandPermutation::random(random),
       rows_in_bands: [
@@ -414,6 +471,70 @@ impl LocPermutation {
     }
   }
 
+  /// Makes a random permutation that maps the main diagonals onto each other,
+  /// as X-Sudoku requires.  It permutes the rows and the columns the same way,
+  /// and in a way that commutes with reversing their order: so the band
+  /// permutation either fixes or swaps the outer bands, the middle band's
+  /// lines are likewise fixed or reversed, and the outer bands' line
+  /// permutations mirror each other.
+  pub fn random_diagonal_preserving(random: &mut Random) -> Self {
+    let bands = if random.next_bool() {
+      BandPermutation::new([BAND3, BAND2, BAND1]).unwrap()
+    } else {
+      BandPermutation::identity()
+    };
+    let outer = BlkLinePermutation::random(random);
+    let middle = if random.next_bool() {
+      BlkLinePermutation::new([BL3, BL2, BL1]).unwrap()
+    } else {
+      BlkLinePermutation::identity()
+    };
+    let mirror = |line: BlkLine| unsafe {
+      // Safe because BlkLines are in 0..3.
+      BlkLine::new_unchecked(2 - line.get())
+    };
+    let mirrored =
+      BlkLinePermutation::new(BlkLine::identity().map(|line| mirror(outer.apply(mirror(line)))))
+        .unwrap();
+    let lines = [outer, middle, mirrored];
+    Self {
+      transpose: random.next_bool(),
+      row_bands: bands,
+      col_bands: bands,
+      rows_in_bands: lines,
+      cols_in_bands: lines,
+    }
+  }
+
+  /// Makes a random permutation that keeps neighboring locations neighbors,
+  /// as the variants that relate locations by distance require: one of the 8
+  /// reflections and rotations of the whole grid.
+  pub fn random_geometric(random: &mut Random) -> Self {
+    let (rows, cols) = (random.next_bool(), random.next_bool());
+    let flip = |reverse: bool| {
+      if reverse {
+        (
+          BandPermutation::new([BAND3, BAND2, BAND1]).unwrap(),
+          [BlkLinePermutation::new([BL3, BL2, BL1]).unwrap(); 3],
+        )
+      } else {
+        (
+          BandPermutation::identity(),
+          [BlkLinePermutation::identity(); 3],
+        )
+      }
+    };
+    let (row_bands, rows_in_bands) = flip(rows);
+    let (col_bands, cols_in_bands) = flip(cols);
+    Self {
+      transpose: random.next_bool(),
+      row_bands,
+      col_bands,
+      rows_in_bands,
+      cols_in_bands,
+    }
+  }
+
   /// Applies this permutation to the given location.
   pub fn apply(&self, mut loc: Loc) -> Loc {
     if self.transpose {
@@ -797,6 +918,19 @@ mod tests {
     assert_eq!(GridPermutation::identity().apply(&g), g);
   }
 
+  #[test]
+  fn test_random_diagonal_preserving() {
+    let mut random = new_random("test");
+    let diags = [D1.locs(), D2.locs()];
+    for _ in 0..50 {
+      let p = LocPermutation::random_diagonal_preserving(&mut random);
+      for diag in diags {
+        let image: LocSet = diag.iter().map(|loc| p.apply(loc)).collect();
+        assert!(diags.contains(&image), "{:?}", p);
+      }
+    }
+  }
+
   #[test]
   fn test_random() {
     let mut random = new_random("test");
@@ -1022,6 +1156,24 @@ mod tests {
     cycle!(Band; 1, 2, 1);
   }
 
+  #[test]
+  fn test_automorphisms() {
+    for (g, count) in [(symmetric_grid(), 54), (asymmetric_grid(), 1)] {
+      let solved = SolvedGrid::try_from(&g).unwrap();
+      let group = GridPermutation::automorphisms(&solved);
+      assert_eq!(count, group.len());
+      assert_eq!(count, GridPermutation::minimizing(&solved).2);
+      assert_eq!(GridPermutation::identity(), group[0]);
+      for a in &group {
+        assert_eq!(g, a.apply(&g), "{:?}", a);
+        assert!(group.contains(&a.inverse()));
+        for b in &group {
+          assert!(group.contains(&a.composed_with(b)));
+        }
+      }
+    }
+  }
+
   #[test]
   fn test_minimizing() {
     fn test(grid: &Grid, perm: GridPermutation, min: &Grid, count: usize) {
@@ -1,45 +1,352 @@
-use rand_distr::{Distribution, Normal};
-use static_assertions::const_assert;
-use std::mem::size_of;
+//! Our own pseudo-random number generator, along with the routines that turn
+//! its output into the samples we need.
+//!
+//! Puzzle IDs name puzzles by the date and counter that seed their
+//! generation, so every bit this module produces from a given seed is part of
+//! the puzzle format.  That's why we don't rely on an outside crate for it:
+//! the algorithms here are frozen, and the golden-value tests below pin them.
+//! (They reproduce, bit for bit, what `rand` 0.9 produced when seeded by
+//! `rand_seeder` 0.4, which is what version 1 of the generator used.)
+
+use std::ops::Range;
 use wasm_bindgen::prelude::wasm_bindgen;
 
-/// The pseudo-random number generator we use.
-pub type Random = rand_pcg::Pcg64Mcg;
+mod ziggurat;
+
+/// The pseudo-random number generator we use.  This is PCG64-MCG, also known
+/// as Mcg128Xsl64: a 128-bit multiplicative congruential generator whose
+/// output is the XOR of its state's halves, rotated right by the state's top
+/// 6 bits.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Random {
+  state: u128,
+}
 
-// Expose some key traits for simplicity.
-pub use rand::prelude::SliceRandom;
-pub use rand::Rng;
+/// The multiplier for the PCG family's 128-bit generators.
+const MULTIPLIER: u128 = 0x2360_ED05_1FC6_5DA4_4385_DF64_9FCC_F645;
 
 /// Constructs a new Random from a given string seed.
+///
+/// The string is hashed, followed by a single 0xff byte, with SipHash-2-4 and
+/// zero keys.  Rather than finalizing the hash, we keep running its rounds as
+/// a generator, and take its first two outputs, little-endian, as the 16-byte
+/// seed for `Random::from_seed`.
 pub fn new_random(seed: &str) -> Random {
-  rand_seeder::Seeder::from(seed).into_rng()
+  let mut sip = SipSeeder::new();
+  sip.write(seed.as_bytes());
+  sip.write(&[0xff]);
+  let mut bytes = [0; 16];
+  for chunk in bytes.chunks_exact_mut(8) {
+    chunk.copy_from_slice(&sip.next_u64().to_le_bytes());
+  }
+  Random::from_seed(bytes)
+}
+
+impl Random {
+  /// Makes a Random from a 16-byte seed, read as a little-endian number.  The
+  /// state of a multiplicative generator must be odd, so we set its low bit.
+  pub fn from_seed(seed: [u8; 16]) -> Self {
+    Self {
+      state: u128::from_le_bytes(seed) | 1,
+    }
+  }
+
+  /// Generates the next 64 random bits.
+  pub fn next_u64(&mut self) -> u64 {
+    self.state = self.state.wrapping_mul(MULTIPLIER);
+    let rot = (self.state >> 122) as u32;
+    let xsl = ((self.state >> 64) as u64) ^ (self.state as u64);
+    xsl.rotate_right(rot)
+  }
+
+  /// Generates the next 32 random bits: the low half of the next 64.
+  pub fn next_u32(&mut self) -> u32 {
+    self.next_u64() as u32
+  }
+
+  /// Flips a fair coin, using the top bit of the next 32.
+  pub fn next_bool(&mut self) -> bool {
+    (self.next_u32() as i32) < 0
+  }
+
+  /// Generates a floating-point number in the half-open interval [0, 1), from
+  /// the top 53 bits of the next 64.
+  pub fn next_f64(&mut self) -> f64 {
+    const SCALE: f64 = 1.0 / (1u64 << 53) as f64;
+    SCALE * (self.next_u64() >> 11) as f64
+  }
+
+  /// Returns true with the given probability, which must be in [0, 1].  We
+  /// compare the next 64 bits to the probability scaled to 2^64; a probability
+  /// of exactly 1 doesn't consume any bits.
+  pub fn random_bool(&mut self, p: f64) -> bool {
+    const SCALE: f64 = 2.0 * (1u64 << 63) as f64;
+    if !(0.0..1.0).contains(&p) {
+      assert!(p == 1.0, "p={:?} is outside range [0.0, 1.0]", p);
+      return true;
+    }
+    self.next_u64() < (p * SCALE) as u64
+  }
+
+  /// Generates an integer in the given non-empty range, using Canon's method.
+  pub fn random_range(&mut self, range: Range<i32>) -> i32 {
+    assert!(range.start < range.end, "cannot sample empty range");
+    let span = range.end.wrapping_sub(range.start) as u32;
+    range.start.wrapping_add(self.below(span) as i32)
+  }
+
+  /// Shuffles the given slice in place.  This is the Fisher-Yates shuffle,
+  /// run from the front: the element at each index is swapped with one at or
+  /// before it.  To save bits, the indices for several consecutive swaps come
+  /// from a single number, chosen below the product of their bounds.
+  pub fn shuffle<T>(&mut self, slice: &mut [T]) {
+    if slice.len() <= 1 {
+      return;
+    }
+    let mut chunk = 0;
+    let mut chunk_remaining = 0;
+    // The first swap is always in place, so it needs no bits.
+    for i in 1..slice.len() {
+      let bound = i as u32 + 1;
+      if chunk_remaining == 0 {
+        (chunk, chunk_remaining) = chunk_bounds(bound);
+        chunk = self.below(chunk);
+      }
+      let index = chunk % bound;
+      chunk /= bound;
+      chunk_remaining -= 1;
+      slice.swap(i, index as usize);
+    }
+  }
+
+  /// Generates a normally distributed floating-point number with the given
+  /// mean and standard deviation.  This is the ziggurat method as `rand_distr`
+  /// 0.5 implements it: the low 8 bits of the next 64 pick a layer, and the
+  /// top 52 give a signed position within it.  Positions outside the layer's
+  /// core are accepted or rejected against the density, and those in the base
+  /// layer's overhang are sampled from the tail directly.
+  pub fn normal(&mut self, mean: f64, std_dev: f64) -> f64 {
+    mean + std_dev * self.standard_normal()
+  }
+
+  fn standard_normal(&mut self) -> f64 {
+    loop {
+      let bits = self.next_u64();
+      let i = (bits & 0xff) as usize;
+      // Fills in the mantissa of a number in [2, 4), giving one in [-1, 1).
+      let u = f64::from_bits((1024 << 52) | (bits >> 12)) - 3.0;
+      let x = u * ziggurat::X[i];
+      if x.abs() < ziggurat::X[i + 1] {
+        return x;
+      }
+      if i == 0 {
+        return self.normal_tail(u);
+      }
+      let f = ziggurat::F[i + 1] + (ziggurat::F[i] - ziggurat::F[i + 1]) * self.next_f64();
+      if f < (-x * x / 2.0).exp() {
+        return x;
+      }
+    }
+  }
+
+  /// Samples the part of the distribution beyond `ziggurat::R`, on the side
+  /// given by the sign of `u`.
+  fn normal_tail(&mut self, u: f64) -> f64 {
+    let (mut x, mut y) = (1.0f64, 0.0f64);
+    while -2.0 * y < x * x {
+      x = self.next_open01().ln() / ziggurat::R;
+      y = self.next_open01().ln();
+    }
+    if u < 0.0 {
+      x - ziggurat::R
+    } else {
+      ziggurat::R - x
+    }
+  }
+
+  /// Generates a floating-point number in the open interval (0, 1), from the
+  /// top 52 bits of the next 64.
+  fn next_open01(&mut self) -> f64 {
+    f64::from_bits((1023 << 52) | (self.next_u64() >> 12)) - (1.0 - f64::EPSILON / 2.0)
+  }
+
+  /// Generates a number less than the given non-zero bound, using Canon's
+  /// method: the high half of the next 32 bits times the bound, corrected by a
+  /// second sample when the low half is close enough to carry.
+  fn below(&mut self, bound: u32) -> u32 {
+    let (mut result, low) = wide_mul(self.next_u32(), bound);
+    if low > bound.wrapping_neg() {
+      let (next_high, _) = wide_mul(self.next_u32(), bound);
+      result += low.checked_add(next_high).is_none() as u32;
+    }
+    result
+  }
+}
+
+/// Chooses indices with probabilities proportional to a list of weights.
+#[derive(Clone, Debug)]
+pub struct WeightedIndex {
+  /// The running totals of the weights, omitting the last (which is `total`).
+  cumulative: Vec<u32>,
+  total: u32,
+  /// Samples whose low half falls below this are rejected, which makes the
+  /// choice exactly uniform.
+  thresh: u32,
+}
+
+impl WeightedIndex {
+  /// Makes a WeightedIndex from the given weights, which must be
+  /// non-negative, not all zero, and sum to less than 2^31.
+  pub fn new(weights: impl IntoIterator<Item = i32>) -> Result<Self, String> {
+    let mut cumulative = vec![];
+    let mut total: i32 = 0;
+    for (i, weight) in weights.into_iter().enumerate() {
+      if weight < 0 {
+        return Err(format!("Negative weight {} at index {}", weight, i));
+      }
+      if i > 0 {
+        cumulative.push(total as u32);
+      }
+      total = total
+        .checked_add(weight)
+        .ok_or_else(|| "Weights add up to too much".to_string())?;
+    }
+    if total == 0 {
+      return Err("No non-zero weights".to_string());
+    }
+    let total = total as u32;
+    Ok(Self {
+      cumulative,
+      total,
+      thresh: total.wrapping_neg() % total,
+    })
+  }
+
+  /// Chooses an index.  We choose a number uniformly below the total weight,
+  /// rejecting samples as needed, and find the first index whose running total
+  /// exceeds it.
+  pub fn sample(&self, random: &mut Random) -> usize {
+    let chosen = loop {
+      let (high, low) = wide_mul(random.next_u32(), self.total);
+      if low >= self.thresh {
+        break high;
+      }
+    };
+    self.cumulative.partition_point(|&w| w <= chosen)
+  }
+}
+
+/// Multiplies two 32-bit numbers, returning the high and low halves of the
+/// 64-bit product.
+fn wide_mul(a: u32, b: u32) -> (u32, u32) {
+  let product = a as u64 * b as u64;
+  ((product >> 32) as u32, product as u32)
+}
+
+/// Returns the product of `bound` and as many of its successors as fit in 32
+/// bits, along with how many numbers went into it.
+fn chunk_bounds(bound: u32) -> (u32, u32) {
+  let mut product = bound;
+  let mut count = 1;
+  while let Some(p) = product.checked_mul(bound + count) {
+    product = p;
+    count += 1;
+  }
+  (product, count)
+}
+
+/// SipHash-2-4, used as a generator for turning seed strings into seeds.
+struct SipSeeder {
+  v: [u64; 4],
+  length: usize,
+  tail: u64,
+  /// Once the input is complete, what to mix into `v[2]` for the next output.
+  adj: Option<u64>,
+}
+
+impl SipSeeder {
+  fn new() -> Self {
+    Self {
+      v: [
+        0x736f6d6570736575,
+        0x646f72616e646f6d,
+        0x6c7967656e657261,
+        0x7465646279746573,
+      ],
+      length: 0,
+      tail: 0,
+      adj: None,
+    }
+  }
+
+  /// Hashes the given bytes, eight at a time, little-endian.
+  fn write(&mut self, bytes: &[u8]) {
+    debug_assert!(self.adj.is_none());
+    for &byte in bytes {
+      self.tail |= (byte as u64) << (8 * (self.length % 8));
+      self.length += 1;
+      if self.length.is_multiple_of(8) {
+        self.absorb(self.tail);
+        self.tail = 0;
+      }
+    }
+  }
+
+  /// Generates the next 64 bits, finishing the input first if need be.
+  fn next_u64(&mut self) -> u64 {
+    let adj = match self.adj {
+      Some(adj) => adj,
+      None => {
+        self.absorb(((self.length as u64 & 0xff) << 56) | self.tail);
+        self.rounds();
+        0x13
+      }
+    };
+    self.v[2] ^= adj;
+    self.adj = Some(adj.wrapping_sub(0x11));
+    self.rounds();
+    self.v[0] ^ self.v[1] ^ self.v[2] ^ self.v[3]
+  }
+
+  fn absorb(&mut self, word: u64) {
+    self.v[3] ^= word;
+    self.rounds();
+    self.v[0] ^= word;
+  }
+
+  /// Runs two rounds of SipHash's compression function.
+  fn rounds(&mut self) {
+    let [mut v0, mut v1, mut v2, mut v3] = self.v;
+    for _ in 0..2 {
+      v0 = v0.wrapping_add(v1);
+      v1 = v1.rotate_left(13) ^ v0;
+      v0 = v0.rotate_left(32);
+      v2 = v2.wrapping_add(v3);
+      v3 = v3.rotate_left(16) ^ v2;
+      v0 = v0.wrapping_add(v3);
+      v3 = v3.rotate_left(21) ^ v0;
+      v2 = v2.wrapping_add(v1);
+      v1 = v1.rotate_left(17) ^ v2;
+      v2 = v2.rotate_left(32);
+    }
+    self.v = [v0, v1, v2, v3];
+  }
 }
 
 /// The alias for Random we expose to JS.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
-#[repr(C, align(16))]
 #[wasm_bindgen]
-pub struct JsRandom {
-  guts: u128,
-}
-const_assert!(size_of::<Random>() == size_of::<JsRandom>());
+pub struct JsRandom(Random);
 
 impl JsRandom {
-  /// Externalizes a Random by cloning its bits.
+  /// Externalizes a Random by copying it.
   pub fn from_rng(rng: &Random) -> Self {
-    unsafe {
-      let p: *const Random = rng;
-      let guts = *(p as *const u128);
-      Self { guts }
-    }
+    Self(*rng)
   }
 
   /// Treats an externalized random as a random.
   pub fn rng(&mut self) -> &mut Random {
-    unsafe {
-      let p: *mut Self = self;
-      &mut *(p as *mut Random)
-    }
+    &mut self.0
   }
 }
 
@@ -48,18 +355,18 @@ impl JsRandom {
   /// Makes a new RNG from a given string seed.
   #[wasm_bindgen(constructor)]
   pub fn new(seed: &str) -> Self {
-    Self::from_rng(&mut new_random(seed))
+    Self(new_random(seed))
   }
 
   /// Generates the next floating-point number in the half-open interval [0,
   /// 1).
   pub fn next(&mut self) -> f64 {
-    self.rng().random()
+    self.0.next_f64()
   }
 
   /// Returns true with the given probability.
   pub fn choice(&mut self, p: f64) -> bool {
-    self.rng().random_bool(p)
+    self.0.random_bool(p)
   }
 
   /// Generates a floating-point number in the half-open interval [a, b).
@@ -70,6 +377,116 @@ impl JsRandom {
   /// Generates a normally distributed floating-point number with the given
   /// mean and standard deviation.
   pub fn normal(&mut self, mean: f64, std_dev: f64) -> f64 {
-    Normal::new(mean, std_dev).unwrap().sample(self.rng())
+    self.0.normal(mean, std_dev)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_seeding() {
+    let mut random = new_random("test");
+    let values: Vec<u64> = (0..4).map(|_| random.next_u64()).collect();
+    assert_eq!(
+      vec![
+        4663728078234469464,
+        17437053768872574073,
+        1417667016683901305,
+        12994912502951158944
+      ],
+      values
+    );
+  }
+
+  #[test]
+  fn test_sampling() {
+    let mut random = new_random("2025-03-14:12");
+    let bools: Vec<bool> = (0..8).map(|_| random.random_bool(0.3)).collect();
+    assert_eq!(
+      vec![true, false, false, false, true, false, false, true],
+      bools
+    );
+
+    let mut random = new_random("range");
+    let ints: Vec<i32> = (0..10).map(|_| random.random_range(0..7)).collect();
+    assert_eq!(vec![5, 0, 6, 6, 6, 6, 2, 6, 2, 1], ints);
+    let ints: Vec<i32> = (0..10).map(|_| random.random_range(0..81)).collect();
+    assert_eq!(vec![19, 34, 21, 70, 6, 12, 3, 50, 66, 48], ints);
+
+    let mut random = new_random("shuffle");
+    let mut array: Vec<i32> = (0..20).collect();
+    random.shuffle(&mut array);
+    assert_eq!(
+      vec![18, 3, 0, 14, 9, 15, 1, 19, 11, 5, 12, 17, 16, 7, 13, 4, 6, 10, 2, 8],
+      array
+    );
+
+    let mut random = new_random("weighted");
+    let dist = WeightedIndex::new([100, 50, 50, 50, 25, 50, 50, 25, 10, 25, 25, 50]).unwrap();
+    let indices: Vec<usize> = (0..12).map(|_| dist.sample(&mut random)).collect();
+    assert_eq!(vec![0, 1, 5, 7, 9, 6, 1, 5, 6, 11, 0, 9], indices);
+
+    let mut random = new_random("float");
+    let floats: Vec<f64> = (0..3).map(|_| random.next_f64()).collect();
+    assert_eq!(
+      vec![
+        0.15757663186145743,
+        0.11670655214921888,
+        0.03236759410211898
+      ],
+      floats
+    );
+    let bools: Vec<bool> = (0..8).map(|_| random.next_bool()).collect();
+    assert_eq!(
+      vec![false, false, true, false, true, false, true, false],
+      bools
+    );
+  }
+
+  #[test]
+  fn test_weighted_index_errors() {
+    assert!(WeightedIndex::new([]).is_err());
+    assert!(WeightedIndex::new([0, 0]).is_err());
+    assert!(WeightedIndex::new([1, -1]).is_err());
+    assert!(WeightedIndex::new([i32::MAX, 1]).is_err());
+    let dist = WeightedIndex::new([0, 3, 0]).unwrap();
+    let mut random = new_random("zeros");
+    assert!((0..20).all(|_| dist.sample(&mut random) == 1));
+  }
+
+  #[test]
+  fn test_normal() {
+    let mut random = new_random("normal");
+    let n = 10000;
+    let samples: Vec<f64> = (0..n).map(|_| random.normal(5.0, 2.0)).collect();
+    let mean = samples.iter().sum::<f64>() / n as f64;
+    let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n as f64;
+    assert!((mean - 5.0).abs() < 0.1, "{}", mean);
+    assert!((variance.sqrt() - 2.0).abs() < 0.1, "{}", variance);
+
+    let mut random = new_random("normal");
+    let samples: Vec<f64> = (0..6).map(|_| random.normal(5.0, 2.0)).collect();
+    assert_eq!(
+      vec![
+        3.6135057800370527,
+        4.441413349476837,
+        4.801211217878184,
+        3.0615826629010474,
+        3.136678553033327,
+        3.6804930330681076
+      ],
+      samples
+    );
+  }
+
+  #[test]
+  fn test_normal_tail() {
+    // Enough samples to land in the tail, and in every layer's overhang.
+    let mut random = new_random("tail");
+    let samples: Vec<f64> = (0..100000).map(|_| random.normal(0.0, 1.0)).collect();
+    assert_eq!(41, samples.iter().filter(|x| x.abs() > ziggurat::R).count());
+    assert_eq!(-595.3959000875643, samples.iter().sum::<f64>());
   }
 }
@@ -2,12 +2,23 @@
 
 use crate::core::*;
 use crate::random::*;
+use wasm_bindgen::prelude::*;
 
+pub mod ambiguity;
+pub mod generic;
 pub mod ledger;
+mod search;
+pub mod sized;
+pub mod snapshot;
+pub mod unavoidable;
 
+use ambiguity::Ambiguity;
 use ledger::*;
 
-pub struct SolutionSummary {
+/// The results of solving a puzzle.  The solutions are usually Sudoku
+/// solutions; a Jigsaw Sudoku's are full grids, since its blocks needn't hold
+/// every numeral.
+pub struct SolutionSummary<S = SolvedGrid> {
   pub clues: Grid,
 
   /// Whether there were more solutions than the maximum number we would allow.
@@ -18,15 +29,88 @@ pub struct SolutionSummary {
   /// The possible solutions to the puzzle.  When `too_many_solutions` is true,
   /// this may be a subset of the puzzle's solutions; when it is false, this is
   /// the complete set of solutions.
-  pub solutions: Vec<SolvedGrid>,
+  pub solutions: Vec<S>,
 }
 
 /// Solves the given puzzle.
 pub fn solve(clues: &Grid, max_solutions: i32, helper: &mut dyn SearchHelper) -> SolutionSummary {
-  let factory = SearcherFactory::new(clues);
+  summarize(*clues, SearcherFactory::new(clues), max_solutions, helper)
+}
+
+/// Solves the given puzzle under the given variant's rules.
+pub fn solve_variant(
+  clues: &Grid,
+  variant: Variant,
+  max_solutions: i32,
+  helper: &mut dyn SearchHelper,
+) -> SolutionSummary {
+  summarize(
+    *clues,
+    SearcherFactory::new_for_variant(clues, variant),
+    max_solutions,
+    helper,
+  )
+}
+
+/// Solves the given Jigsaw puzzle, whose blocks are replaced by the given
+/// layout's regions.  The standard layout gets the usual solver; others get
+/// the slower, generic one.
+pub fn solve_jigsaw(clues: &Grid, regions: &Regions, max_solutions: i32) -> SolutionSummary<Grid> {
+  if !regions.is_standard() {
+    return generic::UnitsSolver::for_regions(regions).solve(clues, max_solutions);
+  }
+  let summary = solve(clues, max_solutions, &mut DefaultHelper());
+  SolutionSummary {
+    clues: summary.clues,
+    too_many_solutions: summary.too_many_solutions,
+    solutions: summary.solutions.iter().map(SolvedGrid::grid).collect(),
+  }
+}
+
+/// Solves the given Killer puzzle: the clues' rules, plus the cages' sums.
+pub fn solve_killer(clues: &Grid, cages: &Cages, max_solutions: i32) -> SolutionSummary {
+  let summary = generic::UnitsSolver::for_regions(&Regions::standard())
+    .with_cages(cages)
+    .solve(clues, max_solutions);
+  SolutionSummary {
+    clues: summary.clues,
+    too_many_solutions: summary.too_many_solutions,
+    solutions: summary
+      .solutions
+      .iter()
+      .map(|grid| {
+        grid
+          .solved_grid()
+          .expect("the standard units make Sudoku solutions")
+      })
+      .collect(),
+  }
+}
+
+/// Solves the given Sukaku puzzle, or resumes solving a puzzle from the given
+/// candidates.  The summary's clues are the locations with a single candidate.
+pub fn solve_candidates(
+  candidates: &CandidateGrid,
+  max_solutions: i32,
+  helper: &mut dyn SearchHelper,
+) -> SolutionSummary {
+  summarize(
+    candidates.to_grid(),
+    SearcherFactory::from_candidates(candidates),
+    max_solutions,
+    helper,
+  )
+}
+
+fn summarize(
+  clues: Grid,
+  factory: SearcherFactory,
+  max_solutions: i32,
+  helper: &mut dyn SearchHelper,
+) -> SolutionSummary {
   let mut searcher = factory.new_searcher(helper);
   let mut summary = SolutionSummary {
-    clues: *clues,
+    clues,
     too_many_solutions: false,
     solutions: searcher.found.map_or_else(|| Vec::new(), |s| vec![s]),
   };
@@ -43,6 +127,58 @@ pub fn solve(clues: &Grid, max_solutions: i32, helper: &mut dyn SearchHelper) ->
   summary
 }
 
+/// Returns an iterator over all the solutions to the given puzzle, in the
+/// order the helper's search finds them.
+pub fn solutions<'a>(clues: &Grid, helper: &'a mut dyn SearchHelper) -> Solutions<'a> {
+  let factory = SearcherFactory::new(clues);
+  Solutions {
+    searcher: Searcher::new(&factory.ledger, &factory.doubles, helper),
+  }
+}
+
+/// Iterates through the solutions of a puzzle, searching lazily for each one.
+pub struct Solutions<'a> {
+  searcher: Searcher<'a>,
+}
+
+impl Iterator for Solutions<'_> {
+  type Item = SolvedGrid;
+
+  fn next(&mut self) -> Option<SolvedGrid> {
+    // The searcher may have found a solution while being constructed.
+    if self.searcher.found.is_none() {
+      self.searcher.run(None);
+    }
+    self.searcher.found.take()
+  }
+}
+
+/// Counts the solutions to the given puzzle, stopping once it reaches `limit`.
+/// This is faster than iterating through `solutions` because it doesn't
+/// construct the solved grids.
+pub fn count_solutions(clues: &Grid, limit: u64) -> u64 {
+  if limit == 0 {
+    return 0;
+  }
+  let factory = SearcherFactory::new(clues);
+  let mut helper = DefaultHelper();
+  let mut searcher = factory.new_searcher(&mut helper);
+  let mut count = searcher.found.is_some() as u64;
+  while count < limit {
+    if searcher.advance(None).1.is_none() {
+      break;
+    }
+    count += 1;
+  }
+  count
+}
+
+/// Counts the solutions to the given puzzle, up to `limit`.
+#[wasm_bindgen(js_name = "countSolutions")]
+pub fn count_solutions_js(clues: &Grid, limit: u32) -> u32 {
+  count_solutions(clues, limit as u64) as u32
+}
+
 impl SolutionSummary {
   /// Counts the number of holes in the intersection of all of the solutions.
   pub fn num_holes(&self) -> i32 {
@@ -55,6 +191,25 @@ impl SolutionSummary {
     }
     81 - intersection.len() as i32
   }
+
+  /// Analyzes how the solutions differ from each other.  When
+  /// `too_many_solutions` is true, this describes only the solutions that were
+  /// found.
+  pub fn ambiguity(&self) -> Ambiguity {
+    Ambiguity::new(&self.solutions)
+  }
+}
+
+/// A point where the search branches into alternatives.  A pivot with just
+/// one alternative is a forced move, and one with none is a dead end that the
+/// search backs out of.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Pivot {
+  /// Tries each of the possible numerals at an unset location.
+  Loc(Loc),
+  /// Tries each of the possible unset locations for a numeral within a unit.
+  /// The numeral must not already be assigned within the unit.
+  Unit(Num, Unit),
 }
 
 /// Callbacks for searching the Sudoku solution space.
@@ -62,8 +217,18 @@ pub trait SearchHelper {
   /// Decides on a location to search through.
   fn choose_pivot_loc(&mut self, ledger: &Ledger, doubles: &LocSet) -> Loc;
 
+  /// Decides where to branch next.  By default, pivots on the location chosen
+  /// by `choose_pivot_loc`.
+  fn choose_pivot(&mut self, ledger: &Ledger, doubles: &LocSet) -> Pivot {
+    Pivot::Loc(self.choose_pivot_loc(ledger, doubles))
+  }
+
   /// Optionally puts the given numerals into a different order.
   fn order_pivot_nums(&mut self, nums: &mut [Option<Num>]);
+
+  /// Optionally puts the given locations into a different order.  By default,
+  /// leaves them alone.
+  fn order_pivot_locs(&mut self, _locs: &mut [Option<Loc>]) {}
 }
 
 /// A fast and deterministic `SearchHelper` implementation, due to Emerentius.
@@ -110,13 +275,55 @@ impl SearchHelper for JczHelper {
   fn order_pivot_nums(&mut self, _nums: &mut [Option<Num>]) {}
 }
 
+/// A deterministic `SearchHelper` that branches on where a numeral goes within
+/// a unit whenever that has fewer options than `DefaultHelper`'s choice of
+/// location.
+pub struct UnitPivotHelper();
+
+impl SearchHelper for UnitPivotHelper {
+  fn choose_pivot_loc(&mut self, ledger: &Ledger, doubles: &LocSet) -> Loc {
+    DefaultHelper().choose_pivot_loc(ledger, doubles)
+  }
+
+  fn choose_pivot(&mut self, ledger: &Ledger, doubles: &LocSet) -> Pivot {
+    // No unit can beat a location with just two possible numerals.
+    let loc = self.choose_pivot_loc(ledger, doubles);
+    if !doubles.is_empty() {
+      return Pivot::Loc(loc);
+    }
+    let loc_count = Num::all()
+      .filter(|num| ledger.is_possible(*num, loc))
+      .count() as i32;
+    let asgmts = ledger.asgmts();
+    let mut best = (loc_count, Pivot::Loc(loc));
+    for num in Num::all() {
+      let num_locs = asgmts.num_locs(num) & *ledger.unset();
+      for unit in ledger.variant().units() {
+        let count = (num_locs & unit.locs()).len();
+        // A count of 1 would mean the numeral's location within the unit is
+        // already determined, which `apply_implications` takes care of; a
+        // count of 0 means it's already assigned in the unit.
+        if count >= 2 && count < best.0 {
+          best = (count, Pivot::Unit(num, unit));
+          if count == 2 {
+            return best.1;
+          }
+        }
+      }
+    }
+    best.1
+  }
+
+  fn order_pivot_nums(&mut self, _nums: &mut [Option<Num>]) {}
+}
+
 /// A `SearchHelper` that picks pivot points at random.  It still prefers
 /// locations in `doubles` when there are any.
 ///
 /// Does not touch the order of pivot numerals.
-pub struct RandomPivotHelper<'a, R: Rng>(&'a mut R);
+pub struct RandomPivotHelper<'a>(&'a mut Random);
 
-impl<'a, R: Rng> SearchHelper for RandomPivotHelper<'a, R> {
+impl SearchHelper for RandomPivotHelper<'_> {
   fn choose_pivot_loc(&mut self, ledger: &Ledger, doubles: &LocSet) -> Loc {
     // Takes anything from doubles, or anything from unset.
     if !doubles.is_empty() {
@@ -140,7 +347,18 @@ pub struct SearcherFactory {
 
 impl SearcherFactory {
   pub fn new(clues: &Grid) -> Self {
-    let result = Ledger::new(clues);
+    Self::from_ledger(Ledger::new(clues))
+  }
+
+  pub fn new_for_variant(clues: &Grid, variant: Variant) -> Self {
+    Self::from_ledger(Ledger::new_for_variant(clues, variant))
+  }
+
+  pub fn from_candidates(candidates: &CandidateGrid) -> Self {
+    Self::from_ledger(Ok(Ledger::from_candidates(candidates)))
+  }
+
+  fn from_ledger(result: Result<Ledger, Invalid>) -> Self {
     if let Ok(mut ledger) = result {
       let result = ledger.apply_implications();
       if let Ok(doubles) = result {
@@ -172,12 +390,11 @@ pub struct Searcher<'a> {
 struct StackItem {
   ledger: Ledger,
 
-  /// An unassigned location we'll try all possible numerals in.
-  pivot_loc: Loc,
-
-  /// The numerals to try assigning to the pivot location.  `count` is the
-  /// total number, and `next` is the index of the next one to try.
-  nums: [Option<Num>; 9],
+  /// The alternative assignments to try at this pivot point: either all the
+  /// possible numerals for a location, or all the possible locations for a
+  /// numeral within a unit.  `count` is the total number, and `next` is the
+  /// index of the next one to try.
+  asgmts: [Option<Asgmt>; 9],
   count: i8,
   next: i8,
 }
@@ -187,29 +404,38 @@ impl<'a> Searcher<'a> {
   /// pivot points (or all remaining pivot points if `max_pivots` is
   /// `None`). Returns the number of pivot points traversed.
   pub fn run(&mut self, max_pivots: Option<i32>) -> i32 {
-    self.found = None;
+    let (count, complete) = self.advance(max_pivots);
+    self.found = complete.and_then(|ledger| Self::solution(&ledger));
+    count
+  }
+
+  /// Does the work of `run`, but returns the completed ledger instead of
+  /// converting it to a solved grid.
+  fn advance(&mut self, max_pivots: Option<i32>) -> (i32, Option<Ledger>) {
     let mut count = 0;
+    let mut complete = None;
     while !self.stack.is_empty() && (max_pivots == None || count < max_pivots.unwrap()) {
       count += 1;
       let item = self.stack.last_mut().unwrap();
-      let (num, last) = item.next_num();
+      let Some((asgmt, last)) = item.next_asgmt() else {
+        // The pivot had no alternatives at all.
+        self.stack.pop();
+        continue;
+      };
       if last {
-        // For the final numeral, we modify the item's ledger in place, instead of
-        // copying.
+        // For the final alternative, we modify the item's ledger in place,
+        // instead of copying.
         let result = item
           .ledger
-          .assign_and_apply_implications(num, item.pivot_loc);
+          .assign_and_apply_implications(asgmt.num, asgmt.loc);
         if let Ok(doubles) = result {
           let pivoted = !item.ledger.is_complete();
           if pivoted {
             // We even reuse the item for the following pivot.
-            item.pivot_loc = self.helper.choose_pivot_loc(&item.ledger, &doubles);
-            item.fill_nums();
-            self
-              .helper
-              .order_pivot_nums(&mut item.nums[..item.count as usize]);
+            let pivot = self.helper.choose_pivot(&item.ledger, &doubles);
+            item.fill_asgmts(pivot, self.helper);
           } else {
-            self.found = Self::solution(&item.ledger);
+            complete = Some(item.ledger);
             self.stack.pop();
             break;
           }
@@ -217,21 +443,21 @@ impl<'a> Searcher<'a> {
           self.stack.pop();
         }
       } else {
-        // There are other numerals after this one, we must modify a copy of the item's
-        // ledger so they'll all start from the same place.
+        // There are other alternatives after this one, we must modify a copy of
+        // the item's ledger so they'll all start from the same place.
         let mut ledger = item.ledger;
-        let result = ledger.assign_and_apply_implications(num, item.pivot_loc);
+        let result = ledger.assign_and_apply_implications(asgmt.num, asgmt.loc);
         if let Ok(doubles) = result {
           let pivoted = self.pivot(&ledger, &doubles);
           if !pivoted {
-            self.found = Self::solution(&ledger);
+            complete = Some(ledger);
             break;
           }
         }
       }
     }
     self.total_pivots += count;
-    count
+    (count, complete)
   }
 
   fn new(ledger: &Option<Ledger>, doubles: &LocSet, helper: &'a mut dyn SearchHelper) -> Self {
@@ -252,26 +478,23 @@ impl<'a> Searcher<'a> {
     answer
   }
 
-  /// Chooses a pivot location and pushes it and its possible numerals onto
+  /// Chooses a pivot point and pushes it and its alternative assignments onto
   /// the stack; returns false if there are no remaining unset locations.
   fn pivot(&mut self, ledger: &Ledger, doubles: &LocSet) -> bool {
     if ledger.is_complete() {
       return false;
     }
 
+    let pivot = self.helper.choose_pivot(ledger, doubles);
     self.stack.push(StackItem {
       ledger: *ledger,
-      pivot_loc: self.helper.choose_pivot_loc(ledger, doubles),
-      nums: [None; 9],
+      asgmts: [None; 9],
       count: 0,
       next: 0,
     });
     self.max_depth = std::cmp::max(self.max_depth, self.stack.len() as _);
     let item = self.stack.last_mut().unwrap();
-    item.fill_nums();
-    self
-      .helper
-      .order_pivot_nums(&mut item.nums[..item.count as usize]);
+    item.fill_asgmts(pivot, self.helper);
     true
   }
 
@@ -285,25 +508,49 @@ impl<'a> Searcher<'a> {
 }
 
 impl StackItem {
-  /// Returns the next numeral to assign to this item's locations.
-  fn next_num(&mut self) -> (Num, bool) {
-    let num = self.nums[self.next as usize].unwrap();
+  /// Returns the next assignment to try for this item's pivot point, and
+  /// whether it's the last one, or None if the pivot point has no
+  /// alternatives.
+  fn next_asgmt(&mut self) -> Option<(Asgmt, bool)> {
+    let asgmt = self.asgmts.get(self.next as usize).copied().flatten()?;
     self.next += 1;
-    (num, self.next >= self.count)
+    Some((asgmt, self.next >= self.count))
   }
 
-  /// Finds all the numerals available for the pivot location and adds them to
-  /// `nums`.
-  fn fill_nums(&mut self) {
+  /// Finds all the alternatives available at the given pivot point, and adds
+  /// them to `asgmts` in the order the helper prefers.
+  fn fill_asgmts(&mut self, pivot: Pivot, helper: &mut dyn SearchHelper) {
     self.count = 0;
     self.next = 0;
-    for num in Num::all() {
-      if self.ledger.is_possible(num, self.pivot_loc) {
-        unsafe {
-          // Safe because there can't be more than 9 numerals in the `nums` array.
-          *self.nums.get_unchecked_mut(self.count as usize) = Some(num);
+    match pivot {
+      Pivot::Loc(loc) => {
+        let mut nums = [None; 9];
+        for num in Num::all() {
+          if self.ledger.is_possible(num, loc) {
+            unsafe {
+              // Safe because there can't be more than 9 numerals in the `nums` array.
+              *nums.get_unchecked_mut(self.count as usize) = Some(num);
+              self.count += 1;
+            }
+          }
+        }
+        helper.order_pivot_nums(&mut nums[..self.count as usize]);
+        for (asgmt, num) in self.asgmts.iter_mut().zip(nums) {
+          *asgmt = num.map(|num| Asgmt { num, loc });
+        }
+      }
+      Pivot::Unit(num, unit) => {
+        let mut locs = [None; 9];
+        let unit_locs = self.ledger.asgmts().num_locs(num) & unit.locs() & *self.ledger.unset();
+        for (i, loc) in unit_locs.iter().enumerate() {
+          // Units have 9 locations, so this stays within the array.
+          locs[i] = Some(loc);
           self.count += 1;
         }
+        helper.order_pivot_locs(&mut locs[..self.count as usize]);
+        for (asgmt, loc) in self.asgmts.iter_mut().zip(locs) {
+          *asgmt = loc.map(|loc| Asgmt { num, loc });
+        }
       }
     }
   }
@@ -333,11 +580,23 @@ mod tests {
               let summary = solve(&clues, MAX_SOLUTIONS, &mut helper);
               assert_eq!(max(0, $count), summary.solutions.len() as i32);
               assert_eq!(summary.too_many_solutions, $count > MAX_SOLUTIONS);
-              for s in summary.solutions {
+              for s in &summary.solutions {
                 let mut s = s.grid();
                 s.intersect(&clues);
                 assert_eq!(s, clues);
               }
+
+              // Branching on units finds the same solutions.
+              let mut helper = UnitPivotHelper();
+              let unit_summary = solve(&clues, MAX_SOLUTIONS, &mut helper);
+              assert_eq!(summary.too_many_solutions, unit_summary.too_many_solutions);
+              if !summary.too_many_solutions {
+                let mut expected = summary.solutions.clone();
+                let mut actual = unit_summary.solutions.clone();
+                expected.sort();
+                actual.sort();
+                assert_eq!(expected, actual);
+              }
           }
       }
     };
@@ -378,4 +637,200 @@ mod tests {
     ".....6....59.....82....8....45........3........6..3.54...325..6..................",
     MAX_SOLUTIONS + 1
   );
+
+  #[test]
+  fn test_solve_candidates() {
+    let clues = Grid::from_str(
+      ".3....91.8.6.....2...8.4...5.2..7..........7.9..4.65.....7.3...3.8.....1.97...8..",
+    )
+    .unwrap();
+    let summary = solve(&clues, MAX_SOLUTIONS, &mut DefaultHelper());
+    let mut candidates = CandidateGrid::from_grid(&clues);
+    let same = solve_candidates(&candidates, MAX_SOLUTIONS, &mut DefaultHelper());
+    assert_eq!(summary.solutions, same.solutions);
+
+    // Ruling out one of the first solution's assignments leaves just the
+    // solutions without it.
+    let first = summary.solutions[0].grid();
+    let asgmt = first
+      .iter()
+      .find(|&a| {
+        summary
+          .solutions
+          .iter()
+          .any(|s| s.grid()[a.loc] != Some(a.num))
+      })
+      .unwrap();
+    candidates.remove(asgmt);
+    let fewer = solve_candidates(&candidates, MAX_SOLUTIONS, &mut DefaultHelper());
+    let expected: Vec<_> = summary
+      .solutions
+      .iter()
+      .filter(|s| s.grid()[asgmt.loc] != Some(asgmt.num))
+      .collect();
+    assert_eq!(expected.len(), fewer.solutions.len());
+    assert!(!fewer.too_many_solutions);
+    for s in &fewer.solutions {
+      assert!(expected.contains(&s));
+    }
+
+    // A location without candidates means there are no solutions.
+    for num in Num::all() {
+      candidates.remove(Asgmt::new(num, L11));
+    }
+    assert!(
+      solve_candidates(&candidates, MAX_SOLUTIONS, &mut DefaultHelper())
+        .solutions
+        .is_empty()
+    );
+  }
+
+  #[test]
+  fn test_solutions_iterator() {
+    let clues = Grid::from_str(
+      ".3....91.8.6.....2...8.4...5.2..7..........7.9..4.65.....7.3...3.8.....1.97...8..",
+    )
+    .unwrap();
+    let all: Vec<SolvedGrid> = solutions(&clues, &mut DefaultHelper()).collect();
+    assert_eq!(9, all.len());
+    let summary = solve(&clues, MAX_SOLUTIONS, &mut DefaultHelper());
+    assert_eq!(summary.solutions, all);
+    for s in all {
+      let mut s = s.grid();
+      s.intersect(&clues);
+      assert_eq!(s, clues);
+    }
+  }
+
+  #[test]
+  fn test_solutions_iterator_no_pivots() {
+    let clues = Grid::from_str(
+      ".9..74....2....6.375...........9..545.3.4.......58.....45....8....1.2.3.......92.",
+    )
+    .unwrap();
+    assert_eq!(1, solutions(&clues, &mut DefaultHelper()).count());
+  }
+
+  #[test]
+  fn test_count_solutions() {
+    let multiple = Grid::from_str(
+      ".3....91.8.6.....2...8.4...5.2..7..........7.9..4.65.....7.3...3.8.....1.97...8..",
+    )
+    .unwrap();
+    assert_eq!(9, count_solutions(&multiple, 100));
+    assert_eq!(5, count_solutions(&multiple, 5));
+
+    let broken = Grid::from_str(
+      "...8.9..6.23.........6.8...7....1..2...45...9......6......7......1.46.....3......",
+    )
+    .unwrap();
+    assert_eq!(0, count_solutions(&broken, 100));
+
+    let unique = Grid::from_str(
+      ".9..74....2....6.375...........9..545.3.4.......58.....45....8....1.2.3.......92.",
+    )
+    .unwrap();
+    assert_eq!(1, count_solutions(&unique, 100));
+    assert_eq!(0, count_solutions(&unique, 0));
+
+    let many = Grid::from_str(
+      ".....6....59.....82....8....45........3........6..3.54...325..6..................",
+    )
+    .unwrap();
+    assert_eq!(100_000, count_solutions(&many, 100_000));
+  }
+
+  /// Always branches on the first unit that has more than one location for a
+  /// numeral.
+  struct AlwaysUnitHelper(i32);
+
+  impl SearchHelper for AlwaysUnitHelper {
+    fn choose_pivot_loc(&mut self, ledger: &Ledger, doubles: &LocSet) -> Loc {
+      DefaultHelper().choose_pivot_loc(ledger, doubles)
+    }
+
+    fn choose_pivot(&mut self, ledger: &Ledger, _doubles: &LocSet) -> Pivot {
+      self.0 += 1;
+      for unit in Unit::all() {
+        for num in Num::all() {
+          let locs = ledger.asgmts().num_locs(num) & unit.locs() & *ledger.unset();
+          if locs.len() > 1 {
+            return Pivot::Unit(num, unit);
+          }
+        }
+      }
+      panic!("no unit pivot available");
+    }
+
+    fn order_pivot_nums(&mut self, _nums: &mut [Option<Num>]) {}
+
+    fn order_pivot_locs(&mut self, locs: &mut [Option<Loc>]) {
+      locs.reverse();
+    }
+  }
+
+  /// Always branches on where the given numeral goes in the first row, even
+  /// when it's already there.
+  struct FirstRowHelper(Num);
+
+  impl SearchHelper for FirstRowHelper {
+    fn choose_pivot_loc(&mut self, ledger: &Ledger, doubles: &LocSet) -> Loc {
+      DefaultHelper().choose_pivot_loc(ledger, doubles)
+    }
+
+    fn choose_pivot(&mut self, _ledger: &Ledger, _doubles: &LocSet) -> Pivot {
+      Pivot::Unit(self.0, R1.to_unit())
+    }
+
+    fn order_pivot_nums(&mut self, _nums: &mut [Option<Num>]) {}
+
+    fn order_pivot_locs(&mut self, _locs: &mut [Option<Loc>]) {}
+  }
+
+  #[test]
+  fn test_pivots_with_few_alternatives() {
+    let clues = Grid::from_str(
+      ".3....91.8.6.....2...8.4...5.2..7..........7.9..4.65.....7.3...3.8.....1.97...8..",
+    )
+    .unwrap();
+    // 3 is already in the first row, so there's nowhere left for it there:
+    // the search backs out instead of failing.
+    let summary = solve(&clues, MAX_SOLUTIONS, &mut FirstRowHelper(N3));
+    assert!(summary.solutions.is_empty());
+    assert!(!summary.too_many_solutions);
+
+    // A unit with a single place for a numeral yields just that assignment.
+    let ledger = Ledger::new(&clues).unwrap();
+    let (num, unit, loc) = Unit::all()
+      .flat_map(|unit| Num::all().map(move |num| (num, unit)))
+      .find_map(|(num, unit)| {
+        let locs = ledger.asgmts().num_locs(num) & unit.locs() & *ledger.unset();
+        (locs.len() == 1).then(|| (num, unit, locs.smallest_item().unwrap()))
+      })
+      .unwrap();
+    let mut item = StackItem {
+      ledger,
+      asgmts: [None; 9],
+      count: 0,
+      next: 0,
+    };
+    item.fill_asgmts(Pivot::Unit(num, unit), &mut DefaultHelper());
+    assert_eq!(Some((Asgmt::new(num, loc), true)), item.next_asgmt());
+    assert_eq!(None, item.next_asgmt());
+  }
+
+  #[test]
+  fn test_unit_pivots() {
+    let clues = Grid::from_str(
+      ".3....91.8.6.....2...8.4...5.2..7..........7.9..4.65.....7.3...3.8.....1.97...8..",
+    )
+    .unwrap();
+    let mut expected = solve(&clues, MAX_SOLUTIONS, &mut DefaultHelper()).solutions;
+    let mut helper = AlwaysUnitHelper(0);
+    let mut actual = solve(&clues, MAX_SOLUTIONS, &mut helper).solutions;
+    assert!(helper.0 > 0);
+    expected.sort();
+    actual.sort();
+    assert_eq!(expected, actual);
+  }
 }
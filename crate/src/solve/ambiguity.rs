@@ -0,0 +1,208 @@
+//! Analyzes where and how the solutions to an improper puzzle differ.
+
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::core::*;
+
+/// Describes the ambiguity of a puzzle with more than one solution.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, TS)]
+#[ts(export, export_to = "../../www/src/facts/")]
+#[serde(rename_all = "camelCase")]
+pub struct Ambiguity {
+  /// The locations whose numerals are the same in every solution.
+  pub backbone: LocSet,
+  /// The locations whose numerals differ among the solutions, grouped into
+  /// holes that can be filled independently of each other.  Every combination
+  /// of the holes' alternatives is a solution.
+  pub holes: Vec<Hole>,
+}
+
+/// A group of locations whose numerals vary together among a puzzle's
+/// solutions.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, TS)]
+#[ts(export, export_to = "../../www/src/facts/")]
+#[serde(rename_all = "camelCase")]
+pub struct Hole {
+  /// The locations in the hole.
+  pub locs: LocSet,
+  /// The distinct ways the solutions fill the hole.  Each alternative lists the
+  /// numerals of the hole's locations, in location order.
+  pub alternatives: Vec<Vec<Num>>,
+}
+
+impl Ambiguity {
+  /// Analyzes the given solutions, which must be distinct solutions to the same
+  /// puzzle.  When `solutions` is empty, the backbone and the holes are both
+  /// empty.
+  pub fn new(solutions: &[SolvedGrid]) -> Self {
+    let mut backbone = LocSet::new();
+    let mut varying = Vec::new();
+    if let Some(first) = solutions.first() {
+      for loc in Loc::all() {
+        if solutions.iter().all(|s| s[loc] == first[loc]) {
+          backbone.insert(loc);
+        } else {
+          varying.push(loc);
+        }
+      }
+    }
+
+    // Start with each varying location in its own group, and merge any two
+    // groups that don't vary independently of each other.
+    let mut groups: Vec<LocSet> = varying.into_iter().map(LocSet::singleton).collect();
+    'merging: loop {
+      for i in 0..groups.len() {
+        for j in i + 1..groups.len() {
+          let a = count_alternatives(solutions, &groups[i]);
+          let b = count_alternatives(solutions, &groups[j]);
+          if count_alternatives(solutions, &(groups[i] | groups[j])) != a * b {
+            let g = groups.remove(j);
+            groups[i] |= g;
+            continue 'merging;
+          }
+        }
+      }
+      break;
+    }
+    // Groups that are pairwise independent can still depend on each other as a
+    // whole.  That's unusual enough that we just lump them all together.
+    let product: usize = groups
+      .iter()
+      .map(|g| count_alternatives(solutions, g))
+      .product();
+    if groups.len() > 1 && product != solutions.len() {
+      let all = groups.iter().fold(LocSet::new(), |acc, g| acc | *g);
+      groups = vec![all];
+    }
+
+    let holes = groups
+      .into_iter()
+      .map(|locs| Hole {
+        locs,
+        alternatives: alternatives(solutions, &locs).into_iter().collect(),
+      })
+      .collect();
+    Ambiguity { backbone, holes }
+  }
+}
+
+/// Returns the distinct ways the given solutions fill the given locations.
+fn alternatives(solutions: &[SolvedGrid], locs: &LocSet) -> BTreeSet<Vec<Num>> {
+  solutions
+    .iter()
+    .map(|s| locs.iter().map(|loc| s[loc]).collect())
+    .collect()
+}
+
+fn count_alternatives(solutions: &[SolvedGrid], locs: &LocSet) -> usize {
+  alternatives(solutions, locs).len()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loc_set;
+  use crate::solve::*;
+  use std::str::FromStr;
+
+  /// A solved grid with (at least) two unavoidable rectangles, at `first()`
+  /// and `second()`.
+  const PATTERN: &str = "
+    215 438 679
+    369 271 845
+    478 695 321
+    824 967 153
+    531 824 967
+    796 513 482
+    987 342 516
+    143 756 298
+    652 189 734";
+
+  fn first() -> LocSet {
+    loc_set! {L13, L19, L23, L29}
+  }
+
+  fn second() -> LocSet {
+    loc_set! {L55, L56, L75, L76}
+  }
+
+  fn solve_with_holes(holes: &[LocSet]) -> Vec<SolvedGrid> {
+    let mut grid = Grid::from_str(PATTERN).unwrap();
+    for hole in holes {
+      for loc in hole.iter() {
+        grid[loc] = None;
+      }
+    }
+    solve(&grid, 100, &mut DefaultHelper()).solutions
+  }
+
+  #[test]
+  fn test_unique() {
+    let solutions = solve_with_holes(&[loc_set! {L11}]);
+    assert_eq!(1, solutions.len());
+    let ambiguity = Ambiguity::new(&solutions);
+    assert_eq!(ambiguity.backbone, LocSet::all());
+    assert!(ambiguity.holes.is_empty());
+  }
+
+  #[test]
+  fn test_no_solutions() {
+    let ambiguity = Ambiguity::new(&[]);
+    assert!(ambiguity.backbone.is_empty());
+    assert!(ambiguity.holes.is_empty());
+  }
+
+  #[test]
+  fn test_single_hole() {
+    // 5 and 9 swap places in the corners of this rectangle.
+    let rectangle = first();
+    let solutions = solve_with_holes(&[rectangle]);
+    assert_eq!(2, solutions.len());
+
+    let ambiguity = Ambiguity::new(&solutions);
+    assert_eq!(ambiguity.backbone, !rectangle);
+    assert_eq!(
+      ambiguity.holes,
+      vec![Hole {
+        locs: rectangle,
+        alternatives: vec![vec![N5, N9, N9, N5], vec![N9, N5, N5, N9]],
+      }]
+    );
+  }
+
+  #[test]
+  fn test_independent_holes() {
+    let first = first();
+    let second = second();
+    let solutions = solve_with_holes(&[first, second]);
+    assert_eq!(4, solutions.len());
+
+    let ambiguity = Ambiguity::new(&solutions);
+    assert_eq!(ambiguity.backbone, !(first | second));
+    assert_eq!(2, ambiguity.holes.len());
+    assert_eq!(ambiguity.holes[0].locs, first);
+    assert_eq!(ambiguity.holes[1].locs, second);
+    for hole in &ambiguity.holes {
+      assert_eq!(2, hole.alternatives.len());
+    }
+  }
+
+  #[test]
+  fn test_dependent_locs_share_a_hole() {
+    // Only two of the four possible combinations of these two rectangles'
+    // alternatives are included, so they form a single hole.
+    let first = first();
+    let second = second();
+    let all = solve_with_holes(&[first, second]);
+    let solutions = vec![all[0], all[3]];
+
+    let ambiguity = Ambiguity::new(&solutions);
+    assert_eq!(ambiguity.backbone, !(first | second));
+    assert_eq!(1, ambiguity.holes.len());
+    assert_eq!(ambiguity.holes[0].locs, first | second);
+    assert_eq!(2, ambiguity.holes[0].alternatives.len());
+  }
+}
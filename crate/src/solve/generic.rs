@@ -0,0 +1,352 @@
+//! Defines a general-purpose solver for grids whose units aren't (just) the
+//! standard rows, columns and blocks, such as Jigsaw Sudoku's, or that have
+//! rules beyond units, such as Killer Sudoku's cages.  It's much
+//! slower than the row-band-based search, so puzzles with the standard units
+//! should be solved with that instead.
+//!
+//! Its solutions are full grids that follow its units, which makes them
+//! Sudoku solutions only when its units include the standard ones.
+
+use crate::core::*;
+use crate::random::Random;
+
+use super::search::Search;
+use super::SolutionSummary;
+
+/// Solves puzzles whose every unit must contain each numeral exactly once.
+pub struct UnitsSolver {
+  /// The units, each of which must have 9 locations.
+  units: Vec<LocSet>,
+  /// Each location's peers: the other locations that share a unit with it.
+  peers: [LocSet; 81],
+  /// Cages whose sums further constrain the solutions.
+  cages: Vec<Cage>,
+}
+
+impl UnitsSolver {
+  /// Makes a solver for the given units.
+  pub fn new(units: impl IntoIterator<Item = LocSet>) -> Self {
+    let units: Vec<LocSet> = units.into_iter().collect();
+    debug_assert!(units.iter().all(|unit| unit.len() == 9));
+    let mut peers = [LocSet::new(); 81];
+    for unit in &units {
+      for loc in unit.iter() {
+        peers[loc.index()] |= *unit;
+      }
+    }
+    for loc in Loc::all() {
+      peers[loc.index()].remove(loc);
+    }
+    Self {
+      units,
+      peers,
+      cages: Vec::new(),
+    }
+  }
+
+  /// Adds the given cages' sums to the rules.  A cage whose numerals must be
+  /// distinct also makes its locations peers.
+  pub fn with_cages(mut self, cages: &Cages) -> Self {
+    for cage in cages.iter() {
+      if cage.distinct {
+        for loc in cage.locs.iter() {
+          self.peers[loc.index()] |= cage.locs - loc.as_set();
+        }
+      }
+      self.cages.push(*cage);
+    }
+    self
+  }
+
+  /// Makes a solver for the given Jigsaw layout.
+  pub fn for_regions(regions: &Regions) -> Self {
+    Self::new(regions.units())
+  }
+
+  /// Solves the given puzzle, finding at most one more than `max_solutions`
+  /// solutions.
+  pub fn solve(&self, clues: &Grid, max_solutions: i32) -> SolutionSummary<Grid> {
+    let mut search = Search::counting(max_solutions);
+    if let Some(state) = self.start(clues) {
+      self.search(state, &mut search);
+    }
+    SolutionSummary {
+      clues: *clues,
+      too_many_solutions: search.is_full(),
+      solutions: search.solutions,
+    }
+  }
+
+  /// Finds a solution to the given puzzle, trying the numerals at each pivot
+  /// in random order.  Gives up after `max_pivots` pivots, or when there is
+  /// no solution.
+  pub fn random_solution(
+    &self,
+    clues: &Grid,
+    random: &mut Random,
+    max_pivots: usize,
+  ) -> Option<Grid> {
+    let mut search = Search::random(random, max_pivots);
+    self.search(self.start(clues)?, &mut search);
+    search.solutions.pop()
+  }
+
+  /// Makes the search state for the given clues, or returns None if they
+  /// break the rules.
+  fn start(&self, clues: &Grid) -> Option<State> {
+    let mut state = State {
+      grid: Grid::new(),
+      candidates: [NumSet::all(); 81],
+      num_locs: [LocSet::all(); 9],
+      unset: LocSet::all(),
+    };
+    for asgmt in clues.iter() {
+      if !state.candidates[asgmt.loc.index()].contains(asgmt.num) {
+        return None;
+      }
+      self.assign(&mut state, asgmt);
+    }
+    self.propagate(&mut state).then_some(state)
+  }
+
+  /// Assigns the given numeral to the given location, and rules it out of the
+  /// location's peers.
+  fn assign(&self, state: &mut State, asgmt: Asgmt) {
+    let Asgmt { num, loc } = asgmt;
+    state.grid[loc] = Some(num);
+    state.unset.remove(loc);
+    for other in state.candidates[loc.index()].iter() {
+      state.num_locs[other.index()].remove(loc);
+    }
+    state.candidates[loc.index()] = NumSet::new();
+    let peers = self.peers[loc.index()] & state.num_locs[num.index()];
+    for peer in peers.iter() {
+      state.candidates[peer.index()].remove(num);
+    }
+    state.num_locs[num.index()] -= peers;
+  }
+
+  /// Makes all the naked and hidden single assignments it can, and rules out
+  /// the numerals that can't add up to their cages' sums, returning false if
+  /// it finds that the state is invalid.
+  fn propagate(&self, state: &mut State) -> bool {
+    loop {
+      let mut changed = false;
+      let unset = state.unset;
+      for loc in unset.iter() {
+        let candidates = state.candidates[loc.index()];
+        match candidates.len() {
+          0 => return false,
+          1 => {
+            let num = candidates.smallest_item().unwrap();
+            self.assign(state, Asgmt { num, loc });
+            changed = true;
+          }
+          _ => {}
+        }
+      }
+      for unit in &self.units {
+        for num in Num::all() {
+          let locs = state.num_locs[num.index()] & *unit;
+          if locs.len() == 1 {
+            let loc = locs.smallest_item().unwrap();
+            self.assign(state, Asgmt { num, loc });
+            changed = true;
+          } else if locs.is_empty() && !unit.iter().any(|loc| state.grid[loc] == Some(num)) {
+            return false;
+          }
+        }
+      }
+      for cage in &self.cages {
+        match self.prune_cage(state, cage) {
+          None => return false,
+          Some(pruned) => changed |= pruned,
+        }
+      }
+      if !changed {
+        return true;
+      }
+    }
+  }
+
+  /// Removes the candidates of the given cage's unset locations that can't be
+  /// part of its sum.  Returns whether it removed any, or None if the cage
+  /// can't add up.
+  fn prune_cage(&self, state: &mut State, cage: &Cage) -> Option<bool> {
+    let locs: Vec<Loc> = cage.locs.iter().collect();
+    let candidates: Vec<NumSet> = locs
+      .iter()
+      .map(|loc| match state.grid[*loc] {
+        Some(num) => num.as_set(),
+        None => state.candidates[loc.index()],
+      })
+      .collect();
+    let narrowed = sum_candidates(&candidates, cage.sum, cage.distinct);
+    let mut pruned = false;
+    for (i, loc) in locs.into_iter().enumerate() {
+      if narrowed[i].is_empty() {
+        return None;
+      }
+      if narrowed[i] != candidates[i] {
+        for num in (candidates[i] - narrowed[i]).iter() {
+          state.num_locs[num.index()].remove(loc);
+        }
+        state.candidates[loc.index()] = narrowed[i];
+        pruned = true;
+      }
+    }
+    Some(pruned)
+  }
+
+  /// Searches depth-first from the given state, pivoting on the location with
+  /// the fewest candidates.
+  fn search(&self, state: State, search: &mut Search<Grid>) {
+    let Some(loc) = state
+      .unset
+      .iter()
+      .min_by_key(|loc| state.candidates[loc.index()].len())
+    else {
+      search.solutions.push(state.grid);
+      return;
+    };
+    let mut nums: Vec<Num> = state.candidates[loc.index()].iter().collect();
+    search.order(&mut nums);
+    for num in nums {
+      if !search.take_pivot() {
+        return;
+      }
+      let mut next = state;
+      self.assign(&mut next, Asgmt { num, loc });
+      if self.propagate(&mut next) {
+        self.search(next, search);
+      }
+    }
+  }
+}
+
+/// The state of a search: the assignments made so far, and the possibilities
+/// that remain.
+#[derive(Clone, Copy)]
+struct State {
+  grid: Grid,
+  /// The possible numerals for each unset location.
+  candidates: [NumSet; 81],
+  /// The unset locations where each numeral is possible.
+  num_locs: [LocSet; 9],
+  unset: LocSet,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::random::new_random;
+  use crate::solve::{solve, DefaultHelper};
+  use std::str::FromStr;
+
+  #[test]
+  fn test_matches_standard_solver() {
+    let solver = UnitsSolver::for_regions(&Regions::standard());
+    for (clues, count) in [
+      (
+        ".3....91.8.6.....2...8.4...5.2..7..........7.9..4.65.....7.3...3.8.....1.97...8..",
+        9,
+      ),
+      (
+        ".6.5.4.3.1...9...8.........9...5...6.4.6.2.7.7...4...5.........4...8...1.5.2.3.4.",
+        1,
+      ),
+      (
+        "1....6....59.....82....8....45...3....3...7....6..3.54...325..6........17389.....",
+        0,
+      ),
+    ] {
+      let clues = Grid::from_str(clues).unwrap();
+      let mut expected: Vec<Grid> = solve(&clues, 12, &mut DefaultHelper())
+        .solutions
+        .iter()
+        .map(SolvedGrid::grid)
+        .collect();
+      let mut actual = solver.solve(&clues, 12).solutions;
+      expected.sort();
+      actual.sort();
+      assert_eq!(count, actual.len());
+      assert_eq!(expected, actual);
+    }
+  }
+
+  #[test]
+  fn test_too_many_solutions() {
+    let solver = UnitsSolver::for_regions(&TRADED_REGIONS.parse().unwrap());
+    let summary = solver.solve(&Grid::new(), 3);
+    assert!(summary.too_many_solutions);
+    assert_eq!(4, summary.solutions.len());
+  }
+
+  #[test]
+  fn test_random_solution() {
+    let regions: Regions = TRADED_REGIONS.parse().unwrap();
+    let solver = UnitsSolver::for_regions(&regions);
+    let mut random = new_random("jigsaw");
+    let solution = solver
+      .random_solution(&Grid::new(), &mut random, 1000)
+      .unwrap();
+    for unit in regions.units() {
+      let nums: NumSet = unit.iter().map(|loc| solution[loc].unwrap()).collect();
+      assert_eq!(NumSet::all(), nums);
+    }
+    assert_ne!(
+      solution,
+      solver
+        .random_solution(&Grid::new(), &mut random, 1000)
+        .unwrap()
+    );
+  }
+
+  #[test]
+  fn test_cages() {
+    let solver = UnitsSolver::for_regions(&Regions::standard());
+    let mut random = new_random("killer");
+    let solution = solver
+      .random_solution(&Grid::new(), &mut random, 1000)
+      .and_then(|grid| grid.solved_grid())
+      .unwrap();
+    // Each block's rows are cages, with every other location as a clue.
+    let cages = Cages::new(
+      Loc::all()
+        .filter(|loc| loc.col().index() % 3 == 0)
+        .map(|loc| {
+          let locs = loc.row().locs() & loc.blk().locs();
+          Cage::from_solution(locs, &solution)
+        })
+        .collect(),
+    )
+    .unwrap();
+    let mut clues = solution.grid();
+    for loc in Loc::all().filter(|loc| loc.index() % 2 != 0) {
+      clues[loc] = None;
+    }
+    // The cages rule out exactly the standard solutions that break them.
+    let standard = solve(&clues, 10_000, &mut DefaultHelper());
+    assert!(!standard.too_many_solutions);
+    let mut expected: Vec<Grid> = standard
+      .solutions
+      .iter()
+      .filter(|s| cages.are_satisfied_by(s))
+      .map(SolvedGrid::grid)
+      .collect();
+    let mut actual = solver.with_cages(&cages).solve(&clues, 10_000).solutions;
+    expected.sort();
+    actual.sort();
+    assert!(actual.contains(&solution.grid()));
+    assert_eq!(expected, actual);
+
+    // A wrong sum has no solutions.
+    let mut wrong: Vec<Cage> = cages.iter().copied().collect();
+    wrong[0].sum = if wrong[0].sum == 6 { 7 } else { 6 };
+    let solver = UnitsSolver::for_regions(&Regions::standard());
+    let summary = solver
+      .with_cages(&Cages::new(wrong).unwrap())
+      .solve(&solution.grid(), 1);
+    assert!(summary.solutions.is_empty());
+  }
+}
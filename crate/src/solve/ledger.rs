@@ -2,6 +2,8 @@
 
 use crate::core::bits::*;
 use crate::core::masks::*;
+#[cfg(feature = "simd")]
+use crate::core::simd::diff_mask;
 use crate::core::*;
 
 /// Tracks possible Sudoku assignments during solving.
@@ -15,21 +17,45 @@ pub struct Ledger {
 
   /// The locations that have not yet been assigned a numeral.
   unset: LocSet,
+
+  /// The rules being solved under.  The row-band machinery handles the
+  /// standard units; a variant's extra units get a slower pass of their own.
+  variant: Variant,
 }
 
 impl Ledger {
   /// Makes a preliminary Ledger with the given puzzle's assignments set but
-  /// all other possibilities still open.  Returns `Err(Invalid)` if the
+  /// all other possibilities still open.  Returns an `Invalid` error if the
   /// puzzle's clues are inconsistent with the rules of Sudoku.
   pub fn new(clues: &Grid) -> Result<Ledger, Invalid> {
+    Self::new_for_variant(clues, Variant::Standard)
+  }
+
+  /// Like `new`, but for a puzzle following the given variant's rules.  Only
+  /// the standard units are checked here; the first call to
+  /// `apply_implications` checks the variant's extra units.
+  pub fn new_for_variant(clues: &Grid, variant: Variant) -> Result<Ledger, Invalid> {
     let asgmts = AsgmtSet::valid_possibles_from_grid(clues)?;
     Ok(Self {
       asgmts,
       old_asgmts: AsgmtSet::all(),
       unset: LocSet::all(),
+      variant,
     })
   }
 
+  /// Makes a preliminary Ledger with the given candidates as its possible
+  /// assignments.  Unlike `new`, this doesn't check validity: the first call
+  /// to `apply_implications` does that.
+  pub fn from_candidates(candidates: &CandidateGrid) -> Ledger {
+    Self {
+      asgmts: candidates.asgmts(),
+      old_asgmts: AsgmtSet::all(),
+      unset: LocSet::all(),
+      variant: Variant::Standard,
+    }
+  }
+
   /// Renders the possible assignments as a grid, leaving unassigned any
   /// locations that don't have a unique possible numeral.
   pub fn to_grid(&self) -> Grid {
@@ -57,11 +83,68 @@ impl Ledger {
     &self.unset
   }
 
+  /// The variant whose rules this ledger follows.
+  pub fn variant(&self) -> Variant {
+    self.variant
+  }
+
+  /// The number of bytes in a ledger's serialized form.
+  pub const BYTES: usize = 4 * (2 * 9 * 3 + 3) + 1;
+
+  /// Appends this ledger's serialized form, `Ledger::BYTES` long, to the given
+  /// vector.
+  pub fn write_bytes(&self, out: &mut Vec<u8>) {
+    for set in [&self.asgmts.0, &self.old_asgmts.0] {
+      for plane in set.array() {
+        write_bits3x27(plane, out);
+      }
+    }
+    write_bits3x27(&self.unset.0, out);
+    out.push(self.variant as u8);
+  }
+
+  /// Reads a ledger from the first `Ledger::BYTES` bytes of the given slice, as
+  /// written by `write_bytes`.  Returns None if there aren't enough bytes or if
+  /// they aren't a valid serialized ledger.
+  pub fn read_bytes(bytes: &[u8]) -> Option<Ledger> {
+    if bytes.len() < Self::BYTES {
+      return None;
+    }
+    let mut chunks = bytes[..Self::BYTES - 1].chunks_exact(3 * 4);
+    let mut read_set = || -> Option<AsgmtSet> {
+      let mut planes = [Bits3x27::ZERO; 9];
+      for plane in planes.iter_mut() {
+        *plane = read_bits3x27(chunks.next()?)?;
+      }
+      Some(AsgmtSet(Bits9x3x27::new(planes)))
+    };
+    let asgmts = read_set()?;
+    let old_asgmts = read_set()?;
+    let unset = LocSet(read_bits3x27(chunks.next()?)?);
+    let variant = *Variant::ALL.get(bytes[Self::BYTES - 1] as usize)?;
+    Some(Self {
+      asgmts,
+      old_asgmts,
+      unset,
+      variant,
+    })
+  }
+
   /// Cycles through the ledger eliminating impossible assignments and
   /// assigning locations with just one possible numeral until there's nothing
   /// left to apply.  Returns an error if it's an invalid Sudoku, or a set of
   /// locations that have just two possible assignments.
   pub fn apply_implications(&mut self) -> Result<LocSet, Invalid> {
+    loop {
+      let doubles = self.apply_standard_implications()?;
+      if self.variant.is_standard() || !self.apply_variant_implications()? {
+        return Ok(doubles);
+      }
+    }
+  }
+
+  /// The part of `apply_implications` that covers the standard units.
+  fn apply_standard_implications(&mut self) -> Result<LocSet, Invalid> {
     loop {
       self.eliminate_by_overlaps()?;
       let (mut singles, doubles) = self.asgmts.valid_singles_and_doubles()?;
@@ -73,6 +156,69 @@ impl Ledger {
     }
   }
 
+  /// The part of `apply_implications` that covers the variant's extra units
+  /// and relations: eliminates each assigned numeral from the rest of its
+  /// extra units, assigns hidden singles within them, and eliminates whatever
+  /// all of a location's possible numerals exclude at its related locations.
+  /// Returns whether it changed anything, in which case the standard
+  /// implications must be reapplied.
+  fn apply_variant_implications(&mut self) -> Result<bool, Invalid> {
+    let mut changed = false;
+    for unit in self.variant.extra_units() {
+      let unit_locs = unit.locs();
+      for num in Num::all() {
+        let locs = self.asgmts.num_locs(num) & unit_locs;
+        let assigned = locs - self.unset;
+        match assigned.len() {
+          0 => {
+            if locs.is_empty() {
+              return Err(Invalid::Unit(*unit, num));
+            }
+            if locs.len() == 1 {
+              self.assign_blindly(num, locs.smallest_item().unwrap());
+              changed = true;
+            }
+          }
+          1 => {
+            if locs.len() > 1 {
+              self.asgmts.intersect_in_place(num, !(locs - assigned));
+              changed = true;
+            }
+          }
+          _ => return Err(Invalid::Unit(*unit, num)),
+        }
+      }
+    }
+    for &relation in self.variant.extra_relations() {
+      let mut nonempty = LocSet::new();
+      for num in Num::all() {
+        nonempty |= self.asgmts.num_locs(num);
+      }
+      for num in Num::all() {
+        // The locations whose every possible numeral excludes this one from
+        // their related locations.  (Relations are symmetric, so those are
+        // the numerals this one excludes.)
+        let excluding = relation.excluded_nums(num);
+        let mut excluders = nonempty;
+        for other in Num::all() {
+          if !excluding.contains(other) {
+            excluders -= self.asgmts.num_locs(other);
+          }
+        }
+        let mut excluded = LocSet::new();
+        for loc in excluders.iter() {
+          excluded |= relation.locs(loc);
+        }
+        let locs = self.asgmts.num_locs(num) & excluded;
+        if !locs.is_empty() {
+          self.asgmts.intersect_in_place(num, !locs);
+          changed = true;
+        }
+      }
+    }
+    Ok(changed)
+  }
+
   /// Assigns the given numeral to the given location, without following any
   /// implications of that assignment.  You'll need to call
   /// `apply_implications()` after this.
@@ -103,6 +249,7 @@ impl Ledger {
   /// or line are confined to where a block and line overlap, and therefore
   /// the numeral can't appear anywhere else in the other unit.  Overlaps are
   /// sometimes referred to as "locked candidates."
+  #[cfg(not(feature = "simd"))]
   fn eliminate_by_overlaps(&mut self) -> Result<(), Invalid> {
     let mut keep_going = true;
     while keep_going {
@@ -119,6 +266,39 @@ impl Ledger {
     Ok(())
   }
 
+  /// The vectorized version of `eliminate_by_overlaps`: finds all the changed
+  /// bands at once rather than one at a time, while still visiting them in the
+  /// same order as the scalar version.
+  #[cfg(feature = "simd")]
+  fn eliminate_by_overlaps(&mut self) -> Result<(), Invalid> {
+    loop {
+      let mut keep_going = false;
+      // The index of the first numeral-band word we haven't yet looked at in
+      // this pass.
+      let mut start = 0;
+      while start < 27 {
+        let changed = diff_mask(self.asgmts.0.words(), self.old_asgmts.0.words()) >> start;
+        if changed == 0 {
+          break;
+        }
+        let i = start + changed.trailing_zeros() as usize;
+        keep_going = true;
+        // Safe because `i` is in 0..27.
+        let (num, band) = unsafe {
+          (
+            Num::new_unchecked((i / 3) as i8 + 1),
+            Band::new_unchecked((i % 3) as i8),
+          )
+        };
+        self.eliminate_by_overlaps_in_band(num, band)?;
+        start = i + 1;
+      }
+      if !keep_going {
+        return Ok(());
+      }
+    }
+  }
+
   /// Eliminates (most of) the given locations' peers from the possible
   /// assignments.  Each given location must have a single possible numeral
   /// remaining.
@@ -147,7 +327,7 @@ impl Ledger {
     let row_masks = blk_rows_to_masks(blk_rows);
     let band_locs = band_locs & row_masks.zeroed_band_locs;
     if band_locs == Bits27::ZERO {
-      return Err(Invalid);
+      return Err(Invalid::Band(band, num));
     }
     *self.old_asgmts.band_locs_mut(num, band) = band_locs;
     *self.asgmts.band_locs_mut(num, band) = band_locs;
@@ -192,6 +372,24 @@ fn eliminate_peers_in_plane(plane: &mut Bits3x27, loc: Loc) {
   }
 }
 
+fn write_bits3x27(bits: &Bits3x27, out: &mut Vec<u8>) {
+  for band in bits.array() {
+    out.extend_from_slice(&band.backing_int().to_le_bytes());
+  }
+}
+
+fn read_bits3x27(bytes: &[u8]) -> Option<Bits3x27> {
+  let mut bands = [Bits27::ZERO; 3];
+  for (band, chunk) in bands.iter_mut().zip(bytes.chunks_exact(4)) {
+    let int = u32::from_le_bytes(chunk.try_into().ok()?);
+    *band = Bits27::from_backing_int(int);
+    if band.backing_int() != int {
+      return None;
+    }
+  }
+  Some(Bits3x27::new(bands))
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -245,4 +443,84 @@ mod tests {
     assert!(!ledger.is_complete());
     assert_eq!(N6, ledger.to_grid()[L63].unwrap());
   }
+
+  #[test]
+  fn test_ledger_x_variant() {
+    let g = Grid::from_str(
+      r"
+            1 . . | . . . | . . .
+            . 2 . | . . . | . . .
+            . . 3 | . . . | . . .
+            - - - + - - - + - - -
+            . . . | 4 . . | . . .
+            . . . | . 5 . | . . .
+            . . . | . . 6 | . . .
+            - - - + - - - + - - -
+            . . . | . . . | 7 . .
+            . . . | . . . | . 8 .
+            . . . | . . . | . . .",
+    )
+    .unwrap();
+    let mut ledger = Ledger::new(&g).unwrap();
+    ledger.apply_implications().unwrap();
+    assert_eq!(None, ledger.to_grid()[L99]);
+
+    // The main diagonal is missing only a 9.
+    let mut ledger = Ledger::new_for_variant(&g, Variant::X).unwrap();
+    ledger.apply_implications().unwrap();
+    assert_eq!(Some(N9), ledger.to_grid()[L99]);
+    assert!(!ledger.is_possible(N5, L19));
+
+    // And it can't have two 1s.
+    let mut g = g;
+    g[L99] = Some(N1);
+    let mut ledger = Ledger::new(&g).unwrap();
+    assert!(ledger.apply_implications().is_ok());
+    let mut ledger = Ledger::new_for_variant(&g, Variant::X).unwrap();
+    assert!(ledger.apply_implications().is_err());
+  }
+
+  #[test]
+  fn test_ledger_relation_variants() {
+    let mut g = Grid::new();
+    g[L13] = Some(N1);
+    g[L55] = Some(N5);
+    let mut ledger = Ledger::new_for_variant(&g, Variant::AntiKnight).unwrap();
+    ledger.apply_implications().unwrap();
+    assert!(!ledger.is_possible(N1, L25));
+    assert!(ledger.is_possible(N1, L24));
+    let mut ledger = Ledger::new_for_variant(&g, Variant::AntiKing).unwrap();
+    ledger.apply_implications().unwrap();
+    assert!(!ledger.is_possible(N1, L24));
+    assert!(ledger.is_possible(N1, L25));
+    let mut ledger = Ledger::new_for_variant(&g, Variant::NonConsecutive).unwrap();
+    ledger.apply_implications().unwrap();
+    assert!(!ledger.is_possible(N4, L45));
+    assert!(!ledger.is_possible(N6, L45));
+    assert!(ledger.is_possible(N4, L44));
+
+    // A knight's move apart, two 1s are fine in standard Sudoku but not in
+    // anti-knight.
+    g[L25] = Some(N1);
+    let mut ledger = Ledger::new(&g).unwrap();
+    assert!(ledger.apply_implications().is_ok());
+    let mut ledger = Ledger::new_for_variant(&g, Variant::AntiKnight).unwrap();
+    assert!(ledger.apply_implications().is_err());
+  }
+
+  #[test]
+  fn test_bytes_round_trip() {
+    let g = Grid::from_str(
+      "1.........2.........3.........4.........5.........6.........7.........8..........",
+    )
+    .unwrap();
+    for variant in Variant::ALL {
+      let mut ledger = Ledger::new_for_variant(&g, variant).unwrap();
+      let _ = ledger.apply_implications();
+      let mut bytes = vec![];
+      ledger.write_bytes(&mut bytes);
+      assert_eq!(Ledger::BYTES, bytes.len());
+      assert_eq!(Some(ledger), Ledger::read_bytes(&bytes));
+    }
+  }
 }
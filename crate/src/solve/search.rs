@@ -0,0 +1,64 @@
+//! Tracks the progress of the depth-first searches that the generic and sized
+//! solvers make, which differ only in their boards.
+
+use crate::random::Random;
+
+/// The progress of a search: the solutions found so far, and when to stop.
+pub(super) struct Search<'a, S> {
+  pub solutions: Vec<S>,
+  /// How many solutions to stop at.
+  limit: usize,
+  /// How many more pivots to try before giving up, if there's a limit.
+  pivots_left: Option<usize>,
+  /// Shuffles the numerals tried at each pivot, if present.
+  random: Option<&'a mut Random>,
+}
+
+impl<'a, S> Search<'a, S> {
+  /// Starts a search that finds at most one more than `max_solutions`
+  /// solutions, however many pivots that takes.
+  pub fn counting(max_solutions: i32) -> Self {
+    Self {
+      solutions: Vec::new(),
+      limit: 0.max(max_solutions) as usize + 1,
+      pivots_left: None,
+      random: None,
+    }
+  }
+
+  /// Starts a search for a single solution that tries the numerals at each
+  /// pivot in random order, and gives up after `max_pivots` pivots.
+  pub fn random(random: &'a mut Random, max_pivots: usize) -> Self {
+    Self {
+      solutions: Vec::new(),
+      limit: 1,
+      pivots_left: Some(max_pivots),
+      random: Some(random),
+    }
+  }
+
+  /// Tells whether the search has all the solutions it stops at, which for a
+  /// counting search means more than it was asked for.
+  pub fn is_full(&self) -> bool {
+    self.solutions.len() >= self.limit
+  }
+
+  /// Puts the numerals to try at a pivot in the order to try them.
+  pub fn order<T>(&mut self, nums: &mut [T]) {
+    if let Some(random) = self.random.as_mut() {
+      random.shuffle(nums);
+    }
+  }
+
+  /// Tells whether to try another numeral at a pivot, counting it against the
+  /// pivot limit if it does.
+  pub fn take_pivot(&mut self) -> bool {
+    if self.is_full() || self.pivots_left == Some(0) {
+      return false;
+    }
+    if let Some(pivots_left) = self.pivots_left.as_mut() {
+      *pivots_left -= 1;
+    }
+    true
+  }
+}
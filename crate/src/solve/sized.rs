@@ -0,0 +1,260 @@
+//! Defines a solver for boards of other sizes than 9x9.  Like the generic
+//! solver, it makes the naked and hidden single assignments at each step and
+//! pivots on the location with the fewest candidates.
+
+use crate::core::*;
+use crate::random::Random;
+
+use super::search::Search;
+
+/// The results of solving a SizedGrid puzzle.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SizedSolutionSummary<const BR: usize, const BC: usize> {
+  /// The puzzle that was solved.
+  pub clues: SizedGrid<BR, BC>,
+  /// The solutions found, at most one more than were asked for.
+  pub solutions: Vec<SizedGrid<BR, BC>>,
+  /// Whether there are more solutions than were asked for.
+  pub too_many_solutions: bool,
+}
+
+/// Solves puzzles on boards whose blocks have `BR` rows and `BC` columns.
+pub struct SizedSolver<const BR: usize, const BC: usize> {
+  /// The cells of each unit.
+  units: Vec<Vec<usize>>,
+  /// Each cell's peers.
+  peers: Vec<Vec<usize>>,
+}
+
+impl<const BR: usize, const BC: usize> SizedSolver<BR, BC> {
+  pub fn new() -> Self {
+    Self {
+      units: SizedGrid::<BR, BC>::units()
+        .map(SizedGrid::<BR, BC>::unit_cells)
+        .collect(),
+      peers: (0..SizedGrid::<BR, BC>::CELLS)
+        .map(SizedGrid::<BR, BC>::peers)
+        .collect(),
+    }
+  }
+
+  /// Lists the given board's solutions, stopping once it has more than
+  /// `max_solutions` of them.
+  pub fn solve(
+    &self,
+    clues: &SizedGrid<BR, BC>,
+    max_solutions: i32,
+  ) -> SizedSolutionSummary<BR, BC> {
+    let mut search = Search::counting(max_solutions);
+    if let Some(state) = self.start(clues) {
+      self.search(state, &mut search);
+    }
+    SizedSolutionSummary {
+      clues: *clues,
+      too_many_solutions: search.is_full(),
+      solutions: search.solutions,
+    }
+  }
+
+  /// Fills in the given board at random, which is how the sized generator
+  /// makes its solutions.  Returns None if the board can't be completed, or
+  /// if `max_pivots` guesses didn't complete it.
+  pub fn random_solution(
+    &self,
+    clues: &SizedGrid<BR, BC>,
+    random: &mut Random,
+    max_pivots: usize,
+  ) -> Option<SizedGrid<BR, BC>> {
+    let mut search = Search::random(random, max_pivots);
+    self.search(self.start(clues)?, &mut search);
+    search.solutions.pop()
+  }
+
+  /// Makes the search state for the given clues, or returns None if they
+  /// break the rules.
+  fn start(&self, clues: &SizedGrid<BR, BC>) -> Option<State<BR, BC>> {
+    let mut state = State {
+      grid: SizedGrid::new(),
+      candidates: [SizedNumSet::all(SizedGrid::<BR, BC>::SIZE); MAX_SIZED_CELLS],
+    };
+    for (cell, num) in clues.iter() {
+      if !state.candidates[cell].contains(num) {
+        return None;
+      }
+      self.assign(&mut state, cell, num);
+    }
+    self.propagate(&mut state).then_some(state)
+  }
+
+  /// Assigns the given numeral to the given cell, and rules it out of the
+  /// cell's peers.
+  fn assign(&self, state: &mut State<BR, BC>, cell: usize, num: u8) {
+    state.grid.set(cell, Some(num));
+    state.candidates[cell] = SizedNumSet::new();
+    for &peer in &self.peers[cell] {
+      state.candidates[peer].remove(num);
+    }
+  }
+
+  /// Makes all the naked and hidden single assignments it can, returning
+  /// false if it finds that the state is invalid.
+  fn propagate(&self, state: &mut State<BR, BC>) -> bool {
+    loop {
+      let mut changed = false;
+      for cell in 0..SizedGrid::<BR, BC>::CELLS {
+        if state.grid.get(cell).is_some() {
+          continue;
+        }
+        let candidates = state.candidates[cell];
+        match candidates.len() {
+          0 => return false,
+          1 => {
+            self.assign(state, cell, candidates.smallest().unwrap());
+            changed = true;
+          }
+          _ => {}
+        }
+      }
+      for unit in &self.units {
+        let mut once = SizedNumSet::new();
+        let mut more = SizedNumSet::new();
+        let mut assigned = SizedNumSet::new();
+        for &cell in unit {
+          match state.grid.get(cell) {
+            Some(num) => assigned.insert(num),
+            None => {
+              let candidates = state.candidates[cell];
+              more = more | (once & candidates);
+              once = once | candidates;
+            }
+          }
+        }
+        if (once | assigned).len() < SizedGrid::<BR, BC>::SIZE {
+          return false;
+        }
+        for num in (once - more).iter() {
+          // Another assignment may have taken the numeral's only place.
+          match unit
+            .iter()
+            .find(|cell| state.candidates[**cell].contains(num))
+          {
+            Some(&cell) => {
+              self.assign(state, cell, num);
+              changed = true;
+            }
+            None => return false,
+          }
+        }
+      }
+      if !changed {
+        return true;
+      }
+    }
+  }
+
+  /// Searches depth-first from the given state, pivoting on the cell with the
+  /// fewest candidates.
+  fn search(&self, state: State<BR, BC>, search: &mut Search<SizedGrid<BR, BC>>) {
+    let Some(cell) = (0..SizedGrid::<BR, BC>::CELLS)
+      .filter(|cell| state.grid.get(*cell).is_none())
+      .min_by_key(|cell| state.candidates[*cell].len())
+    else {
+      // The grid is full, and `propagate` has checked it.
+      search.solutions.push(state.grid);
+      return;
+    };
+    let mut nums: Vec<u8> = state.candidates[cell].iter().collect();
+    search.order(&mut nums);
+    for num in nums {
+      if !search.take_pivot() {
+        return;
+      }
+      let mut next = state;
+      self.assign(&mut next, cell, num);
+      if self.propagate(&mut next) {
+        self.search(next, search);
+      }
+    }
+  }
+}
+
+impl<const BR: usize, const BC: usize> Default for SizedSolver<BR, BC> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// The state of a search: the assignments made so far, and the possibilities
+/// that remain.
+#[derive(Clone, Copy)]
+struct State<const BR: usize, const BC: usize> {
+  grid: SizedGrid<BR, BC>,
+  /// The possible numerals for each unset cell.
+  candidates: [SizedNumSet; MAX_SIZED_CELLS],
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::random::new_random;
+  use crate::solve::{solve, DefaultHelper};
+  use std::str::FromStr;
+
+  #[test]
+  fn test_matches_standard_solver() {
+    let solver = SizedSolver::<3, 3>::new();
+    for clues in [
+      ".3....91.8.6.....2...8.4...5.2..7..........7.9..4.65.....7.3...3.8.....1.97...8..",
+      ".6.5.4.3.1...9...8.........9...5...6.4.6.2.7.7...4...5.........4...8...1.5.2.3.4.",
+      "11...............................................................................",
+    ] {
+      let clues = Grid::from_str(clues).unwrap();
+      let expected = solve(&clues, 10, &mut DefaultHelper());
+      let mut actual = solver.solve(&SizedGrid::from(&clues), 10);
+      actual.solutions.sort();
+      let mut solutions: Vec<SizedGrid<3, 3>> = expected
+        .solutions
+        .iter()
+        .map(|s| SizedGrid::from(&s.grid()))
+        .collect();
+      solutions.sort();
+      assert_eq!(solutions, actual.solutions, "{}", clues);
+      assert_eq!(expected.too_many_solutions, actual.too_many_solutions);
+    }
+  }
+
+  #[test]
+  fn test_counts() {
+    // There are 288 4x4 Sudoku grids.
+    let summary = SizedSolver::<2, 2>::new().solve(&Grid4::new(), 1000);
+    assert_eq!(288, summary.solutions.len());
+    assert!(!summary.too_many_solutions);
+    assert!(summary.solutions.iter().all(Grid4::is_solved));
+
+    let clues = Grid6::from_str("1.....  ......  ......  ......  ......  .....1").unwrap();
+    let summary = SizedSolver::<2, 3>::new().solve(&clues, 5);
+    assert!(summary.too_many_solutions);
+    assert!(summary
+      .solutions
+      .iter()
+      .all(|s| s.is_solved() && s.extends(&clues)));
+    let clues = Grid4::from_str("11..  ....  ....  ....").unwrap();
+    assert!(SizedSolver::<2, 2>::new()
+      .solve(&clues, 5)
+      .solutions
+      .is_empty());
+  }
+
+  #[test]
+  fn test_random_solutions() {
+    let mut random = new_random("sized");
+    let solution = SizedSolver::<3, 4>::new()
+      .random_solution(&Grid12::new(), &mut random, 10_000)
+      .unwrap();
+    assert!(solution.is_solved());
+    let solution = SizedSolver::<4, 4>::new()
+      .random_solution(&Grid16::new(), &mut random, 10_000)
+      .unwrap();
+    assert!(solution.is_solved());
+  }
+}
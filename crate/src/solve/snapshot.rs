@@ -0,0 +1,281 @@
+//! Saves and restores the progress of a `Searcher`, so a long search can be
+//! split across many short tasks.
+
+use std::fmt;
+
+use wasm_bindgen::prelude::*;
+
+use crate::solve::ledger::Ledger;
+use crate::solve::*;
+
+/// The version of the snapshot format written by `Searcher::snapshot`.
+const SNAPSHOT_VERSION: u8 = 3;
+
+/// The size of the snapshot header: the version byte, the puzzle's
+/// fingerprint, the pivot count, the maximum depth, and the stack size.
+const HEADER_BYTES: usize = 1 + 8 + 4 + 4 + 2;
+
+/// The size of each stack item: its ledger, the assignment count and next
+/// index, and the assignments as location/numeral pairs.
+const ITEM_BYTES: usize = Ledger::BYTES + 1 + 1 + 2 * 9;
+
+/// The ways restoring a searcher from a snapshot can fail.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SnapshotError {
+  /// The snapshot was written by an incompatible version of this code.
+  WrongVersion(u8),
+  /// The snapshot was taken while searching a different puzzle.
+  WrongPuzzle,
+  /// The snapshot is truncated or corrupt.
+  Malformed,
+}
+
+impl fmt::Display for SnapshotError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      SnapshotError::WrongVersion(v) => write!(
+        f,
+        "Snapshot has version {}, but this code reads version {}",
+        v, SNAPSHOT_VERSION
+      ),
+      SnapshotError::WrongPuzzle => write!(f, "Snapshot is for a different puzzle"),
+      SnapshotError::Malformed => write!(f, "Snapshot is malformed"),
+    }
+  }
+}
+
+impl<'a> Searcher<'a> {
+  /// Serializes the searcher's remaining work.  The snapshot doesn't include
+  /// `found`, so take any solution out of that before calling this.  Nor does
+  /// it include the helper: resuming with a stateless helper like
+  /// `DefaultHelper` continues the search exactly where it left off.
+  pub fn snapshot(&self, clues: &Grid) -> Vec<u8> {
+    let mut out = Vec::with_capacity(HEADER_BYTES + ITEM_BYTES * self.stack.len());
+    out.push(SNAPSHOT_VERSION);
+    out.extend_from_slice(&fingerprint(clues).to_le_bytes());
+    out.extend_from_slice(&self.total_pivots.to_le_bytes());
+    out.extend_from_slice(&self.max_depth.to_le_bytes());
+    out.extend_from_slice(&(self.stack.len() as u16).to_le_bytes());
+    for item in &self.stack {
+      item.ledger.write_bytes(&mut out);
+      out.push(item.count as u8);
+      out.push(item.next as u8);
+      for asgmt in &item.asgmts {
+        match asgmt {
+          Some(a) => out.extend_from_slice(&[a.loc.get() as u8, a.num.get() as u8]),
+          None => out.extend_from_slice(&[0, 0]),
+        }
+      }
+    }
+    out
+  }
+
+  /// Recreates a searcher from a snapshot of one that was searching the given
+  /// puzzle.
+  pub fn resume(
+    clues: &Grid,
+    snapshot: &[u8],
+    helper: &'a mut dyn SearchHelper,
+  ) -> Result<Self, SnapshotError> {
+    if snapshot.len() < HEADER_BYTES {
+      return Err(SnapshotError::Malformed);
+    }
+    if snapshot[0] != SNAPSHOT_VERSION {
+      return Err(SnapshotError::WrongVersion(snapshot[0]));
+    }
+    let u64_at = |i: usize| u64::from_le_bytes(snapshot[i..i + 8].try_into().unwrap());
+    let i32_at = |i: usize| i32::from_le_bytes(snapshot[i..i + 4].try_into().unwrap());
+    if u64_at(1) != fingerprint(clues) {
+      return Err(SnapshotError::WrongPuzzle);
+    }
+    let total_pivots = i32_at(9);
+    let max_depth = i32_at(13);
+    let len = u16::from_le_bytes([snapshot[17], snapshot[18]]) as usize;
+    let items = &snapshot[HEADER_BYTES..];
+    if items.len() != len * ITEM_BYTES {
+      return Err(SnapshotError::Malformed);
+    }
+    let stack = items
+      .chunks_exact(ITEM_BYTES)
+      .map(read_item)
+      .collect::<Option<Vec<_>>>()
+      .ok_or(SnapshotError::Malformed)?;
+    Ok(Searcher {
+      found: None,
+      total_pivots,
+      max_depth,
+      stack,
+      helper,
+    })
+  }
+
+  /// Tells whether the searcher has run out of places to look.
+  pub fn is_done(&self) -> bool {
+    self.stack.is_empty()
+  }
+}
+
+fn read_item(bytes: &[u8]) -> Option<StackItem> {
+  let ledger = Ledger::read_bytes(bytes)?;
+  let rest = &bytes[Ledger::BYTES..];
+  let count = rest[0] as i8;
+  let next = rest[1] as i8;
+  if !(0 < count && count <= 9 && 0 <= next && next < count) {
+    return None;
+  }
+  let mut asgmts = [None; 9];
+  for (i, pair) in rest[2..].chunks_exact(2).enumerate() {
+    if (i as i8) < count {
+      asgmts[i] = Some(Asgmt {
+        loc: Loc::new(pair[0] as i8)?,
+        num: Num::new(pair[1] as i8)?,
+      });
+    }
+  }
+  Some(StackItem {
+    ledger,
+    asgmts,
+    count,
+    next,
+  })
+}
+
+/// Hashes the puzzle's clues, using 64-bit FNV-1a.
+fn fingerprint(clues: &Grid) -> u64 {
+  clues.bytes().iter().fold(0xcbf29ce484222325, |hash, &b| {
+    (hash ^ b as u64).wrapping_mul(0x100000001b3)
+  })
+}
+
+/// The outcome of one slice of a time-sliced search.
+#[derive(Clone, Debug)]
+#[wasm_bindgen(getter_with_clone)]
+pub struct SearchSlice {
+  /// The solution found during this slice, if any.
+  pub solution: Option<SolvedGrid>,
+  /// The number of pivot points traversed during this slice.
+  pub pivots: i32,
+  /// The snapshot to pass to the next slice, or None if the search is over.
+  pub snapshot: Option<Box<[u8]>>,
+}
+
+/// Runs a slice of a search through the given puzzle's solutions, stopping
+/// after `max_pivots` pivot points or when it finds a solution.  Pass no
+/// snapshot to start a new search, and the previous slice's snapshot to
+/// continue one.
+#[wasm_bindgen(js_name = "searchSlice")]
+pub fn search_slice(
+  clues: &Grid,
+  snapshot: Option<Box<[u8]>>,
+  max_pivots: i32,
+) -> Result<SearchSlice, String> {
+  let mut helper = DefaultHelper();
+  let factory;
+  let mut searcher = match snapshot {
+    None => {
+      factory = SearcherFactory::new(clues);
+      factory.new_searcher(&mut helper)
+    }
+    Some(bytes) => Searcher::resume(clues, &bytes, &mut helper).map_err(|e| e.to_string())?,
+  };
+  let mut solution = searcher.found.take();
+  let pivots = if solution.is_some() {
+    0
+  } else {
+    let pivots = searcher.run(Some(max_pivots));
+    solution = searcher.found.take();
+    pivots
+  };
+  Ok(SearchSlice {
+    solution,
+    pivots,
+    snapshot: if searcher.is_done() {
+      None
+    } else {
+      Some(searcher.snapshot(clues).into_boxed_slice())
+    },
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::str::FromStr;
+
+  fn clues() -> Grid {
+    Grid::from_str(
+      ".3....91.8.6.....2...8.4...5.2..7..........7.9..4.65.....7.3...3.8.....1.97...8..",
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn test_resume_finds_same_solutions() {
+    let clues = clues();
+    let expected = solve(&clues, 100, &mut DefaultHelper()).solutions;
+
+    let mut found = Vec::new();
+    let mut snapshot = None;
+    loop {
+      let slice = search_slice(&clues, snapshot, 1).unwrap();
+      found.extend(slice.solution);
+      snapshot = slice.snapshot;
+      if snapshot.is_none() {
+        break;
+      }
+    }
+    assert_eq!(expected, found);
+  }
+
+  #[test]
+  fn test_snapshot_round_trip() {
+    let clues = clues();
+    let factory = SearcherFactory::new(&clues);
+    let mut helper = DefaultHelper();
+    let mut searcher = factory.new_searcher(&mut helper);
+    searcher.run(Some(3));
+    let snapshot = searcher.snapshot(&clues);
+
+    let mut helper2 = DefaultHelper();
+    let resumed = Searcher::resume(&clues, &snapshot, &mut helper2).unwrap();
+    assert_eq!(searcher.total_pivots, resumed.total_pivots);
+    assert_eq!(searcher.max_depth, resumed.max_depth);
+    assert_eq!(snapshot, resumed.snapshot(&clues));
+  }
+
+  #[test]
+  fn test_resume_errors() {
+    let clues = clues();
+    let factory = SearcherFactory::new(&clues);
+    let mut helper = DefaultHelper();
+    let mut searcher = factory.new_searcher(&mut helper);
+    searcher.run(Some(3));
+    let snapshot = searcher.snapshot(&clues);
+
+    let mut helper = DefaultHelper();
+    let mut other = clues;
+    other[L11] = Some(N1);
+    assert_eq!(
+      Some(SnapshotError::WrongPuzzle),
+      Searcher::resume(&other, &snapshot, &mut helper).err()
+    );
+    assert_eq!(
+      Some(SnapshotError::Malformed),
+      Searcher::resume(&clues, &snapshot[..snapshot.len() - 1], &mut helper).err()
+    );
+    let mut bad = snapshot.clone();
+    bad[0] = 99;
+    assert_eq!(
+      Some(SnapshotError::WrongVersion(99)),
+      Searcher::resume(&clues, &bad, &mut helper).err()
+    );
+    let mut bad = snapshot.clone();
+    // Corrupt the top item's next-assignment index.
+    let i = bad.len() - 19;
+    bad[i] = 9;
+    assert_eq!(
+      Some(SnapshotError::Malformed),
+      Searcher::resume(&clues, &bad, &mut helper).err()
+    );
+  }
+}
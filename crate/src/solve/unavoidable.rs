@@ -0,0 +1,109 @@
+//! Finds the unavoidable sets of solved Sudoku grids.
+
+use itertools::Itertools;
+
+use crate::core::*;
+use crate::solve::*;
+
+/// The largest number of distinct numerals we look at when searching for
+/// unavoidable sets.  Every numeral in an unavoidable set appears at least
+/// twice, so this limit only matters for sets of more than 9 locations.
+const MAX_NUMS: usize = 4;
+
+impl SolvedGrid {
+  /// Finds this grid's minimal unavoidable sets of no more than `max_size`
+  /// locations, sorted by size.  An unavoidable set is a set of locations that
+  /// could be filled in differently while leaving the rest of the grid alone,
+  /// which means every puzzle with this solution must have a clue in it.
+  ///
+  /// Sets with more than 9 locations are found only if they involve no more
+  /// than 4 distinct numerals.
+  pub fn unavoidable_sets(&self, max_size: usize) -> Vec<LocSet> {
+    // Any alternative filling of a set of locations uses the same numerals in
+    // each unit as the original, so we can find all unavoidable sets involving
+    // a given combination of numerals by erasing every location holding one of
+    // those numerals and solving.
+    let mut found: Vec<LocSet> = Vec::new();
+    for count in 2..=MAX_NUMS.min(max_size / 2) {
+      for nums in Num::all().combinations(count) {
+        let mut grid = self.grid();
+        for loc in Loc::all() {
+          if nums.contains(&self[loc]) {
+            grid[loc] = None;
+          }
+        }
+        for solution in solutions(&grid, &mut DefaultHelper()) {
+          let diffs: LocSet = Loc::all().filter(|&loc| solution[loc] != self[loc]).collect();
+          if !diffs.is_empty() && diffs.len() as usize <= max_size {
+            found.push(diffs);
+          }
+        }
+      }
+    }
+
+    found.sort_by_key(|set| (set.len(), set.as_boxed_indices()));
+    found.dedup();
+    let mut minimal: Vec<LocSet> = Vec::new();
+    for set in found {
+      if !minimal.iter().any(|m| *m <= set) {
+        minimal.push(set);
+      }
+    }
+    minimal
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loc_set;
+  use std::str::FromStr;
+
+  fn grid() -> SolvedGrid {
+    Grid::from_str(
+      "
+      215 438 679
+      369 271 845
+      478 695 321
+      824 967 153
+      531 824 967
+      796 513 482
+      987 342 516
+      143 756 298
+      652 189 734",
+    )
+    .unwrap()
+    .solved_grid()
+    .unwrap()
+  }
+
+  #[test]
+  fn test_rectangles() {
+    let sets = grid().unavoidable_sets(4);
+    assert!(!sets.is_empty());
+    assert!(sets.contains(&loc_set! {L13, L19, L23, L29}));
+    assert!(sets.contains(&loc_set! {L55, L56, L75, L76}));
+    for set in &sets {
+      assert_eq!(4, set.len());
+    }
+  }
+
+  #[test]
+  fn test_sets_are_unavoidable_and_minimal() {
+    let solved = grid();
+    let sets = solved.unavoidable_sets(6);
+    assert!(sets.iter().any(|s| s.len() == 6));
+    for (i, set) in sets.iter().enumerate() {
+      // Erasing the set leaves more than one solution.
+      let mut grid = solved.grid();
+      for loc in set.iter() {
+        grid[loc] = None;
+      }
+      assert!(count_solutions(&grid, 2) > 1, "{:?}", set);
+      // No set contains another: each other set has a location this one lacks.
+      for (j, other) in sets.iter().enumerate() {
+        assert!(i == j || !(*other - *set).is_empty());
+      }
+    }
+  }
+}
@@ -13,18 +13,29 @@ use serde::Serialize;
 use wasm_bindgen::{
   convert::{FromWasmAbi, IntoWasmAbi},
   describe::{inform, WasmDescribe, I8},
-  prelude::wasm_bindgen,
+  prelude::{wasm_bindgen, JsError},
   JsValue,
 };
 
-use crate::{core::*, random::*};
-#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+use crate::{
+  core::*,
+  permute::{GroupElement, NumPermutation},
+  random::*,
+};
+
+mod custom;
+
+pub use custom::*;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, ts_rs::TS)]
+#[ts(export, export_to = "../../www/src/facts/")]
 pub enum Diagonal {
   Main,
   Anti,
 }
 
-#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, ts_rs::TS)]
+#[ts(export, export_to = "../../www/src/facts/")]
 pub enum Axis {
   X,
   Y,
@@ -39,7 +50,8 @@ pub enum Axis {
 /// `Sym` implements partial ordering according to whether one symmetry implies
 /// the other, meaning that all of the orbits of the "larger" one are supersets
 /// of the orbits of the "smaller" one.
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, ts_rs::TS)]
+#[ts(export, export_to = "../../www/src/facts/")]
 pub enum Sym {
   /// The classic Sudoku symmetry, 180-degree rotational.
   Rotation180,
@@ -81,18 +93,29 @@ pub enum ExplicitSym {
   None,
 }
 
-#[wasm_bindgen(js_name = "evaluateSymmetry")]
-pub fn evaluate_symmetry(sym: ExplicitSym, clues: &Grid) -> JsValue {
-  serde_wasm_bindgen::to_value(&Sym::from_explicit(sym).evaluate(clues)).unwrap()
+// The TypeScript type of the matches the entry points below return, as
+// exported by ts-rs.
+#[wasm_bindgen(typescript_custom_section)]
+const TS_TYPES: &str = r#"
+import type {SymMatch} from '../../www/src/facts/SymMatch';
+"#;
+
+#[wasm_bindgen(js_name = "evaluateSymmetry", unchecked_return_type = "SymMatch")]
+pub fn evaluate_symmetry(sym: ExplicitSym, clues: &Grid) -> Result<JsValue, JsError> {
+  let m = Sym::from_explicit(sym).evaluate(clues);
+  Ok(serde_wasm_bindgen::to_value(&m)?)
 }
 
-#[wasm_bindgen(js_name = "bestSymmetryMatches")]
-pub fn best_matches(clues: &Grid, max_nonconforming_locs: usize) -> JsValue {
+#[wasm_bindgen(
+  js_name = "bestSymmetryMatches",
+  unchecked_return_type = "[Sym, SymMatch][]"
+)]
+pub fn best_matches(clues: &Grid, max_nonconforming_locs: usize) -> Result<JsValue, JsError> {
   let matches: Vec<(i32, SymMatch)> = Sym::best_matches(clues, max_nonconforming_locs)
     .into_iter()
     .map(|(s, m)| (s.explicit() as i32, m))
     .collect();
-  serde_wasm_bindgen::to_value(&matches).unwrap()
+  Ok(serde_wasm_bindgen::to_value(&matches)?)
 }
 
 impl WasmDescribe for Sym {
@@ -120,14 +143,44 @@ impl IntoWasmAbi for Sym {
 
 /// How well a puzzle matches a symmetry, in terms of which locations have
 /// clues.
-#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize)]
-pub struct SymMatch {
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, ts_rs::TS)]
+#[ts(export, export_to = "../../www/src/facts/")]
+pub struct SymMatch<'a> {
   /// The orbits that are full.
-  pub full_orbits: Vec<&'static [Loc]>,
+  #[ts(as = "Vec<Vec<Loc>>")]
+  pub full_orbits: Vec<&'a [Loc]>,
   /// The number of locations that don't conform to the symmetry.
   pub num_nonconforming_locs: usize,
   /// The orbits that are only partially full.
-  pub partial_orbits: Vec<&'static [Loc]>,
+  #[ts(as = "Vec<Vec<Loc>>")]
+  pub partial_orbits: Vec<&'a [Loc]>,
+}
+
+/// What generating and evaluating puzzles need from a symmetry: its orbits.
+/// Both the built-in `Sym`s and `CustomSym`s have them.
+pub trait Symmetry {
+  /// Returns the symmetry's orbits, which between them hold every location
+  /// once.
+  fn orbits(&self) -> Vec<&[Loc]>;
+
+  /// Tells whether this is the symmetry that relates each location only to
+  /// itself.
+  fn is_trivial(&self) -> bool {
+    self.orbits().len() == 81
+  }
+
+  /// Returns the symmetry's orbits after shuffling by the given random number
+  /// generator.
+  fn shuffled_orbits(&self, random: &mut Random) -> Vec<&[Loc]> {
+    let mut answer = self.orbits();
+    random.shuffle(&mut answer);
+    answer
+  }
+
+  /// Calculates how well the given puzzle matches this symmetry.
+  fn evaluate(&self, clues: &Grid) -> SymMatch<'_> {
+    evaluate_orbits(self.orbits(), clues)
+  }
 }
 
 /// All the symmetries.
@@ -203,63 +256,235 @@ impl Sym {
 
   /// Returns this symmetry's orbits after shuffling by the given random
   /// number generator.
-  pub fn shuffled_orbits<R: Rng>(self, random: &mut R) -> Vec<&'static [Loc]> {
+  pub fn shuffled_orbits(self, random: &mut Random) -> Vec<&'static [Loc]> {
     let mut answer = Vec::from(self.orbits());
-    answer.shuffle(random);
+    random.shuffle(&mut answer);
     answer
   }
 
-  /// Calculates how well the given puzzle matches this symmetry.
-  pub fn evaluate(self, clues: &Grid) -> SymMatch {
-    let mut full_orbits = vec![];
-    let mut num_nonconforming_locs = 0;
-    let mut partial_orbits = vec![];
-    for orbit in self.orbits() {
-      let filled_count = filled_count(orbit, clues);
-      num_nonconforming_locs += min(filled_count, orbit.len() - filled_count);
-      if filled_count > 0 {
-        if filled_count < orbit.len() {
-          partial_orbits.push(*orbit);
-        } else {
-          full_orbits.push(*orbit);
-        }
+  /// Returns the location mapping that generates this symmetry, if it's one
+  /// that a Sudoku solution can have once its numerals are mapped too: a
+  /// rotation or a blockwise translation.  The reflections all fix a line or
+  /// a diagonal, whose numerals would have to map to themselves, and then the
+  /// numerals reflected across it would clash.
+  pub fn loc_mapping(self) -> Option<fn(Loc) -> Loc> {
+    match self {
+      Self::Rotation180 => Some(|loc| loc.opp()),
+      Self::Rotation90 => Some(|loc| Loc::at(loc.col().t(), loc.row().opp().t())),
+      Self::Blockwise(Diagonal::Main) => Some(|loc| {
+        Blk::from_bands(loc.row_band().next(), loc.col_band().next())
+          .loc_at(loc.blk_row(), loc.blk_col())
+      }),
+      Self::Blockwise(Diagonal::Anti) => Some(|loc| {
+        Blk::from_bands(loc.row_band().next(), loc.col_band().prev())
+          .loc_at(loc.blk_row(), loc.blk_col())
+      }),
+      _ => None,
+    }
+  }
+
+  /// Finds the permutation of numerals under which the given clues are
+  /// symmetric: the clue at each clue's image under `loc_mapping` is the
+  /// clue's numeral mapped by the permutation.  Returns None if there's no
+  /// such permutation, or if more than one numeral is missing from the clues,
+  /// since then the clues don't pin it down.
+  pub fn digit_mapping(self, clues: &Grid) -> Option<NumPermutation> {
+    let mapping = self.loc_mapping()?;
+    let mut images: [Option<Num>; 9] = [None; 9];
+    for asgmt in clues.iter() {
+      let image = clues[mapping(asgmt.loc)]?;
+      match images[asgmt.num.index()] {
+        Some(prev) if prev != image => return None,
+        _ => images[asgmt.num.index()] = Some(image),
       }
     }
-    SymMatch {
-      full_orbits,
-      num_nonconforming_locs,
-      partial_orbits,
+    let missing: Vec<Num> = Num::all().filter(|n| images[n.index()].is_none()).collect();
+    match missing[..] {
+      [] => {}
+      [num] => {
+        let used: NumSet = images.iter().flatten().copied().collect();
+        images[num.index()] = (NumSet::all() - used).smallest_item();
+      }
+      _ => return None,
+    }
+    // Fails if two numerals have the same image.
+    let nums = NumPermutation::new(images.map(|image| image.unwrap_or(N1)))?;
+    let order = self.orbits().iter().map(|orbit| orbit.len()).max().unwrap();
+    let mut power = nums;
+    for _ in 1..order {
+      power.compose(&nums);
+    }
+    (power == NumPermutation::identity()).then_some(nums)
+  }
+
+  /// Tells whether this symmetry maps the given variant's extra units onto
+  /// each other, so that a puzzle with this symmetry's clue pattern looks like
+  /// it belongs to the variant.  All the reflections and rotations preserve
+  /// X-Sudoku's diagonals, but the blockwise translations don't.  Variants
+  /// without extra units look the same under every symmetry.
+  pub fn preserves(self, variant: Variant) -> bool {
+    match variant {
+      Variant::X => !matches!(self, Self::Blockwise(_)),
+      _ => true,
     }
   }
 
+  /// Calculates how well the given puzzle matches this symmetry.
+  pub fn evaluate(self, clues: &Grid) -> SymMatch<'static> {
+    evaluate_orbits(self.orbits().to_vec(), clues)
+  }
+
   /// Finds the symmetries that best match the given puzzle grid,
   /// disqualifying any symmetries that match with more than the given number
   /// of nonconforming locations.
-  pub fn best_matches(clues: &Grid, max_nonconforming_locs: usize) -> Vec<(Sym, SymMatch)> {
-    let mut answer: Vec<(Sym, SymMatch)> = vec![];
-    for sym in SYMS {
-      let m = sym.evaluate(clues);
-      if m.num_nonconforming_locs > max_nonconforming_locs {
-        // Ignore symmetries that are too far from describing this puzzle.
-        continue;
+  pub fn best_matches(
+    clues: &Grid,
+    max_nonconforming_locs: usize,
+  ) -> Vec<(Sym, SymMatch<'static>)> {
+    Self::best_variant_matches(clues, max_nonconforming_locs, Variant::Standard)
+  }
+
+  /// Like `best_matches`, but considers only the symmetries that preserve the
+  /// given variant's units.
+  pub fn best_variant_matches(
+    clues: &Grid,
+    max_nonconforming_locs: usize,
+    variant: Variant,
+  ) -> Vec<(Sym, SymMatch<'static>)> {
+    let syms = SYMS.iter().filter(|sym| sym.preserves(variant));
+    best_matches_among(syms, clues, max_nonconforming_locs)
+      .into_iter()
+      .map(|(sym, m)| (*sym, m))
+      .collect()
+  }
+}
+
+impl Symmetry for Sym {
+  fn orbits(&self) -> Vec<&[Loc]> {
+    Sym::orbits(*self).to_vec()
+  }
+
+  fn is_trivial(&self) -> bool {
+    *self == Sym::None
+  }
+}
+
+/// Calculates how well the given puzzle matches a symmetry with the given
+/// orbits.
+fn evaluate_orbits<'a>(orbits: Vec<&'a [Loc]>, clues: &Grid) -> SymMatch<'a> {
+  let mut full_orbits = vec![];
+  let mut num_nonconforming_locs = 0;
+  let mut partial_orbits = vec![];
+  for orbit in orbits {
+    let filled_count = filled_count(orbit, clues);
+    num_nonconforming_locs += min(filled_count, orbit.len() - filled_count);
+    if filled_count > 0 {
+      if filled_count < orbit.len() {
+        partial_orbits.push(orbit);
+      } else {
+        full_orbits.push(orbit);
       }
-      let mut add = true;
-      for (prev_sym, prev_match) in &answer {
-        if *prev_sym > *sym && (!m.is_complete() || prev_match.is_complete() || *sym == Sym::None) {
-          add = false;
-          break;
-        }
+    }
+  }
+  SymMatch {
+    full_orbits,
+    num_nonconforming_locs,
+    partial_orbits,
+  }
+}
+
+/// Finds the symmetries among the given ones that best match the given puzzle
+/// grid, disqualifying any that match with more than the given number of
+/// nonconforming locations, and any that a greater symmetry matches as well.
+/// No symmetry may come after one it's greater than.
+fn best_matches_among<'a, S: Symmetry + PartialOrd + 'a>(
+  syms: impl Iterator<Item = &'a S>,
+  clues: &Grid,
+  max_nonconforming_locs: usize,
+) -> Vec<(&'a S, SymMatch<'a>)> {
+  let mut answer: Vec<(&S, SymMatch)> = vec![];
+  for sym in syms {
+    let m = sym.evaluate(clues);
+    if m.num_nonconforming_locs > max_nonconforming_locs {
+      // Ignore symmetries that are too far from describing this puzzle.
+      continue;
+    }
+    let mut add = true;
+    for (prev_sym, prev_match) in &answer {
+      if *prev_sym > sym && (!m.is_complete() || prev_match.is_complete() || sym.is_trivial()) {
+        add = false;
+        break;
       }
-      if add {
-        answer.push((*sym, m));
+    }
+    if add {
+      answer.push((sym, m));
+    }
+  }
+  answer.sort_by(|a, b| a.1.num_nonconforming_locs.cmp(&b.1.num_nonconforming_locs));
+  answer
+}
+
+/// A symmetry that maps numerals as well as locations.  By Gurth's theorem,
+/// when a puzzle with a unique solution has such a symmetry, so does its
+/// solution.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct DigitSym {
+  /// The symmetry whose `loc_mapping` moves the locations.
+  pub sym: Sym,
+  /// How the numerals move along with them.
+  pub nums: NumPermutation,
+}
+
+impl DigitSym {
+  /// Finds a symmetry of the given clues that maps numerals too, preferring
+  /// the larger symmetries.
+  pub fn find(clues: &Grid) -> Option<Self> {
+    SYMS
+      .iter()
+      .find_map(|&sym| sym.digit_mapping(clues).map(|nums| Self { sym, nums }))
+  }
+
+  /// Makes a DigitSym from a symmetry and the images of the numerals 1
+  /// through 9, as found in `Fact::SymmetricPlacement`.
+  pub fn from_mapping(sym: Sym, mapping: &[Num]) -> Option<Self> {
+    sym.loc_mapping()?;
+    let nums = NumPermutation::new(mapping.try_into().ok()?)?;
+    Some(Self { sym, nums })
+  }
+
+  /// Returns the images of the numerals 1 through 9.
+  pub fn mapping(&self) -> Vec<Num> {
+    Num::all().map(|num| self.nums.apply(num)).collect()
+  }
+
+  /// Maps the given assignment.
+  pub fn apply(&self, asgmt: Asgmt) -> Asgmt {
+    Asgmt::new(
+      self.nums.apply(asgmt.num),
+      self.sym.loc_mapping().unwrap()(asgmt.loc),
+    )
+  }
+
+  /// Tells whether a solution with this symmetry can't have the given
+  /// assignment, when only the given assignments are possible.  It can't if
+  /// any of the assignment's images is impossible, or if an image puts the
+  /// same numeral elsewhere in one of the assignment's units.
+  pub fn excludes(&self, asgmt: Asgmt, possible: &AsgmtSet) -> bool {
+    let mut image = self.apply(asgmt);
+    while image.loc != asgmt.loc {
+      if !possible.contains(image)
+        || (image.num == asgmt.num && asgmt.loc.peers().contains(image.loc))
+      {
+        return true;
       }
+      image = self.apply(image);
     }
-    answer.sort_by(|a, b| a.1.num_nonconforming_locs.cmp(&b.1.num_nonconforming_locs));
-    answer
+    // Back at the assignment's own location, the numeral must be too.
+    image.num != asgmt.num
   }
 }
 
-impl SymMatch {
+impl SymMatch<'_> {
   pub fn is_complete(&self) -> bool {
     self.num_nonconforming_locs == 0
   }
@@ -516,6 +741,52 @@ mod tests {
     }
   }
 
+  #[test]
+  fn digit_mapping() {
+    let clues = Grid::from_str(
+      "26.....79.1...3..4...8..5.......678...........783.......9..7...1..6...4.58.....32",
+    )
+    .unwrap();
+    let digit_sym = DigitSym::find(&clues).unwrap();
+    assert_eq!(Sym::Rotation180, digit_sym.sym);
+    assert_eq!(N4, digit_sym.nums.apply(N1));
+    assert_eq!(N2, digit_sym.nums.apply(N2));
+    assert_eq!(None, Sym::Rotation90.digit_mapping(&clues));
+    assert_eq!(None, Sym::Mirror(Axis::X).digit_mapping(&clues));
+    assert_eq!(
+      Some(digit_sym),
+      DigitSym::from_mapping(Sym::Rotation180, &digit_sym.mapping())
+    );
+    let mut broken = clues;
+    broken[L11] = Some(N3);
+    assert_eq!(None, DigitSym::find(&broken));
+
+    let clues = Grid::from_str(
+      "...63..5.7..9.1.......2.....1..6..894.82.96.726..8..1.....9.......1.2..4.3..58...",
+    )
+    .unwrap();
+    let digit_sym = DigitSym::find(&clues).unwrap();
+    assert_eq!(Sym::Rotation90, digit_sym.sym);
+    for asgmt in clues.iter() {
+      assert_eq!(
+        Some(digit_sym.apply(asgmt).num),
+        clues[digit_sym.apply(asgmt).loc]
+      );
+    }
+
+    // The center can only hold the numeral that maps to itself.
+    let possible = AsgmtSet::all();
+    assert!(DigitSym::find(&Grid::new()).is_none());
+    let rotation =
+      DigitSym::from_mapping(Sym::Rotation180, &[N9, N8, N7, N6, N5, N4, N3, N2, N1]).unwrap();
+    assert!(!rotation.excludes(Asgmt::new(N5, L55), &possible));
+    assert!(rotation.excludes(Asgmt::new(N1, L55), &possible));
+    // And the middle row can't hold it anywhere else, since its image would
+    // be in the same row.
+    assert!(rotation.excludes(Asgmt::new(N5, L51), &possible));
+    assert!(!rotation.excludes(Asgmt::new(N1, L51), &possible));
+  }
+
   #[test]
   fn best_matches() {
     let clues = Grid::from_str(
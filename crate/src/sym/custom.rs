@@ -0,0 +1,233 @@
+//! Defines symmetries built from arbitrary location permutations.
+
+use std::{
+  cmp::Ordering,
+  hash::{Hash, Hasher},
+};
+
+use crate::{core::*, permute::LocPermutation};
+
+use super::{best_matches_among, SymMatch, Symmetry};
+
+/// A symmetry of the Sudoku grid that isn't one of the built-in `Sym`s, such as
+/// a translation or a glide reflection.  It's the group of location
+/// permutations generated by a set of `LocPermutation`s, and its orbits are
+/// computed when it's made.
+///
+/// Two custom symmetries are equal when they have the same orbits, whatever
+/// generators they were made from; and one is greater than another when each
+/// of the other's orbits lies within one of its own.
+#[derive(Clone, Debug)]
+pub struct CustomSym {
+  generators: Vec<LocPermutation>,
+  orbits: Vec<Vec<Loc>>,
+}
+
+impl CustomSym {
+  /// Makes the symmetry generated by the given location permutations.  With no
+  /// generators it's the trivial symmetry.
+  pub fn new(generators: Vec<LocPermutation>) -> Self {
+    let mut orbits = vec![];
+    let mut remaining = LocSet::all();
+    while let Some(loc) = remaining.smallest_item() {
+      let mut in_orbit = loc.as_set();
+      let mut pending = vec![loc];
+      while let Some(loc) = pending.pop() {
+        for perm in &generators {
+          let image = perm.apply(loc);
+          if !in_orbit.contains(image) {
+            in_orbit.insert(image);
+            pending.push(image);
+          }
+        }
+      }
+      orbits.push(in_orbit.iter().collect());
+      remaining -= in_orbit;
+    }
+    Self { generators, orbits }
+  }
+
+  /// The location permutations this symmetry was made from.
+  pub fn generators(&self) -> &[LocPermutation] {
+    &self.generators
+  }
+
+  /// Finds the symmetries among the given ones that best match the given
+  /// puzzle grid, disqualifying any symmetries that match with more than the
+  /// given number of nonconforming locations, and any whose match is no
+  /// better than a greater symmetry's.
+  pub fn best_matches<'a>(
+    syms: &'a [CustomSym],
+    clues: &Grid,
+    max_nonconforming_locs: usize,
+  ) -> Vec<(&'a CustomSym, SymMatch<'a>)> {
+    // Greater symmetries have fewer orbits, so this puts each symmetry ahead
+    // of the ones it's greater than.
+    let mut sorted: Vec<&CustomSym> = syms.iter().collect();
+    sorted.sort_by_key(|sym| sym.orbits.len());
+    best_matches_among(sorted.into_iter(), clues, max_nonconforming_locs)
+  }
+
+  /// Returns an array that maps every location to the orbit it belongs to, as
+  /// a set.
+  fn loc_orbits(&self) -> [LocSet; 81] {
+    let mut answer = [LocSet::new(); 81];
+    for orbit in &self.orbits {
+      let set: LocSet = orbit.iter().copied().collect();
+      for loc in orbit {
+        answer[loc.index()] = set;
+      }
+    }
+    answer
+  }
+}
+
+impl Symmetry for CustomSym {
+  fn orbits(&self) -> Vec<&[Loc]> {
+    self.orbits.iter().map(|orbit| orbit.as_slice()).collect()
+  }
+}
+
+impl PartialEq for CustomSym {
+  fn eq(&self, other: &Self) -> bool {
+    self.orbits == other.orbits
+  }
+}
+
+impl Eq for CustomSym {}
+
+impl Hash for CustomSym {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.orbits.hash(state);
+  }
+}
+
+impl PartialOrd for CustomSym {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    let (mine, theirs) = (self.loc_orbits(), other.loc_orbits());
+    let mut answer = Ordering::Equal;
+    for loc in Loc::all() {
+      match mine[loc.index()].partial_cmp(&theirs[loc.index()])? {
+        Ordering::Equal => {}
+        order if answer == Ordering::Equal => answer = order,
+        order if answer != order => return None,
+        _ => {}
+      }
+    }
+    Some(answer)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    gen::{gen_simple_puzzle, gen_solved_grid, improve_puzzle},
+    permute::{BandPermutation, BlkLinePermutation, GroupElement},
+    random::new_random,
+    solve::ledger::Ledger,
+    sym::Sym,
+  };
+
+  /// Shifts every location down by one band.
+  fn band_shift() -> LocPermutation {
+    LocPermutation {
+      row_bands: BandPermutation::new([BAND2, BAND3, BAND1]).unwrap(),
+      ..LocPermutation::identity()
+    }
+  }
+
+  /// Reverses the order of the columns.
+  fn mirror_y() -> LocPermutation {
+    LocPermutation {
+      col_bands: BandPermutation::new([BAND3, BAND2, BAND1]).unwrap(),
+      cols_in_bands: [BlkLinePermutation::new([BL3, BL2, BL1]).unwrap(); 3],
+      ..LocPermutation::identity()
+    }
+  }
+
+  fn orbit_sizes(sym: &CustomSym) -> Vec<usize> {
+    sym.orbits.iter().map(|orbit| orbit.len()).collect()
+  }
+
+  #[test]
+  fn matches_built_in_syms() {
+    let mirror_x = LocPermutation {
+      transpose: false,
+      row_bands: mirror_y().col_bands,
+      col_bands: BandPermutation::identity(),
+      rows_in_bands: mirror_y().cols_in_bands,
+      cols_in_bands: [BlkLinePermutation::identity(); 3],
+    };
+    let rot180 = mirror_x.composed_with(&mirror_y());
+    let custom = CustomSym::new(vec![rot180]);
+    assert_eq!(custom.orbits(), Sym::Rotation180.orbits());
+    assert!(!custom.is_trivial());
+
+    let custom = CustomSym::new(vec![mirror_x, mirror_y()]);
+    assert_eq!(custom.orbits(), Sym::DoubleMirror.orbits());
+
+    let trivial = CustomSym::new(vec![]);
+    assert_eq!(trivial.orbits(), Sym::None.orbits());
+    assert!(trivial.is_trivial());
+  }
+
+  #[test]
+  fn orbits_and_order() {
+    let translation = CustomSym::new(vec![band_shift()]);
+    assert_eq!(orbit_sizes(&translation), vec![3; 27]);
+    let glide = CustomSym::new(vec![band_shift().composed_with(&mirror_y())]);
+    assert_eq!(glide.orbits().len(), 9 / 3 + 72 / 6);
+    let mirror = CustomSym::new(vec![mirror_y()]);
+    let both = CustomSym::new(vec![band_shift(), mirror_y()]);
+    let trivial = CustomSym::new(vec![]);
+
+    assert_eq!(glide, both);
+    assert_ne!(glide, translation);
+    assert!(both > translation);
+    assert!(both > mirror);
+    assert!(translation > trivial);
+    assert_eq!(translation.partial_cmp(&mirror), None);
+    assert_eq!(
+      translation.partial_cmp(&CustomSym::new(vec![band_shift().inverse()])),
+      Some(Ordering::Equal)
+    );
+  }
+
+  #[test]
+  fn generates_and_evaluates_puzzles() {
+    let mut random = new_random("custom syms");
+    let solution = gen_solved_grid(Variant::Standard, &mut random);
+    let translation = CustomSym::new(vec![band_shift()]);
+    let glide = CustomSym::new(vec![band_shift().composed_with(&mirror_y())]);
+    let trivial = CustomSym::new(vec![]);
+    for sym in [&translation, &glide] {
+      let clues = gen_simple_puzzle(&solution, Variant::Standard, sym, &mut random);
+      assert!(sym.evaluate(&clues).is_complete());
+      let mut ledger = Ledger::new(&clues).unwrap();
+      ledger.apply_implications().unwrap();
+      assert!(ledger.unset().is_empty(), "{}", clues);
+
+      let summary = improve_puzzle(&clues, Variant::Standard, sym, &mut random, 1, 0);
+      assert_eq!(1, summary.solutions.len());
+      assert!(summary.clues.len() < clues.len());
+      assert!(sym.evaluate(&summary.clues).is_complete());
+    }
+
+    let clues = improve_puzzle(
+      &gen_simple_puzzle(&solution, Variant::Standard, &translation, &mut random),
+      Variant::Standard,
+      &translation,
+      &mut random,
+      1,
+      0,
+    )
+    .clues;
+    let syms = [trivial.clone(), glide.clone(), translation.clone()];
+    let matches = CustomSym::best_matches(&syms, &clues, 0);
+    assert_eq!(1, matches.len());
+    assert_eq!(&translation, matches[0].0);
+    let matches = CustomSym::best_matches(&syms, &clues, 81);
+    assert!(matches.iter().all(|(sym, _)| **sym != trivial));
+  }
+}
@@ -5,6 +5,8 @@ import {LitElement, css, html} from 'lit';
 import {customElement, query, state} from 'lit/decorators.js';
 import {Game} from '../game/game';
 import {Grid} from '../game/grid';
+import {Loc} from '../game/loc';
+import * as wasm from '../wasm';
 import {log} from '../system/analytics';
 import {
   lookUpPuzzleByClues,
@@ -29,6 +31,16 @@ import {cssPixels} from './types';
 
 const CLUES_PARAM = 'clues';
 
+/** Progress someone made on a pasted puzzle before pasting it. */
+interface ImportedProgress {
+  /** The clues of the puzzle this progress belongs to, as a flat string. */
+  readonly clues: string;
+  /** The numerals placed in locations without clues. */
+  readonly placed: Grid;
+  /** The candidates remaining in the other locations, if the text had any. */
+  readonly candidates: ReadonlyMap<Loc, ReadonlySet<number>>;
+}
+
 @customElement('enter-puzzle-page')
 export class EnterPuzzlePage extends LitElement {
   static override styles = [
@@ -76,6 +88,13 @@ export class EnterPuzzlePage extends LitElement {
           </div>
         `
       : ''}
+      ${this.pastedProgress ?
+        html`<p>
+          ${this.inDb ?
+            'This puzzle is already saved, so the progress pasted with it was discarded.'
+          : 'The progress pasted with this puzzle will be carried over when you save it.'}
+        </p>`
+      : ''}
       <h1>Luke-doku</h1>
       <h2>Enter a Puzzle</h2>
     `;
@@ -84,6 +103,7 @@ export class EnterPuzzlePage extends LitElement {
   @state() private clues = new Grid();
   @state() private testResult?: PuzzleTestedMessage;
   @state() private puzzleRecord?: PuzzleRecord;
+  @state() private importedProgress?: ImportedProgress;
   @query('#source') private sourceInput?: HTMLInputElement;
 
   private get valid(): boolean {
@@ -94,6 +114,12 @@ export class EnterPuzzlePage extends LitElement {
     return !!this.puzzleRecord;
   }
 
+  /** The pasted progress, as long as the clues haven't changed since. */
+  private get pastedProgress(): ImportedProgress | undefined {
+    const progress = this.importedProgress;
+    return progress?.clues === this.clues.toFlatString() ? progress : undefined;
+  }
+
   override connectedCallback(): void {
     super.connectedCallback();
     addTypeSafeListener(
@@ -101,6 +127,7 @@ export class EnterPuzzlePage extends LitElement {
       'hash-state-changed',
       this.handleHashStateChanged,
     );
+    window.addEventListener('paste', this.handlePaste);
     this.updateToHashState(getHashState());
     this.updateComplete.then(() => {
       this.sourceInput?.focus();
@@ -115,12 +142,38 @@ export class EnterPuzzlePage extends LitElement {
       'hash-state-changed',
       this.handleHashStateChanged,
     );
+    window.removeEventListener('paste', this.handlePaste);
   }
 
   private handleHashStateChanged = async (event: CustomEvent<HashState>) => {
     this.updateToHashState(event.detail);
   };
 
+  /**
+   * Replaces the clues with a pasted puzzle, in any of the formats the wasm
+   * importer understands, and holds on to any progress pasted with it.
+   * Pasting into the source field works as usual.
+   */
+  private handlePaste = (event: ClipboardEvent) => {
+    if (event.composedPath()[0] === this.sourceInput) return;
+    const text = event.clipboardData?.getData('text/plain');
+    if (!text) return;
+    let imported: wasm.Imported;
+    try {
+      imported = wasm.importPuzzle(text);
+    } catch (e) {
+      log('pasted text is not a puzzle', e);
+      return;
+    }
+    event.preventDefault();
+    const givens = imported.givens;
+    this.clues = new Grid(givens);
+    givens.free();
+    this.importedProgress = readProgress(imported, this.clues);
+    imported.free();
+    this.updateUrl();
+  };
+
   private updateToHashState(hashState: HashState) {
     const clues = hashState.params.get(CLUES_PARAM);
     if (clues && clues !== this.clues.toFlatString()) {
@@ -157,9 +210,26 @@ export class EnterPuzzlePage extends LitElement {
       this.testResult!.solutions!,
       source,
     );
+    await this.applyPastedProgress(game);
     await navigateToPuzzle(game.sudoku);
   }
 
+  /** Carries the progress pasted with a puzzle over into its new game. */
+  private async applyPastedProgress(game: Game) {
+    const progress = this.pastedProgress;
+    if (!progress) return;
+    for (const loc of Loc.ALL) {
+      const num = progress.placed.get(loc);
+      const nums = progress.candidates.get(loc);
+      if (num) {
+        game.setNum(loc, num);
+      } else if (nums) {
+        game.setNums(loc, nums);
+      }
+    }
+    await game.save();
+  }
+
   private async playPuzzle() {
     if (!this.inDb) {
       return;
@@ -174,3 +244,31 @@ declare global {
     'enter-puzzle-page': EnterPuzzlePage;
   }
 }
+
+/**
+ * Copies the progress out of an imported puzzle, or returns undefined if there
+ * isn't any.
+ */
+function readProgress(
+  imported: wasm.Imported,
+  clues: Grid,
+): ImportedProgress | undefined {
+  const wasmPlaced = imported.placed;
+  const placed = new Grid(wasmPlaced);
+  wasmPlaced.free();
+  const candidates = new Map<Loc, ReadonlySet<number>>();
+  if (imported.hasEliminations()) {
+    const wasmCandidates = imported.candidates();
+    for (const loc of Loc.ALL) {
+      if (clues.get(loc) || placed.get(loc)) continue;
+      const nums = new Set<number>();
+      for (let num = 1; num <= 9; ++num) {
+        if (wasmCandidates.hasCandidate(num, loc.index)) nums.add(num);
+      }
+      candidates.set(loc, nums);
+    }
+    wasmCandidates.free();
+  }
+  if (!placed.getAssignedCount() && !candidates.size) return undefined;
+  return {clues: clues.toFlatString(), placed, candidates};
+}
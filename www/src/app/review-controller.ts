@@ -882,6 +882,15 @@ export class ReviewController implements ReactiveController {
             base.string_ends.includes(locIndex) ||
             unitContains(base.block, this.selectedLoc!)
           );
+        case 'RelationClaim':
+          return (
+            base.locs.includes(locIndex) ||
+            unitContains(base.unit, this.selectedLoc!)
+          );
+        case 'RelationCover':
+          return base.loc === locIndex || base.locs.includes(locIndex);
+        case 'SymmetricPlacement':
+          return base.locs.includes(locIndex);
         default:
           ensureExhaustiveSwitch(base);
       }
@@ -897,7 +906,10 @@ export class ReviewController implements ReactiveController {
         base.type === 'Fish' ||
         base.type === 'EmptyRectangle' ||
         base.type === 'Skyscraper' ||
-        base.type === 'TwoStringKite'
+        base.type === 'TwoStringKite' ||
+        base.type === 'RelationClaim' ||
+        base.type === 'RelationCover' ||
+        base.type === 'SymmetricPlacement'
       ) {
         eliminations.push(fact);
       } else {
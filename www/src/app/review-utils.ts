@@ -1,3 +1,4 @@
+import type {EliminationConstraint} from '../facts/EliminationConstraint';
 import type {Fact} from '../facts/Fact';
 import {CommandTag, RecordedCommand} from '../game/command';
 
@@ -80,8 +81,8 @@ export function computeInterestingIndices(
 
 export function getEliminationConstraints(
   elims: Fact[],
-): {loc: number; num: number}[][] {
-  const result = [];
+): EliminationConstraint[] {
+  const result: EliminationConstraint[] = [];
   for (const elim of elims) {
     if (
       elim.type === 'Implication' &&
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Hole } from "./Hole";
+import type { LocSet } from "./LocSet";
+
+/**
+ * Describes the ambiguity of a puzzle with more than one solution.
+ */
+export type Ambiguity = { 
+/**
+ * The locations whose numerals are the same in every solution.
+ */
+backbone: LocSet, 
+/**
+ * The locations whose numerals differ among the solutions, grouped into
+ * holes that can be filled independently of each other.  Every combination
+ * of the holes' alternatives is a solution.
+ */
+holes: Array<Hole>, };
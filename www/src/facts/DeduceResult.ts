@@ -1,4 +1,7 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { Fact } from "./Fact";
 
+/**
+ * What `deduceFacts` and `deduceQuickFact` return.
+ */
 export type DeduceResult = { facts: Array<Fact>, timedOut: boolean, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Identifies one of the 2 main diagonals of a Sudoku grid, which are
+ * units only in variants like X-Sudoku.
+ *
+ * D1 runs from the top left to the bottom right; D2 from the top right to
+ * the bottom left.
+ */
+export type Diag = number;
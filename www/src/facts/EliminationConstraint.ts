@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { IndexedAsgmt } from "./IndexedAsgmt";
+
+/**
+ * A set of assignments that have been disproved together: they can't all
+ * hold, so once all but one of them do, the last one is eliminated.  A
+ * single assignment is simply eliminated.
+ */
+export type EliminationConstraint = Array<IndexedAsgmt>;
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LocSet } from "./LocSet";
+import type { Num } from "./Num";
+
+/**
+ * A group of locations whose numerals vary together among a puzzle's
+ * solutions.
+ */
+export type Hole = { 
+/**
+ * The locations in the hole.
+ */
+locs: LocSet, 
+/**
+ * The distinct ways the solutions fill the hole.  Each alternative lists the
+ * numerals of the hole's locations, in location order.
+ */
+alternatives: Array<Array<Num>>, };
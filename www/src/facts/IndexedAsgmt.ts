@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An assignment as the TypeScript side passes it in: a location index, 0
+ * through 80, and a numeral, 1 through 9.  Neither is checked until it's
+ * converted to an `Asgmt`.
+ */
+export type IndexedAsgmt = { loc: number, num: number, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JigsawUnit } from "./JigsawUnit";
+import type { Loc } from "./Loc";
+import type { LocSet } from "./LocSet";
+import type { Num } from "./Num";
+import type { NumSet } from "./NumSet";
+
+/**
+ * A fact that can be deduced from a Jigsaw Sudoku grid.  These correspond to
+ * the `Fact` kinds of the same names, and serialize the same way.
+ */
+export type JigsawFact = { "type": "SingleLoc", num: Num, unit: JigsawUnit, loc: Loc, } | { "type": "SingleNum", loc: Loc, num: Num, } | { "type": "Overlap", num: Num, unit: JigsawUnit, cross_unit: JigsawUnit, } | { "type": "Subset", nums: NumSet, unit: JigsawUnit, locs: LocSet, is_naked: boolean, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Col } from "./Col";
+import type { Region } from "./Region";
+import type { Row } from "./Row";
+
+/**
+ * One of a Jigsaw Sudoku's units.
+ */
+export type JigsawUnit = { "type": "Row", "id": Row } | { "type": "Col", "id": Col } | { "type": "Region", "id": Region };
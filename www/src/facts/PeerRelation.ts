@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A way that an assignment rules out assignments at other locations.
+ */
+export type PeerRelation = "Unit" | "Knight" | "King" | "NonConsecutive";
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Identifies one of the 9 regions of a Jigsaw Sudoku layout.
+ *
+ * In the standard layout, the regions are the blocks, numbered the same
+ * way.
+ */
+export type Region = number;
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Axis } from "./Axis";
+import type { Diagonal } from "./Diagonal";
+
+/**
+ * The symmetries of the Sudoku grid.  These are typically used to generate and
+ * describe the arrangement of a puzzle's clues within the grid.
+ *
+ * Each symmetry divides the Sudoku grid into "orbits," collections of cells
+ * that are equivalent to each other under the symmetry.
+ *
+ * `Sym` implements partial ordering according to whether one symmetry implies
+ * the other, meaning that all of the orbits of the "larger" one are supersets
+ * of the orbits of the "smaller" one.
+ */
+export type Sym = "Rotation180" | "Rotation90" | { "Mirror": Axis } | "DoubleMirror" | { "Diagonal": Diagonal } | "DoubleDiagonal" | "FullyReflective" | { "Blockwise": Diagonal } | "None";
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Loc } from "./Loc";
+
+/**
+ * How well a puzzle matches a symmetry, in terms of which locations have
+ * clues.
+ */
+export type SymMatch = { 
+/**
+ * The orbits that are full.
+ */
+full_orbits: Array<Array<Loc>>, 
+/**
+ * The number of locations that don't conform to the symmetry.
+ */
+num_nonconforming_locs: number, 
+/**
+ * The orbits that are only partially full.
+ */
+partial_orbits: Array<Array<Loc>>, };
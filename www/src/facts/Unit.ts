@@ -1,9 +1,10 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { Blk } from "./Blk";
 import type { Col } from "./Col";
+import type { Diag } from "./Diag";
 import type { Row } from "./Row";
 
 /**
- * One of a row, column, or block.
+ * One of a row, column, or block, or in some variants a diagonal.
  */
-export type Unit = { "type": "Blk", "id": Blk } | { "type": "Row", "id": Row } | { "type": "Col", "id": Col };
+export type Unit = { "type": "Blk", "id": Blk } | { "type": "Row", "id": Row } | { "type": "Col", "id": Col } | { "type": "Diag", "id": Diag };
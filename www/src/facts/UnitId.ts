@@ -1,10 +1,12 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
 /**
- * Identifies one of the 27 "units" (row/col/block) of a Sudoku grid.
+ * Identifies one of the "units" of a Sudoku grid: the 27 standard ones
+ * (row/col/block), followed by the 2 diagonals.
  *
  * The units of a Sudoku (not to be confused with Rust's unit type) are
  * all the subregions of the grid that must contain all 9 numerals in a
- * valid solution.
+ * valid solution.  The diagonals are units only in variants that say so;
+ * see `Variant`.
  */
 export type UnitId = number;
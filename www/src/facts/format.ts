@@ -4,6 +4,8 @@ import type {Fact} from './Fact';
 import type {LocSet} from './LocSet';
 import type {Num} from './Num';
 import type {NumSet} from './NumSet';
+import type {PeerRelation} from './PeerRelation';
+import type {Sym} from './Sym';
 import type {Unit} from './Unit';
 import {type Disproof, isDisproof} from './disproof';
 import {nub} from './utils';
@@ -25,24 +27,52 @@ export function formatNums(nums: NumSet): string {
   return `[${nums.map(formatNum).join(', ')}]`;
 }
 
-/** Formats a unit (Block, Row, Column, 1-based). Example: "R1", "C2", "B3" */
+/** Formats a unit (Block, Row, Column, Diagonal, 1-based). Example: "R1", "C2", "B3", "D1" */
 export function formatUnitShorthand(unit: Unit): string {
   const typeStr =
     unit.type === 'Blk' ? 'B'
     : unit.type === 'Row' ? 'R'
+    : unit.type === 'Diag' ? 'D'
     : 'C';
   return `${typeStr}${unit.id + 1}`;
 }
 
-/** Formats a unit (Block, Row, Column, 1-based). Example: "Row 1" */
+/** Formats a unit (Block, Row, Column, Diagonal, 1-based). Example: "Row 1" */
 export function formatUnit(unit: Unit): string {
   const typeStr =
     unit.type === 'Blk' ? 'Block'
     : unit.type === 'Row' ? 'Row'
+    : unit.type === 'Diag' ? 'Diagonal'
     : 'Column';
   return `${typeStr} ${unit.id + 1}`;
 }
 
+/** Formats a peer relation. Example: "Knight" */
+export function formatRelation(relation: PeerRelation): string {
+  return relation === 'NonConsecutive' ? 'Non-Consecutive' : relation;
+}
+
+/** Formats a symmetry. Example: "180° rotation" */
+export function formatSym(sym: Sym): string {
+  if (sym === 'Rotation180') return '180° rotation';
+  if (sym === 'Rotation90') return '90° rotation';
+  if (typeof sym === 'string') return sym;
+  if ('Mirror' in sym) return `${sym.Mirror} mirror`;
+  if ('Diagonal' in sym) return `${sym.Diagonal} diagonal`;
+  return `${sym.Blockwise} blockwise translation`;
+}
+
+/**
+ * Formats a mapping of numerals, given as the images of 1 through 9, listing
+ * just the ones that move. Example: "1→4, 4→1"
+ */
+export function formatNumMapping(mapping: readonly Num[]): string {
+  return mapping
+    .map((image, i) => (image === i + 1 ? '' : `${i + 1}→${image}`))
+    .filter(s => s)
+    .join(', ');
+}
+
 /** Returns the human-readable name of a Fish pattern based on base size and fins. */
 export function getFishName(size: number, isFinned: boolean): string {
   let baseName = 'Fish';
@@ -126,6 +156,15 @@ export function shorthandFact(fact: Fact): string {
     case 'TwoStringKite':
       return `${formatNum(fact.num)} Kite: ${formatUnitShorthand(fact.block)} (${formatUnitShorthand(fact.row)}, ${formatUnitShorthand(fact.col)}) ➔ ${formatLocs(fact.elimination_locs)}`;
 
+    case 'RelationClaim':
+      return `${formatNum(fact.num)} ∈ ${formatUnitShorthand(fact.unit)} ${formatRelation(fact.relation)} ➔ ${formatLocs(fact.locs)}`;
+
+    case 'RelationCover':
+      return `${formatNums(fact.nums)} @ ${GameLoc.of(fact.loc).toString()} ${formatRelation(fact.relation)} ➔ ${formatNum(fact.num)} ∉ ${formatLocs(fact.locs)}`;
+
+    case 'SymmetricPlacement':
+      return `Gurth ${formatSym(fact.sym)} ➔ ${formatNum(fact.num)} ∉ ${formatLocs(fact.locs)}`;
+
     default:
       ensureExhaustiveSwitch(fact);
   }
@@ -193,6 +232,15 @@ export function describeFact(fact: Fact): string {
     case 'TwoStringKite':
       return `${shorthand}: 2-String Kite for ${formatNum(fact.num)} in ${formatUnit(fact.block)} connecting ${formatUnit(fact.row)} and ${formatUnit(fact.col)} with ends ${formatLocs(fact.string_ends)} eliminates ${formatNum(fact.num)} at ${formatLocs(fact.elimination_locs)}`;
 
+    case 'RelationClaim':
+      return `${shorthand}: Wherever ${formatNum(fact.num)} goes in ${formatUnit(fact.unit)}, it is a ${formatRelation(fact.relation)} peer of ${formatLocs(fact.locs)}, which rules out numbers there`;
+
+    case 'RelationCover':
+      return `${shorthand}: Every number left at ${GameLoc.of(fact.loc).toString()} (${formatNums(fact.nums)}) rules out ${formatNum(fact.num)} at its ${formatRelation(fact.relation)} peers ${formatLocs(fact.locs)}`;
+
+    case 'SymmetricPlacement':
+      return `${shorthand}: The clues are symmetric under ${formatSym(fact.sym)} with numbers mapped ${formatNumMapping(fact.mapping)}, so the solution is too, which rules out ${formatNum(fact.num)} at ${formatLocs(fact.locs)}`;
+
     default:
       ensureExhaustiveSwitch(fact);
   }
@@ -75,6 +75,8 @@ export function unitContains(unit: Unit, loc: GameLoc): boolean {
       const locBlk = blkRow * 3 + blkCol;
       return locBlk === unit.id;
     }
+    case 'Diag':
+      return unit.id === 0 ? loc.row === loc.col : loc.row + loc.col === 8;
     default:
       ensureExhaustiveSwitch(unit);
   }
@@ -134,6 +136,11 @@ function getFactRank(fact: Fact): number {
     case 'Skyscraper':
     case 'TwoStringKite':
       return 160;
+    case 'RelationClaim':
+    case 'RelationCover':
+      return 145;
+    case 'SymmetricPlacement':
+      return 170;
     default:
       ensureExhaustiveSwitch(base);
   }
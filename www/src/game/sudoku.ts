@@ -10,7 +10,7 @@ import type {
 } from '../worker/worker-types';
 import {Grid, ReadonlyGrid} from './grid';
 import {Loc} from './loc';
-import {DateString, GridString} from './types';
+import {DateString, dateString, GridString} from './types';
 
 /**
  * Describes a Sudoku puzzle.
@@ -129,30 +129,21 @@ export class PuzzleId {
   }
 
   static parse(s: string): PuzzleId | null {
-    const parts = s.split(':');
-    if (parts.length < 2 || parts.length > 3) return null;
+    let id: wasm.PuzzleId;
     try {
-      wasm.LogicalDate.fromString(parts[0]).free();
+      id = wasm.PuzzleId.fromString(s);
     } catch {
       return null;
     }
-    const date = parts[0] as DateString;
-    const counter = Number(parts[1]);
-    if (isNaN(counter) || counter < 1 || !Number.isInteger(counter)) {
-      return null;
-    }
-    let generatorVersion = 0;
-    if (parts.length === 3) {
-      generatorVersion = Number(parts[2]);
-      if (
-        isNaN(generatorVersion) ||
-        generatorVersion < 0 ||
-        !Number.isInteger(generatorVersion)
-      ) {
-        return null;
-      }
-    }
-    return new PuzzleId(date, counter, generatorVersion);
+    const date = id.date;
+    const answer = new PuzzleId(
+      dateString(date),
+      id.counter,
+      id.generatorVersion,
+    );
+    date.free();
+    id.free();
+    return answer;
   }
 }
 
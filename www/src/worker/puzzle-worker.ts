@@ -1,4 +1,5 @@
-import type {Disproof} from '../facts/disproof';
+import {isDisproof} from '../facts/disproof';
+import type {Fact} from '../facts/Fact';
 import {formatDisproofDescription, shorthandFact} from '../facts/format';
 import {getTotalAntecedents, nub} from '../facts/utils';
 import {ensureExhaustiveSwitch} from '../game/utils';
@@ -197,12 +198,12 @@ function deduceFacts(m: DeduceFactsMessage): FromWorkerMessage {
     return toErrorCaught(m, 'deduceFacts', new Error('Invalid grid'));
   }
   const startTimeMs = performance.now();
-  let facts: any[] = [];
+  let facts: Fact[] = [];
   let timedOut = false;
   try {
     const result = wasm.deduceFacts(grid, m.eliminations, m.maxTimeMs);
-    facts = result?.facts || [];
-    timedOut = result?.timedOut || false;
+    facts = result.facts;
+    timedOut = result.timedOut;
   } catch (e: unknown) {
     return toErrorCaught(m, 'deduceFacts', e);
   } finally {
@@ -224,7 +225,7 @@ function deduceQuickFact(m: DeduceQuickFactMessage): FromWorkerMessage {
     return toErrorCaught(m, 'deduceQuickFact', new Error('Invalid grid'));
   }
   const startTimeMs = performance.now();
-  let facts: any[] = [];
+  let facts: Fact[] = [];
   let timedOut = false;
   try {
     const result = wasm.deduceQuickFact(
@@ -234,8 +235,8 @@ function deduceQuickFact(m: DeduceQuickFactMessage): FromWorkerMessage {
       m.eliminations,
       m.maxTimeMs ?? 200,
     );
-    facts = result?.facts || [];
-    timedOut = result?.timedOut || false;
+    facts = result.facts;
+    timedOut = result.timedOut;
   } catch (e: unknown) {
     return toErrorCaught(m, 'deduceQuickFact', e);
   } finally {
@@ -339,7 +340,7 @@ function disproveErroneousAssignment(
         }
       }
     }
-    const disproofJsonStr = wasm.disproveErroneousAssignment(
+    const disproof = wasm.disproveErroneousAssignment(
       grid,
       m.target,
       solutions,
@@ -350,10 +351,9 @@ function disproveErroneousAssignment(
     solutions = undefined;
 
     let disproofMetadata: DisproofMetadata | undefined = undefined;
-    if (disproofJsonStr) {
-      const disproof = JSON.parse(disproofJsonStr) as any;
-      if (disproof.error) {
-        throw new Error(disproof.error);
+    if (disproof) {
+      if (!isDisproof(disproof)) {
+        throw new Error(`Not a disproof: ${shorthandFact(disproof)}`);
       }
       disproofMetadata = {
         type: 'DisproofMetadata',
@@ -363,7 +363,7 @@ function disproveErroneousAssignment(
         rootLoc: disproof.antecedents[0].loc,
         rootNum: disproof.antecedents[0].num,
         errorFact: nub(disproof),
-        json: m.includeJson ? disproofJsonStr : '',
+        json: m.includeJson ? JSON.stringify(disproof) : '',
         maxDepth: m.maxDepth ?? 1,
       };
     }
@@ -1,7 +1,12 @@
+import type {EliminationConstraint} from '../facts/EliminationConstraint';
 import type {ErroneousAssignmentProductivity} from '../facts/ErroneousAssignmentProductivity';
 import type {Fact} from '../facts/Fact';
+import type {IndexedAsgmt} from '../facts/IndexedAsgmt';
+import type {SymMatch} from '../facts/SymMatch';
 import * as wasm from '../wasm';
 
+export type {EliminationConstraint};
+
 export enum ToWorkerMessageType {
   GENERATE_PUZZLE = 'GENERATE_PUZZLE',
   EVALUATE_PUZZLE = 'EVALUATE_PUZZLE',
@@ -52,8 +57,6 @@ export interface FindSymmetriesMessage extends ToWorkerMessageBase {
   readonly clues: string;
 }
 
-export type EliminationConstraint = {loc: number; num: number}[];
-
 export interface DeduceFactsMessage extends ToWorkerMessageBase {
   readonly type: ToWorkerMessageType.DEDUCE_FACTS;
 
@@ -70,7 +73,7 @@ export interface DeduceFactsMessage extends ToWorkerMessageBase {
 export interface DeduceQuickFactMessage extends ToWorkerMessageBase {
   readonly type: ToWorkerMessageType.DEDUCE_QUICK_FACT;
   readonly grid: string;
-  readonly target: {loc: number; num: number};
+  readonly target: IndexedAsgmt;
   readonly eliminations?: readonly EliminationConstraint[];
   readonly maxTimeMs?: number;
 }
@@ -85,7 +88,7 @@ export interface CalculateErroneousProductivityMessage extends ToWorkerMessageBa
 export interface DisproveErroneousAssignmentMessage extends ToWorkerMessageBase {
   readonly type: ToWorkerMessageType.DISPROVE_ERRONEOUS_ASSIGNMENT;
   readonly grid: string;
-  readonly target: {loc: number; num: number};
+  readonly target: IndexedAsgmt;
   readonly solutions?: readonly string[];
   readonly eliminations?: readonly EliminationConstraint[];
   readonly maxTimeMs?: number;
@@ -136,15 +139,6 @@ export interface ErrorCaughtMessage extends FromWorkerMessageBase {
   readonly stack?: string;
 }
 
-/**
- * This is the interface of the symmetry match objects returned from Rust.
- */
-export declare interface WasmSymMatch {
-  full_orbits: number[][];
-  num_nonconforming_locs: number;
-  partial_orbits: number[][];
-}
-
 export interface PuzzleGeneratedMessage extends FromWorkerMessageBase {
   readonly toWorkerMessage: GeneratePuzzleMessage;
   readonly type: FromWorkerMessageType.PUZZLE_GENERATED;
@@ -217,7 +211,7 @@ export interface SymmetriesFoundMessage extends FromWorkerMessageBase {
    * A non-empty array of symmetries that match or partially match the layout of
    * the puzzle's clues on the grid.
    */
-  readonly symmetryMatches: readonly [wasm.Sym, WasmSymMatch][];
+  readonly symmetryMatches: readonly [wasm.Sym, SymMatch][];
 
   /**
    * How long it took the worker to calculate the symmetries of the puzzle's